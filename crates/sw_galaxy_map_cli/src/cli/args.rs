@@ -1,6 +1,9 @@
 use clap::{ArgAction, Args, Parser, Subcommand};
 
-use sw_galaxy_map_core::domain::RouteListSort;
+use crate::cli::db_runtime::DEFAULT_MAX_RETRIES;
+use crate::ui::{ColorChoice, Style};
+use sw_galaxy_map_core::domain::{DedupePolicy, Metric, NearSort, PlanetColumn, RouteListSort};
+use sw_galaxy_map_core::routing::router::DEFAULT_MAX_WAYPOINTS;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -18,14 +21,64 @@ GUI startup is handled by the separate `sw_galaxy_map_gui` crate.
 "
 )]
 pub struct Cli {
-    /// Path to the SQLite database
+    /// Path to the SQLite database (defaults to $SW_GALAXY_DB, then the OS app data dir)
     #[arg(long)]
     pub db: Option<String>,
 
+    /// Colorize output: auto (only on a terminal), always, never.
+    ///
+    /// Precedence: an explicit `always`/`never` wins, then a non-empty `NO_COLOR`
+    /// environment variable disables colors, then terminal detection decides.
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Refuse to use a database whose schema is newer than this binary supports
+    /// (the default is a warning).
+    #[arg(long, global = true, action = ArgAction::SetTrue)]
+    pub strict: bool,
+
     #[command(subcommand)]
     pub cmd: Option<Commands>,
 }
 
+impl Cli {
+    /// Output style resolved from `--color`.
+    pub fn style(&self) -> Style {
+        Style::for_color(self.color)
+    }
+
+    /// True when the selected command emits JSON on stdout, so failures must too.
+    pub fn wants_json(&self) -> bool {
+        match &self.cmd {
+            Some(Commands::Route { cmd }) => match cmd {
+                RouteCmd::List { json, .. } => *json,
+                RouteCmd::Explain(args) => args.json,
+                RouteCmd::Show {
+                    obstacles_geojson, ..
+                } => *obstacles_geojson,
+                RouteCmd::Export { file, .. } => file.is_none(),
+                _ => false,
+            },
+            Some(Commands::Db { cmd }) => match cmd {
+                DbCommands::SkippedPlanets => true,
+                DbCommands::Status { json, .. } => *json,
+                DbCommands::History { json, .. } => *json,
+                DbCommands::Update { stats_json, .. } => *stats_json,
+                _ => false,
+            },
+            Some(Commands::Waypoint { cmd }) => match cmd {
+                WaypointCmd::Show { json, .. } => *json,
+                WaypointCmd::Export { file, .. } => file.is_none(),
+                _ => false,
+            },
+            Some(Commands::Search { json, .. }) => *json,
+            Some(Commands::Info { json, .. }) => *json,
+            Some(Commands::Distance { json, .. }) => *json,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Search planets by text and/or filters (uses FTS if available, otherwise LIKE)
@@ -49,6 +102,10 @@ pub enum Commands {
         #[arg(long)]
         status: Option<String>,
 
+        /// Filter by source reference (partial match on `ref`, case-insensitive)
+        #[arg(long = "by-ref", value_name = "REF")]
+        by_ref: Option<String>,
+
         /// Show only Canon planets
         #[arg(long, action = clap::ArgAction::SetTrue)]
         canon: bool,
@@ -61,14 +118,59 @@ pub enum Commands {
         #[arg(long, action = clap::ArgAction::SetTrue)]
         fuzzy: bool,
 
+        /// Fail instead of warning when FTS is enabled but its index is missing, so
+        /// the search would silently fall back to LIKE
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        strict_fts: bool,
+
+        /// Match query words as prefixes ("corell" finds Corellia)
+        #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "fuzzy")]
+        prefix: bool,
+
+        /// Append the relevance score column (lower is better; `-` when unranked)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        scores: bool,
+
+        /// Print results as a JSON array of `{ fid, planet, score }` objects
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        json: bool,
+
         #[arg(long, default_value_t = 20)]
         limit: i64,
+
+        /// Skip this many results first (page with --limit)
+        #[arg(long, default_value_t = 0)]
+        offset: i64,
     },
 
     /// Print all available information about a planet
     Info {
-        /// Planet name (or alias)
+        /// Planet name, alias, or FID (names are matched first)
         planet: String,
+
+        /// Also list the N nearest other planets (sanity-check the position)
+        #[arg(long = "show-nearest", value_name = "N")]
+        show_nearest: Option<usize>,
+
+        /// Max aliases to list (0 = all)
+        #[arg(long = "alias-limit", value_name = "N", default_value_t = 20)]
+        alias_limit: usize,
+
+        /// Also list persisted routes that start, end or pass near this planet
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        relations: bool,
+
+        /// Also list other planets in the same system (or sector when the system is unknown)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        siblings: bool,
+
+        /// Print the planet and its aliases as a single JSON object
+        #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["show_nearest", "relations", "siblings"])]
+        json: bool,
+
+        /// Open the planet's wiki page in the default browser after printing the info
+        #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "json")]
+        open: bool,
     },
 
     /// Find nearby planets within a radius (parsecs) using Euclidean distance on X/Y.
@@ -86,6 +188,10 @@ pub enum Commands {
         #[arg(short = 'r', long = "range")]
         range: f64,
 
+        /// Inner radius (parsecs): skip planets closer than this
+        #[arg(long = "min-r", value_name = "PARSECS")]
+        min_r: Option<f64>,
+
         /// Use unknown planets table
         #[arg(long)]
         unknown: bool,
@@ -105,6 +211,58 @@ pub enum Commands {
         /// Limit number of results
         #[arg(long, default_value_t = 10)]
         limit: i64,
+
+        /// Exclude a planet FID from the results (repeatable)
+        #[arg(long = "exclude-fid", value_name = "FID")]
+        exclude_fid: Vec<i64>,
+
+        /// Sort field (distance|name|fid). Default: distance
+        #[arg(long, value_enum, default_value_t = NearSort::Distance)]
+        sort: NearSort,
+
+        /// Show only Canon planets
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        canon: bool,
+
+        /// Show only Legends planets
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        legends: bool,
+    },
+
+    /// Straight-line X/Y distance between two planets (parsecs)
+    Distance {
+        /// First planet (name, alias or FID)
+        from: String,
+
+        /// Second planet (name, alias or FID)
+        to: String,
+
+        /// Print the result as JSON
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        json: bool,
+    },
+
+    /// Estimate hyperspace travel time between two planets
+    ///
+    /// Uses the persisted FROM→TO route length when one exists, the direct distance otherwise.
+    Hyperspace {
+        /// Start planet (name, alias or FID)
+        from: String,
+
+        /// Destination planet (name, alias or FID)
+        to: String,
+
+        /// Hyperdrive class (e.g. 0.5, 1.0, 2.0)
+        #[arg(long, default_value_t = 1.0)]
+        class: f64,
+
+        /// Use this galactic region (e.g. "mid rim") instead of the planets' own
+        #[arg(long, value_name = "REGION")]
+        region_override: Option<String>,
+
+        /// JSON file overriding per-region compression factors (e.g. {"outer rim": 20})
+        #[arg(long, value_name = "PATH")]
+        compression_config: Option<std::path::PathBuf>,
     },
 
     /// Database provisioning commands (C2: build local DB from remote data source)
@@ -130,6 +288,12 @@ pub enum Commands {
         #[command(subcommand)]
         cmd: UnknownCmd,
     },
+
+    /// Manage local-only planets (house-ruled or fan-created systems)
+    Planets {
+        #[command(subcommand)]
+        cmd: PlanetsCmd,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -140,13 +304,34 @@ pub enum DbCommands {
         #[arg(long)]
         out: Option<String>,
 
-        /// Overwrite existing database if present
+        /// Overwrite existing database if present (the previous file is kept as `<path>.bak`)
         #[arg(long, action = ArgAction::SetTrue)]
         force: bool,
+
+        /// Keep the first or last occurrence of duplicate FIDs instead of aborting
+        #[arg(long, value_enum)]
+        dedupe: Option<DedupePolicy>,
+
+        /// ArcGIS feature layer URL to download from instead of the default service
+        /// (e.g. a mirror); recorded in meta as `source_serviceUrl`
+        #[arg(long, value_name = "URL")]
+        service_url: Option<String>,
+
+        /// Retries per request on network errors and 5xx responses (exponential backoff)
+        #[arg(long, default_value_t = DEFAULT_MAX_RETRIES)]
+        max_retries: u32,
     },
 
     /// Show local database status (path, meta, counts)
-    Status,
+    Status {
+        /// Also list every table with its row count, largest first
+        #[arg(long, action = ArgAction::SetTrue)]
+        sizes: bool,
+
+        /// Emit the report as a JSON object (meta, counts, schema and FTS state)
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
 
     /// Update the local database with new data from the remote service
     Update {
@@ -158,6 +343,10 @@ pub enum DbCommands {
         #[arg(long, action = ArgAction::SetTrue)]
         dry_run: bool,
 
+        /// Skip the mark-deleted pass (keep local planets missing from the remote feed)
+        #[arg(long, action = ArgAction::SetTrue)]
+        no_mark_deleted: bool,
+
         /// Show update statistics
         #[arg(long, action = ArgAction::SetTrue)]
         stats: bool,
@@ -165,21 +354,85 @@ pub enum DbCommands {
         /// Limit for statistics output (default: 10)
         #[arg(long, default_value_t = 10)]
         stats_limit: usize,
+
+        /// Emit JSON lines instead of the text report: one `{fid, kind, planet}` object
+        /// per change (uncapped), then a `{"kind": "summary", ...}` object with the counters
+        #[arg(long, action = ArgAction::SetTrue, conflicts_with = "bench")]
+        stats_json: bool,
+
+        /// Fetch only features edited after this time (ISO date/time or Unix epoch).
+        /// `--since` alone uses the edit date stored by the previous update. The
+        /// mark-deleted pass is skipped for such partial downloads
+        #[arg(long, num_args = 0..=1, require_equals = true, value_name = "WHEN")]
+        since: Option<Option<String>>,
+
+        /// Report phase timings (download, hash, compare); requires --dry-run
+        #[arg(long, action = ArgAction::SetTrue)]
+        bench: bool,
+
+        /// Hash the downloaded features on all cores (same report as the sequential pass)
+        #[arg(long, action = ArgAction::SetTrue)]
+        parallel_hash: bool,
+
+        /// ArcGIS feature layer URL to compare against instead of the default service
+        #[arg(long, value_name = "URL")]
+        service_url: Option<String>,
+
+        /// Retries per request on network errors and 5xx responses (exponential backoff)
+        #[arg(long, default_value_t = DEFAULT_MAX_RETRIES)]
+        max_retries: u32,
     },
 
     /// Emit JSON listing the most recently skipped planets during db update
     SkippedPlanets,
 
-    /// Migrate the local database to the latest schema version
+    /// Migrate the local database to the latest (or `--to`) schema version
     Migrate {
         /// Show what migrations would be applied without executing them
-        #[arg(long, action = clap::ArgAction::SetTrue)]
+        #[arg(long, action = ArgAction::SetTrue)]
         dry_run: bool,
+
+        /// Stop at this schema version instead of the latest (no downgrades)
+        #[arg(long, value_name = "VERSION")]
+        to: Option<i64>,
     },
 
     /// Rebuild the `planet_search` table and FTS index from current `planets` data
     RebuildSearch,
 
+    /// Check database integrity and schema invariants (exits non-zero on failure)
+    ///
+    /// Runs `PRAGMA integrity_check`, compares the schema version with the latest,
+    /// checks the FTS row count and looks for orphan waypoint links and route rows.
+    Verify,
+
+    /// Apply a local CSV overlay of planet corrections
+    ///
+    /// Columns use the canonical field names written by `db export --table planets`
+    /// (FID, Planet, X and Y are required). Rows are upserted like `db update` features;
+    /// search indexes are rebuilt unless `--dry-run`.
+    #[command(name = "import")]
+    ImportCsv {
+        /// CSV file to apply
+        #[arg(long = "csv", value_name = "FILE")]
+        file: std::path::PathBuf,
+
+        /// Report what would change without writing anything
+        #[arg(long, action = ArgAction::SetTrue)]
+        dry_run: bool,
+
+        /// Mark active planets missing from the CSV as deleted (matched by FID or name;
+        /// local planets are never marked)
+        #[arg(long, action = ArgAction::SetTrue)]
+        mark_deleted: bool,
+    },
+
+    /// Compact the database file with `VACUUM` (reports the size before and after)
+    Vacuum,
+
+    /// Run `PRAGMA optimize` and merge the FTS index segments when present
+    Optimize,
+
     /// Display aggregate galaxy statistics (planets by region/sector/grid/status, routes)
     Stats {
         /// Number of top entries to show per category (default: 10)
@@ -187,6 +440,24 @@ pub enum DbCommands {
         top: usize,
     },
 
+    /// Show the log of past `db update` runs (newest first)
+    History {
+        /// Max rows (default: 20)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Emit the log rows as a JSON array
+        #[arg(long, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+
+    /// List the distinct values of a planet column with counts (e.g. valid region names)
+    Values {
+        /// Column to inspect
+        #[arg(value_enum)]
+        column: PlanetColumn,
+    },
+
     /// Sync the official Lucasfilm star-systems catalog into the planets table
     ///
     /// Reads a CSV, matches against existing records, updates status fields,
@@ -315,6 +586,93 @@ pub enum UnknownCmd {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum PlanetsCmd {
+    /// Add a local-only planet (kept by `db update`)
+    Add {
+        /// Planet name
+        name: String,
+
+        /// X coordinate
+        #[arg(long, allow_hyphen_values = true)]
+        x: f64,
+
+        /// Y coordinate
+        #[arg(long, allow_hyphen_values = true)]
+        y: f64,
+
+        /// Explicit FID (must be in the local range; default: next free)
+        #[arg(long)]
+        fid: Option<i64>,
+
+        /// Region
+        #[arg(long)]
+        region: Option<String>,
+
+        /// Sector
+        #[arg(long)]
+        sector: Option<String>,
+
+        /// System
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Grid
+        #[arg(long)]
+        grid: Option<String>,
+
+        /// Canon flag (true/false)
+        #[arg(long)]
+        canon: Option<bool>,
+
+        /// Legends flag (true/false)
+        #[arg(long)]
+        legend: Option<bool>,
+    },
+
+    /// Edit a local-only planet (imported planets are read-only)
+    Edit {
+        /// Planet FID
+        fid: i64,
+
+        /// Planet name
+        #[arg(long)]
+        planet: Option<String>,
+
+        /// X coordinate
+        #[arg(long, allow_hyphen_values = true)]
+        x: Option<f64>,
+
+        /// Y coordinate
+        #[arg(long, allow_hyphen_values = true)]
+        y: Option<f64>,
+
+        /// Region
+        #[arg(long)]
+        region: Option<String>,
+
+        /// Sector
+        #[arg(long)]
+        sector: Option<String>,
+
+        /// System
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Grid
+        #[arg(long)]
+        grid: Option<String>,
+
+        /// Canon flag (true/false)
+        #[arg(long)]
+        canon: Option<bool>,
+
+        /// Legends flag (true/false)
+        #[arg(long)]
+        legend: Option<bool>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum WaypointCmd {
     /// Add a new waypoint
@@ -322,13 +680,43 @@ pub enum WaypointCmd {
         /// Waypoint name (unique, human friendly)
         name: String,
 
-        /// X coordinate (parsec)
-        #[arg(allow_hyphen_values = true)]
-        x: f64,
+        /// X coordinate (parsec). Required unless --from-planet is used.
+        #[arg(
+            allow_hyphen_values = true,
+            required_unless_present = "from_planet",
+            conflicts_with = "from_planet"
+        )]
+        x: Option<f64>,
 
-        /// Y coordinate (parsec)
-        #[arg(allow_hyphen_values = true)]
-        y: f64,
+        /// Y coordinate (parsec). Required unless --from-planet is used.
+        #[arg(
+            allow_hyphen_values = true,
+            required_unless_present = "from_planet",
+            conflicts_with = "from_planet"
+        )]
+        y: Option<f64>,
+
+        /// Place the waypoint relative to this planet (name or alias)
+        #[arg(long = "from-planet")]
+        from_planet: Option<String>,
+
+        /// X offset from --from-planet (parsec)
+        #[arg(
+            long,
+            default_value_t = 0.0,
+            allow_hyphen_values = true,
+            requires = "from_planet"
+        )]
+        dx: f64,
+
+        /// Y offset from --from-planet (parsec)
+        #[arg(
+            long,
+            default_value_t = 0.0,
+            allow_hyphen_values = true,
+            requires = "from_planet"
+        )]
+        dy: f64,
 
         /// Waypoint kind (manual, junction, nav_buoy, computed, ...)
         #[arg(long, default_value = "manual")]
@@ -354,6 +742,10 @@ pub enum WaypointCmd {
     Show {
         /// Waypoint name (e.g. "Corellian Junction") or numeric id (e.g. "12")
         key: String,
+
+        /// Print the full waypoint record as JSON instead of the details block
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        json: bool,
     },
 
     /// Delete waypoint by id
@@ -362,13 +754,87 @@ pub enum WaypointCmd {
         id: i64,
     },
 
+    /// List catalog waypoints within a radius of a planet or of X/Y coordinates
+    Near {
+        /// Center planet (name or alias)
+        #[arg(long, conflicts_with_all = ["x", "y"])]
+        planet: Option<String>,
+
+        /// Center X coordinate (alternative to --planet)
+        #[arg(long, allow_hyphen_values = true, requires = "y")]
+        x: Option<f64>,
+
+        /// Center Y coordinate (alternative to --planet)
+        #[arg(long, allow_hyphen_values = true, requires = "x")]
+        y: Option<f64>,
+
+        /// Search radius (parsecs)
+        #[arg(short = 'r', long = "range")]
+        range: f64,
+
+        /// Max rows (default: 20)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Move a waypoint by a relative offset (parsec)
+    Move {
+        /// Waypoint id
+        id: i64,
+
+        /// X offset (parsec, may be negative)
+        #[arg(allow_hyphen_values = true)]
+        dx: f64,
+
+        /// Y offset (parsec, may be negative)
+        #[arg(allow_hyphen_values = true)]
+        dy: f64,
+    },
+
+    /// Rename a waypoint (planet links are kept)
+    Rename {
+        /// Waypoint id
+        id: i64,
+
+        /// New waypoint name (must be unique)
+        new_name: String,
+    },
+
+    /// Edit an existing waypoint (only the given fields change; links are kept)
+    Update {
+        /// Waypoint id
+        id: i64,
+
+        /// New name (must stay unique)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New X coordinate (parsec)
+        #[arg(long, allow_hyphen_values = true)]
+        x: Option<f64>,
+
+        /// New Y coordinate (parsec)
+        #[arg(long, allow_hyphen_values = true)]
+        y: Option<f64>,
+
+        /// New kind (manual, junction, nav_buoy, ...)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// New note
+        #[arg(long)]
+        note: Option<String>,
+    },
+
     /// Link a waypoint to a planet (planet name or alias)
     Link {
         /// Waypoint ID
-        waypoint_id: i64,
+        #[arg(required_unless_present = "from_file")]
+        waypoint_id: Option<i64>,
 
         /// Planet name or alias
-        planet: String,
+        #[arg(required_unless_present = "from_file")]
+        planet: Option<String>,
 
         /// Role of the planet for this waypoint (default: "anchor")
         #[arg(long, default_value = "anchor")]
@@ -377,6 +843,39 @@ pub enum WaypointCmd {
         /// Optional distance (parsec). If omitted, it can be computed later.
         #[arg(long)]
         distance: Option<f64>,
+
+        /// Batch mode: CSV with `waypoint_id,planet,role[,distance]` rows.
+        /// An empty role falls back to --role.
+        #[arg(
+            long,
+            value_name = "CSV",
+            conflicts_with_all = ["waypoint_id", "planet", "distance"]
+        )]
+        from_file: Option<std::path::PathBuf>,
+    },
+
+    /// Bulk-add waypoints from a CSV with `name,x,y[,kind][,note]` rows
+    ///
+    /// Rows whose name already exists (in the catalog or earlier in the file) are
+    /// reported and skipped.
+    Import {
+        /// CSV file (an optional `name,...` header row is ignored)
+        file: std::path::PathBuf,
+
+        /// Parse and validate only, write nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export the whole waypoint catalog, with planet links, as a JSON array
+    Export {
+        /// Emit JSON (the default and only format; accepted for explicit scripts)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        json: bool,
+
+        /// Write JSON to file (absolute or relative path). Default: stdout
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
     },
 
     /// List planet links for a waypoint
@@ -410,6 +909,10 @@ pub enum WaypointCmd {
 
         /// Planet name or alias
         planet: String,
+
+        /// Remove only this role (default: every role for the planet)
+        #[arg(long)]
+        role: Option<String>,
     },
 
     /// Remove orphan computed waypoints (not referenced by any route)
@@ -422,6 +925,10 @@ pub enum WaypointCmd {
         /// Links will be removed as part of the prune.
         #[arg(long)]
         include_linked: bool,
+
+        /// Only prune waypoints whose last update (or creation) is older than this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
     },
 }
 
@@ -434,6 +941,22 @@ pub enum RouteCmd {
     Show {
         /// Route id
         route_id: i64,
+
+        /// Emit the route line and the detoured obstacles (as circle polygons) as GeoJSON
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        obstacles_geojson: bool,
+
+        /// Write GeoJSON to file (absolute or relative path). Requires --obstacles-geojson.
+        #[arg(long, requires = "obstacles_geojson")]
+        file: Option<std::path::PathBuf>,
+
+        /// Hyperdrive class used for the ETA line (e.g. 0.5, 1.0, 2.0)
+        #[arg(long, visible_alias = "class", default_value_t = 1.0)]
+        hyperdrive_class: f64,
+
+        /// JSON file overriding per-region compression factors (e.g. {"outer rim": 20})
+        #[arg(long, value_name = "PATH")]
+        compression_config: Option<std::path::PathBuf>,
     },
 
     /// Explain a persisted route detours (why/what/how) by id
@@ -448,6 +971,15 @@ pub enum RouteCmd {
         to: String,
     },
 
+    /// Compare two persisted routes (length, iterations, detours by obstacle)
+    Diff {
+        /// Route id A (baseline)
+        a: i64,
+
+        /// Route id B (compared against A)
+        b: i64,
+    },
+
     /// Clear all persisted routes (routes, waypoints, detours)
     Clear {
         /// Skip interactive confirmation prompt (destructive)
@@ -458,6 +990,27 @@ pub enum RouteCmd {
     /// Prune orphan rows in route_waypoints / route_detours not linked to any route
     Prune,
 
+    /// Export a persisted route (polyline, detours, obstacles) as shareable JSON
+    Export {
+        /// Route id
+        route_id: i64,
+
+        /// Emit the polyline and detour waypoints as a GeoJSON FeatureCollection instead
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        geojson: bool,
+
+        /// Write JSON to file (absolute or relative path). Default: stdout
+        #[arg(long)]
+        file: Option<std::path::PathBuf>,
+    },
+
+    /// Import a route written by `route export`, matching endpoints by FID
+    Import {
+        /// JSON file produced by `route export`
+        #[arg(long)]
+        file: std::path::PathBuf,
+    },
+
     // ...
     List {
         #[arg(long, action = clap::ArgAction::SetTrue)]
@@ -485,6 +1038,10 @@ pub enum RouteCmd {
         #[arg(long)]
         wp: Option<usize>,
 
+        /// Only routes that detoured around this planet (name or alias)
+        #[arg(long = "near-obstacle", value_name = "PLANET")]
+        near_obstacle: Option<String>,
+
         /// Sort field (updated|id|length). Default: updated
         #[arg(long, value_enum, default_value_t = RouteListSort::Updated)]
         sort: RouteListSort,
@@ -518,6 +1075,10 @@ pub struct RouteComputeArgs {
     #[arg(long, default_value_t = 32)]
     pub max_iters: usize,
 
+    /// Fail once the route grows past this many waypoints (start and end included)
+    #[arg(long, default_value_t = DEFAULT_MAX_WAYPOINTS)]
+    pub max_waypoints: usize,
+
     #[arg(long, default_value_t = 6)]
     pub max_offset_tries: usize,
 
@@ -540,6 +1101,19 @@ pub struct RouteComputeArgs {
     #[arg(long, default_value_t = 0.5)]
     pub proximity_margin: f64,
 
+    /// Falloff of the proximity penalty: 1 = linear, 2 = quadratic (clamped to 1..=4)
+    #[arg(long, default_value_t = 2.0)]
+    pub proximity_exponent: f64,
+
+    /// Compare the best detour on each side of an obstacle instead of taking the
+    /// first offset that works (slower: may try every offset for every detour)
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub explore_both_sides: bool,
+
+    /// Distance metric for route length and detour scoring (collisions stay Euclidean)
+    #[arg(long, value_enum, default_value_t = Metric::Euclidean)]
+    pub metric: Metric,
+
     /// Bounding box margin (parsec) around the segment A->B to fetch candidate obstacles
     #[arg(long, default_value_t = 80.0)]
     pub bbox_margin: f64,
@@ -547,9 +1121,90 @@ pub struct RouteComputeArgs {
     /// Max obstacles to consider (debug safety cap)
     #[arg(long, default_value_t = 8000)]
     pub max_obstacles: usize,
+
+    /// Persist the computed route (use `--persist=false` to compute without saving)
+    #[arg(
+        long,
+        default_value_t = true,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        action = ArgAction::Set
+    )]
+    pub persist: bool,
+
+    /// Compute and print the route without writing anything to the database
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+
+    /// Reuse a persisted TO→FROM route (reversed) instead of recomputing FROM→TO,
+    /// when it was computed with the same options.
+    ///
+    /// Valid because the routing cost model is symmetric; any future asymmetric cost
+    /// (e.g. directional hyperlanes) must disable this.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub symmetric: bool,
+
+    /// Route through this catalog waypoint (name or id). Repeatable, in travel order.
+    ///
+    /// Each stretch between stops is computed separately and the polylines joined.
+    /// Only valid for a single FROM→TO leg.
+    #[arg(long = "waypoint", value_name = "WAYPOINT")]
+    pub via: Vec<String>,
+
+    /// Start from the polyline of this stored route (id) instead of the straight segment.
+    ///
+    /// Only what collides under the current options is repaired. Ignored, with a
+    /// warning, when the stored route joins different planets or --waypoint is used.
+    #[arg(long, value_name = "ID")]
+    pub seed_route: Option<i64>,
+
+    /// Treat this planet (name or alias) as an obstacle even if it would not be one.
+    ///
+    /// Repeatable; uses the --safety radius. Ignored when it is the leg's start or end.
+    #[arg(long, value_name = "PLANET")]
+    pub avoid: Vec<String>,
+
+    /// Minimum gap (parsec) between the whole polyline and every obstacle edge.
+    ///
+    /// When violated, obstacles are widened by the shortfall and the route recomputed
+    /// (a few times at most).
+    #[arg(long, value_name = "W")]
+    pub min_corridor: Option<f64>,
+
+    /// Merge consecutive waypoints closer than D parsec after computing.
+    ///
+    /// A waypoint is only dropped when the resulting shortcut still clears every obstacle.
+    #[arg(long, value_name = "D")]
+    pub min_spacing: Option<f64>,
+
+    /// Write a JSON-lines log of every router iteration (collision, candidates, choice)
+    #[arg(long, value_name = "PATH")]
+    pub trace_file: Option<std::path::PathBuf>,
+
+    /// Round persisted waypoint/detour coordinates to N decimals (default: full precision).
+    ///
+    /// Smaller values shrink the database and make stored routes stable across
+    /// float noise, at the cost of slightly displaced waypoints.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(0..=12))]
+    pub coord_precision: Option<u32>,
+
+    /// Move start/end onto the nearest known waypoint within --snap-radius, if any
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub snap_endpoints: bool,
+
+    /// Max distance (parsec) an endpoint may move when snapping
+    #[arg(
+        long,
+        value_name = "R",
+        default_value_t = 1.0,
+        requires = "snap_endpoints"
+    )]
+    pub snap_radius: f64,
 }
 
 #[derive(Args, Debug)]
+#[command(group(clap::ArgGroup::new("explain_export").args(["json", "detours_csv"])))]
 pub struct RouteExplainArgs {
     /// Route id
     pub route_id: i64,
@@ -558,14 +1213,27 @@ pub struct RouteExplainArgs {
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub json: bool,
 
-    /// Write JSON to file (absolute or relative path). Requires --json.
-    #[arg(long, requires = "json")]
+    /// Export one CSV row per detour (stdout), for spreadsheets.
+    ///
+    /// Columns: idx, iteration, obstacle_id, obstacle_name, closest_dist, required,
+    /// violated_by, offset_used, score_base, score_turn, score_back, score_proximity,
+    /// score_total, tries_used, tries_exhausted
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["json", "csv"])]
+    pub detours_csv: bool,
+
+    /// Write JSON / detour CSV to file (absolute or relative path).
+    /// Requires --json or --detours-csv.
+    #[arg(long, requires = "explain_export")]
     pub file: Option<std::path::PathBuf>,
 
     /// Hyperdrive class (e.g. 0.5, 1.0, 2.0)
     #[arg(long = "class", default_value_t = 1.0)]
     pub hyperdrive_class: f64,
 
+    /// JSON file overriding per-region compression factors (e.g. {"outer rim": 20})
+    #[arg(long, value_name = "PATH")]
+    pub compression_config: Option<std::path::PathBuf>,
+
     /// Region blend strategy: avg | conservative | <from_weight>
     #[arg(long = "region-blend", default_value = "avg")]
     pub region_blend: String,
@@ -581,6 +1249,14 @@ pub struct RouteExplainArgs {
     /// Columns: seq, x, y, segment_parsec, cumulative_parsec, label
     #[arg(long = "csv")]
     pub csv: Option<std::path::PathBuf>,
+
+    /// Print the route as a Graphviz DOT graph (stdout), e.g. `| dot -Tpng -o route.png`
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["json", "csv", "detours_csv"])]
+    pub dot: bool,
+
+    /// Draw an ASCII gauge per detour: how deep the straight line cut into `required`
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["json", "dot"])]
+    pub gauge: bool,
 }
 
 #[derive(Debug, Args)]
@@ -590,27 +1266,56 @@ pub struct DbBackupArgs {
     pub output: Option<std::path::PathBuf>,
 }
 
+/// Export file format (`db export --format`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
 /// Export a database table to CSV or JSON.
 #[derive(Debug, Args)]
 #[command(group(
-    clap::ArgGroup::new("format")
+    clap::ArgGroup::new("export_format")
         .required(true)
-        .args(["csv", "json"])
+        .args(["format", "csv", "json"])
 ))]
 pub struct DbExportArgs {
     /// Table name to export.
-    #[arg(long)]
+    #[arg(long, default_value = "planets")]
     pub table: String,
 
-    /// Export table rows as CSV.
+    /// Export format.
+    #[arg(long, value_enum)]
+    pub format: Option<ExportFormat>,
+
+    /// Export table rows as CSV (same as `--format csv`).
     #[arg(long)]
     pub csv: bool,
 
-    /// Export table rows as JSON.
+    /// Export table rows as JSON (same as `--format json`).
     #[arg(long)]
     pub json: bool,
 
     /// Destination directory for the export file.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "out")]
     pub output: Option<std::path::PathBuf>,
+
+    /// Destination file (overwritten if it exists).
+    #[arg(long)]
+    pub out: Option<std::path::PathBuf>,
+
+    /// Include planets marked deleted (`planets` table only).
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub include_deleted: bool,
+}
+
+impl DbExportArgs {
+    pub fn export_format(&self) -> ExportFormat {
+        match self.format {
+            Some(f) => f,
+            None if self.json => ExportFormat::Json,
+            None => ExportFormat::Csv,
+        }
+    }
 }
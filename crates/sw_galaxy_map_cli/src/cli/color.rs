@@ -70,7 +70,11 @@ impl Colors {
 
     #[inline]
     pub fn red_alert(&self, s: &str) -> String {
-        format!("\x1b[41;97m{}\x1b[0m", s) // bg red (41), fg white (97)
+        if self.enabled {
+            format!("\x1b[41;97m{}\x1b[0m", s) // bg red (41), fg white (97)
+        } else {
+            s.to_string()
+        }
     }
 
     // Domain-specific helpers (policy)
@@ -9,13 +9,17 @@ use sw_galaxy_map_core::db::db_status::resolve_db_path;
 
 use crate::cli::args::DbBackupArgs;
 use crate::cli::commands::db::utils::human_size;
+use crate::cli::errors::not_found;
 
 /// Creates a consistent backup copy of the current SQLite database.
 pub fn run(db_override: Option<String>, args: &DbBackupArgs) -> Result<()> {
     let db_path = resolve_db_path(db_override)?;
 
     if !db_path.exists() {
-        bail!("Database file not found: {}", db_path.display());
+        return Err(not_found(format!(
+            "Database file not found: {}",
+            db_path.display()
+        )));
     }
 
     let timestamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
@@ -9,9 +9,11 @@ use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
 
 use sw_galaxy_map_core::db::db_status::resolve_db_path;
+use sw_galaxy_map_core::db::provision::ARCGIS_HASH_FIELDS;
 
-use crate::cli::args::DbExportArgs;
+use crate::cli::args::{DbExportArgs, ExportFormat};
 use crate::cli::commands::db::utils::human_size;
+use crate::cli::errors::not_found;
 
 /// Tables that can be exported through the CLI.
 ///
@@ -43,29 +45,43 @@ pub fn run(db_override: Option<String>, args: &DbExportArgs) -> Result<()> {
         );
     }
 
+    if args.include_deleted && table != "planets" {
+        bail!("--include-deleted only applies to the planets table.");
+    }
+
     let db_path = resolve_db_path(db_override)?;
     if !db_path.exists() {
-        bail!("Database file not found: {}", db_path.display());
+        return Err(not_found(format!(
+            "Database file not found: {}",
+            db_path.display()
+        )));
     }
 
     let con = Connection::open(&db_path)?;
+    let format = args.export_format();
 
-    let dest_dir = match &args.output {
+    let output_path = match &args.out {
         Some(path) => path.clone(),
-        None => prompt_destination_directory()?,
+        None => {
+            let dest_dir = match &args.output {
+                Some(path) => path.clone(),
+                None => prompt_destination_directory()?,
+            };
+            validate_destination_directory(&dest_dir)?;
+            build_output_path(&dest_dir, table, format)
+        }
     };
-    validate_destination_directory(&dest_dir)?;
 
-    let output_path = build_output_path(&dest_dir, table, args.csv, args.json)?;
-
-    if args.csv {
-        export_csv(&con, table, &output_path)?;
-        println!("CSV export completed successfully.");
-    } else if args.json {
-        export_json(&con, table, &output_path)?;
-        println!("JSON export completed successfully.");
-    } else {
-        bail!("You must specify either --csv or --json.");
+    let sql = select_sql(table, args.include_deleted);
+    match format {
+        ExportFormat::Csv => {
+            export_csv(&con, &sql, &output_path)?;
+            println!("CSV export completed successfully.");
+        }
+        ExportFormat::Json => {
+            export_json(&con, &sql, &output_path)?;
+            println!("JSON export completed successfully.");
+        }
     }
 
     let size = std::fs::metadata(&output_path)?.len();
@@ -109,25 +125,44 @@ fn validate_destination_directory(path: &Path) -> Result<()> {
 }
 
 /// Builds the final export file path using table name, timestamp, and format.
-fn build_output_path(dest_dir: &Path, table: &str, csv: bool, json: bool) -> Result<PathBuf> {
-    let ext = if csv {
-        "csv"
-    } else if json {
-        "json"
-    } else {
-        bail!("You must specify either --csv or --json.");
+fn build_output_path(dest_dir: &Path, table: &str, format: ExportFormat) -> PathBuf {
+    let ext = match format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Json => "json",
     };
 
     let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
     let file_name = format!("{}_{}.{}", table, timestamp, ext);
 
-    Ok(dest_dir.join(file_name))
+    dest_dir.join(file_name)
 }
 
-/// Exports the selected table as CSV.
-fn export_csv(con: &Connection, table: &str, output_path: &Path) -> Result<()> {
-    let sql = format!("SELECT * FROM {table}");
-    let mut stmt = con.prepare(&sql)?;
+/// Query for a whitelisted table.
+///
+/// `planets` uses the canonical ArcGIS field order (the one hashed into `arcgis_hash`),
+/// ordered by FID and without deleted rows unless `include_deleted`, so snapshots of
+/// different releases diff cleanly.
+fn select_sql(table: &str, include_deleted: bool) -> String {
+    if table != "planets" {
+        return format!("SELECT * FROM {table}");
+    }
+
+    let columns = ARCGIS_HASH_FIELDS
+        .iter()
+        .map(|c| format!("\"{c}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let filter = if include_deleted {
+        ""
+    } else {
+        " WHERE deleted = 0 AND (status IS NULL OR status <> 'deleted')"
+    };
+    format!("SELECT {columns} FROM planets{filter} ORDER BY FID")
+}
+
+/// Exports the query rows as CSV.
+fn export_csv(con: &Connection, sql: &str, output_path: &Path) -> Result<()> {
+    let mut stmt = con.prepare(sql)?;
     let columns: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
 
     let file = File::create(output_path)?;
@@ -150,15 +185,17 @@ fn export_csv(con: &Connection, table: &str, output_path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Exports the selected table as JSON.
-fn export_json(con: &Connection, table: &str, output_path: &Path) -> Result<()> {
-    let sql = format!("SELECT * FROM {table}");
-    let mut stmt = con.prepare(&sql)?;
+/// Exports the query rows as a JSON array, streamed one object per line.
+fn export_json(con: &Connection, sql: &str, output_path: &Path) -> Result<()> {
+    let mut stmt = con.prepare(sql)?;
     let columns: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
 
-    let mut rows = stmt.query([])?;
-    let mut out = Vec::<Value>::new();
+    let file = File::create(output_path)?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(b"[")?;
 
+    let mut rows = stmt.query([])?;
+    let mut first = true;
     while let Some(row) = rows.next()? {
         let mut obj = Map::new();
 
@@ -166,13 +203,13 @@ fn export_json(con: &Connection, table: &str, output_path: &Path) -> Result<()>
             obj.insert(col.clone(), sqlite_value_to_json(row, idx)?);
         }
 
-        out.push(Value::Object(obj));
+        writer.write_all(if first { b"\n  " } else { b",\n  " })?;
+        serde_json::to_writer(&mut writer, &Value::Object(obj))?;
+        first = false;
     }
 
-    let file = File::create(output_path)?;
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, &out)?;
-
+    writer.write_all(b"\n]\n")?;
+    writer.flush()?;
     Ok(())
 }
 
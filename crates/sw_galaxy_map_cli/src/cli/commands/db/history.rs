@@ -0,0 +1,52 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use sw_galaxy_map_core::db::queries::list_update_log;
+
+/// Prints the `db update` log as a table, or as a JSON array with `--json`.
+pub fn run(con: &Connection, limit: usize, json: bool) -> Result<()> {
+    let rows = list_update_log(con, limit)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("No db update runs recorded yet.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<24}  {:>8}  {:>6}  {:>6}  {:>6}  {:>6}  {:>6}  {:>6}  FLAGS",
+        "RAN AT", "FEATURES", "INS", "UPD", "REV", "DEL", "PRUNED", "SKIP"
+    );
+    for r in rows {
+        let mut flags = Vec::new();
+        if r.prune_used {
+            flags.push("prune");
+        }
+        if !r.mark_deleted_used {
+            flags.push("no-mark-deleted");
+        }
+        let flags = if flags.is_empty() {
+            "-".to_string()
+        } else {
+            flags.join(",")
+        };
+
+        println!(
+            "{:<24}  {:>8}  {:>6}  {:>6}  {:>6}  {:>6}  {:>6}  {:>6}  {}",
+            r.ran_at,
+            r.downloaded_features,
+            r.inserted,
+            r.updated,
+            r.revived,
+            r.marked_deleted,
+            r.pruned,
+            r.skipped,
+            flags
+        );
+    }
+
+    Ok(())
+}
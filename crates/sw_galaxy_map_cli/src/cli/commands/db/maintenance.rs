@@ -0,0 +1,83 @@
+use anyhow::{Context, Result, bail};
+use rusqlite::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sw_galaxy_map_core::db::db_status::resolve_db_path;
+use sw_galaxy_map_core::db::has_table;
+
+use crate::cli::commands::db::utils::human_size;
+use crate::cli::errors::not_found;
+use crate::ui::{info, success};
+
+/// Opens the database as-is (no init, no migration) and checks it has a schema.
+fn open_existing(db_override: Option<String>) -> Result<(PathBuf, Connection)> {
+    let db_path = resolve_db_path(db_override)?;
+
+    if !db_path.exists() {
+        return Err(not_found(format!(
+            "Database file not found: {}",
+            db_path.display()
+        )));
+    }
+
+    let con = Connection::open(&db_path)
+        .with_context(|| format!("Unable to open database: {}", db_path.display()))?;
+
+    if !has_table(&con, "meta")? {
+        bail!(
+            "Table 'meta' is missing in {} (database not initialized or schema is invalid)",
+            db_path.display()
+        );
+    }
+
+    Ok((db_path, con))
+}
+
+fn file_size(path: &Path) -> Result<u64> {
+    Ok(fs::metadata(path)
+        .context("Unable to read database file metadata")?
+        .len())
+}
+
+/// Rebuilds the database file with `VACUUM`, reporting the size before and after.
+pub fn run_vacuum(db_override: Option<String>) -> Result<()> {
+    let (db_path, con) = open_existing(db_override)?;
+    let before = file_size(&db_path)?;
+
+    info(format!("Vacuuming {} ...", db_path.display()));
+    con.execute_batch("VACUUM;").context("VACUUM failed")?;
+    // In WAL mode the compacted pages land in the -wal file until checkpointed.
+    con.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+        .context("WAL checkpoint failed")?;
+    drop(con);
+
+    let after = file_size(&db_path)?;
+    println!("Size before : {}", human_size(before));
+    println!("Size after  : {}", human_size(after));
+    println!("Reclaimed   : {}", human_size(before.saturating_sub(after)));
+    success("Vacuum complete.");
+    Ok(())
+}
+
+/// Runs `PRAGMA optimize` and merges the FTS index segments when FTS is present.
+pub fn run_optimize(db_override: Option<String>) -> Result<()> {
+    let (_, con) = open_existing(db_override)?;
+
+    con.execute_batch("PRAGMA optimize;")
+        .context("PRAGMA optimize failed")?;
+    success("PRAGMA optimize done.");
+
+    if has_table(&con, "planets_fts")? {
+        con.execute(
+            "INSERT INTO planets_fts(planets_fts) VALUES('optimize')",
+            [],
+        )
+        .context("FTS optimize failed")?;
+        success("planets_fts index optimized.");
+    } else {
+        info("planets_fts not present; FTS optimize skipped.");
+    }
+
+    Ok(())
+}
@@ -2,4 +2,7 @@
 
 pub mod backup;
 pub mod export;
+pub mod history;
+pub mod maintenance;
 pub mod utils;
+pub mod verify;
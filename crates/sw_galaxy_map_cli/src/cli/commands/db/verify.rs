@@ -0,0 +1,31 @@
+use crate::ui::{error, success};
+use anyhow::{Result, bail};
+use rusqlite::Connection;
+
+const LABEL_W: usize = 16;
+
+/// Prints one line per check and fails (non-zero exit) when any check failed.
+pub fn run(con: &Connection) -> Result<()> {
+    let report = sw_galaxy_map_core::db::db_verify::run(con)?;
+
+    for c in &report.checks {
+        let line = format!("{:<LABEL_W$}: {}", c.name, c.detail);
+        if c.ok {
+            success(line);
+        } else {
+            error(line);
+        }
+    }
+
+    let failed = report.failed();
+    if failed > 0 {
+        bail!(
+            "Database verification failed: {} of {} checks",
+            failed,
+            report.checks.len()
+        );
+    }
+    println!();
+    success("All checks passed.");
+    Ok(())
+}
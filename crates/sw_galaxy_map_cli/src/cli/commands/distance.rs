@@ -0,0 +1,66 @@
+use crate::cli::errors::not_found;
+use crate::ui::info;
+use anyhow::Result;
+use rusqlite::Connection;
+use serde_json::json;
+use sw_galaxy_map_core::db::queries::{find_planet_by_fid, find_planet_for_info};
+use sw_galaxy_map_core::model::{PC_TO_LY, Planet};
+use sw_galaxy_map_core::routing::geometry::{Point, dist};
+use sw_galaxy_map_core::utils::normalize_text;
+
+const LABEL_W: usize = 16;
+
+/// Resolves a planet by name or alias, then by FID.
+fn find(con: &Connection, name: &str) -> Result<Planet> {
+    let found = match find_planet_for_info(con, &normalize_text(name))? {
+        Some(p) => Some(p),
+        None => find_planet_by_fid(con, name)?,
+    };
+    found.ok_or_else(|| not_found(format!("No planet found matching '{}'", name)))
+}
+
+/// Resolves both planets and returns them with their X/Y distance (parsec).
+pub(crate) fn resolve(con: &Connection, from: &str, to: &str) -> Result<(Planet, Planet, f64)> {
+    let a = find(con, from)?;
+    let b = find(con, to)?;
+    let d = dist(Point::new(a.x, a.y), Point::new(b.x, b.y));
+    Ok((a, b, d))
+}
+
+fn endpoint_json(p: &Planet) -> serde_json::Value {
+    json!({ "fid": p.fid, "planet": p.planet, "x": p.x, "y": p.y })
+}
+
+pub fn run(con: &Connection, from: &str, to: &str, as_json: bool) -> Result<()> {
+    let (a, b, d) = resolve(con, from, to)?;
+
+    if as_json {
+        let doc = json!({
+            "from": endpoint_json(&a),
+            "to": endpoint_json(&b),
+            "distance_pc": d,
+            "distance_ly": d * PC_TO_LY,
+        });
+        println!("{}", serde_json::to_string_pretty(&doc)?);
+        return Ok(());
+    }
+
+    info("Distance");
+    println!();
+    println!(
+        "{:<LABEL_W$}: {} (FID={}) at ({:.2}, {:.2})",
+        "From", a.planet, a.fid, a.x, a.y
+    );
+    println!(
+        "{:<LABEL_W$}: {} (FID={}) at ({:.2}, {:.2})",
+        "To", b.planet, b.fid, b.x, b.y
+    );
+    println!(
+        "{:<LABEL_W$}: {:.3} parsec ({:.3} ly)",
+        "Distance",
+        d,
+        d * PC_TO_LY
+    );
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use crate::ui::{info, warning};
+use anyhow::{Result, bail};
+use rusqlite::Connection;
+use std::path::Path;
+use sw_galaxy_map_core::db::queries::get_route_by_from_to;
+use sw_galaxy_map_core::model::Planet;
+use sw_galaxy_map_core::routing::hyperspace::{
+    CompressionTable, DetourPenaltyParams, GalacticRegion, detour_penalty_multiplier,
+    estimate_travel_time_hours, extract_galactic_region,
+};
+
+use super::distance;
+
+const LABEL_W: usize = 16;
+
+/// Region assumed for an endpoint without usable region data.
+const FALLBACK_REGION: GalacticRegion = GalacticRegion::OuterRim;
+
+/// Travel-time estimate between two planets.
+pub(crate) struct Estimate {
+    pub from: Planet,
+    pub to: Planet,
+    pub direct: f64,
+    /// Persisted FROM→TO route (id, length), when one exists.
+    pub routed: Option<(i64, f64)>,
+    pub from_region: GalacticRegion,
+    pub to_region: GalacticRegion,
+    /// Endpoints whose region fell back to [`FALLBACK_REGION`].
+    pub missing_region: Vec<String>,
+    pub detour_mult: f64,
+    pub compression: f64,
+    pub hours: f64,
+}
+
+/// Resolves both planets and estimates the hyperspace travel time.
+///
+/// Uses the persisted FROM→TO route length (with its detour penalty) when available,
+/// the direct distance otherwise. `region_override` replaces both endpoint regions;
+/// region factors come from `table`.
+pub(crate) fn resolve(
+    con: &Connection,
+    from: &str,
+    to: &str,
+    class: f64,
+    region_override: Option<&str>,
+    table: &CompressionTable,
+) -> Result<Estimate> {
+    if !class.is_finite() || class <= 0.0 {
+        bail!("--class must be a finite number > 0");
+    }
+    let forced = match region_override {
+        Some(s) => match GalacticRegion::parse(s) {
+            Some(r) => Some(r),
+            None => bail!("Unknown galactic region: '{}'", s),
+        },
+        None => None,
+    };
+
+    let (a, b, direct) = distance::resolve(con, from, to)?;
+    if direct <= 0.0 {
+        bail!(
+            "'{}' and '{}' are at the same coordinates",
+            a.planet,
+            b.planet
+        );
+    }
+
+    let routed = get_route_by_from_to(con, a.fid, b.fid)?
+        .filter(|r| r.status == "ok")
+        .and_then(|r| r.length.map(|len| (r.id, len)));
+
+    let mut missing_region = Vec::new();
+    let mut region_of = |p: &Planet| {
+        forced
+            .or_else(|| extract_galactic_region(p))
+            .unwrap_or_else(|| {
+                missing_region.push(p.planet.clone());
+                FALLBACK_REGION
+            })
+    };
+    let from_region = region_of(&a);
+    let to_region = region_of(&b);
+
+    let detour_mult = match routed {
+        Some((_, len)) => {
+            detour_penalty_multiplier(direct, len.max(direct), DetourPenaltyParams::default())
+        }
+        None => 1.0,
+    };
+    let cf_base = (table.factor(from_region) + table.factor(to_region)) / 2.0;
+    let compression = cf_base * detour_mult;
+    let distance = routed.map_or(direct, |(_, len)| len);
+    let hours = estimate_travel_time_hours(distance, compression, class);
+
+    Ok(Estimate {
+        from: a,
+        to: b,
+        direct,
+        routed,
+        from_region,
+        to_region,
+        missing_region,
+        detour_mult,
+        compression,
+        hours,
+    })
+}
+
+pub fn run(
+    con: &Connection,
+    from: &str,
+    to: &str,
+    class: f64,
+    region_override: Option<&str>,
+    compression_config: Option<&Path>,
+) -> Result<()> {
+    let table = CompressionTable::load(compression_config)?;
+    let e = resolve(con, from, to, class, region_override, &table)?;
+
+    info("Hyperspace estimate");
+    println!();
+    println!(
+        "{:<LABEL_W$}: {} (FID={}) [{:?}]",
+        "From", e.from.planet, e.from.fid, e.from_region
+    );
+    println!(
+        "{:<LABEL_W$}: {} (FID={}) [{:?}]",
+        "To", e.to.planet, e.to.fid, e.to_region
+    );
+    println!("{:<LABEL_W$}: {:.3} parsec", "Direct", e.direct);
+    match e.routed {
+        Some((id, len)) => println!(
+            "{:<LABEL_W$}: {:.3} parsec (route #{}, detour mult={:.3})",
+            "Routed", len, id, e.detour_mult
+        ),
+        None => println!("{:<LABEL_W$}: - (no persisted route)", "Routed"),
+    }
+    println!("{:<LABEL_W$}: {:.1}", "Hyperdrive class", class);
+    println!("{:<LABEL_W$}: {:.2}", "Compression", e.compression);
+    println!(
+        "{:<LABEL_W$}: {:.1} h (~{:.1} d)",
+        "ETA",
+        e.hours,
+        e.hours / 24.0
+    );
+
+    if !e.missing_region.is_empty() {
+        warning(format!(
+            "No region data for {}; using the {:?} compression factor.",
+            e.missing_region.join(", "),
+            FALLBACK_REGION
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve;
+    use rusqlite::Connection;
+    use sw_galaxy_map_core::db::provision;
+    use sw_galaxy_map_core::routing::hyperspace::CompressionTable;
+
+    #[test]
+    fn endpoints_resolve_by_name_or_fid() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, Region, X, Y, arcgis_hash) VALUES
+                (1, 'Start', 'start', 'Core', 0.0, 0.0, 'h1'),
+                (2, 'End', 'end', 'Core', 30.0, 40.0, 'h2');
+            "#,
+        )
+        .expect("planets");
+        let table = CompressionTable::default();
+
+        let est = resolve(&con, "Start", "2", 1.0, None, &table).expect("name then FID");
+        assert_eq!((est.from.fid, est.to.fid), (1, 2));
+        assert!((est.direct - 50.0).abs() < 1e-9);
+
+        let est = resolve(&con, "1", "End", 1.0, None, &table).expect("FID then name");
+        assert_eq!((est.from.fid, est.to.fid), (1, 2));
+
+        assert!(resolve(&con, "Start", "99", 1.0, None, &table).is_err());
+    }
+}
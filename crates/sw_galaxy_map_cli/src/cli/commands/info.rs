@@ -1,12 +1,28 @@
-use crate::ui::info;
+use crate::cli::errors::not_found;
+use crate::ui::{info, warning};
 use anyhow::Result;
 use rusqlite::Connection;
-use sw_galaxy_map_core::db::queries::{find_planet_for_info, get_aliases};
-use sw_galaxy_map_core::model::PlanetSearchRow;
+use serde::Serialize;
+use sw_galaxy_map_core::db::queries::{
+    count_aliases, find_planet_by_fid, find_planet_for_info, get_aliases, list_planets_in_system,
+    list_routes, nearest_planets_excluding_fid, routes_passing_near,
+};
+use sw_galaxy_map_core::domain::RouteListSort;
+use sw_galaxy_map_core::model::{AliasRow, NearHit, Planet, PlanetSearchRow};
 use sw_galaxy_map_core::utils::normalize_text;
 
 const LABEL_W: usize = 24;
 
+/// A route "passes by" a planet when its polyline comes this close (parsec).
+/// Kept well above the default safety radius, which routes never cross.
+const RELATIONS_PASS_RADIUS: f64 = 5.0;
+
+/// Max routes listed per `--relations` group.
+const RELATIONS_CAP: usize = 10;
+
+/// Max planets listed by `--siblings`.
+const SIBLINGS_CAP: usize = 50;
+
 fn opt<T: ToString>(v: Option<T>) -> String {
     v.map(|x| x.to_string()).unwrap_or_else(|| "-".into())
 }
@@ -15,20 +31,45 @@ fn opt_str(v: Option<&str>) -> &str {
     v.unwrap_or("-")
 }
 
-pub(crate) fn resolve(con: &Connection, planet: &str) -> Result<(PlanetSearchRow, Vec<String>)> {
-    let pn = normalize_text(planet);
-    let p = match find_planet_for_info(con, &pn)? {
-        Some(p) => p,
-        None => anyhow::bail!("No planet found matching '{}'", planet),
+/// `0` disables the alias cap.
+fn alias_cap(alias_limit: usize) -> Option<usize> {
+    (alias_limit > 0).then_some(alias_limit)
+}
+
+fn more_items_line(hidden: usize) -> String {
+    format!("... and {} more", hidden)
+}
+
+/// Looks `planet` up by name, then alias, then (for an integer) FID.
+///
+/// Names win over FIDs, so a planet literally named "1138" is still found by name
+/// even if another planet has FID 1138.
+fn find_info_planet(con: &Connection, planet: &str) -> Result<Planet> {
+    let found = match find_planet_for_info(con, &normalize_text(planet))? {
+        Some(p) => Some(p),
+        None => find_planet_by_fid(con, planet)?,
     };
+    found.ok_or_else(|| not_found(format!("No planet found matching '{}'", planet)))
+}
+
+pub(crate) fn resolve(
+    con: &Connection,
+    planet: &str,
+    alias_limit: usize,
+) -> Result<(PlanetSearchRow, Vec<String>)> {
+    let p = find_info_planet(con, planet)?;
 
-    let aliases = get_aliases(con, p.fid)?
+    let mut aliases: Vec<String> = get_aliases(con, p.fid, alias_cap(alias_limit))?
         .into_iter()
         .map(|a| {
             let src = a.source.unwrap_or_else(|| "unknown".to_string());
             format!("{} ({})", a.alias, src)
         })
         .collect();
+    let hidden = count_aliases(con, p.fid)?.saturating_sub(aliases.len());
+    if hidden > 0 {
+        aliases.push(more_items_line(hidden));
+    }
 
     let row = PlanetSearchRow {
         fid: p.fid,
@@ -42,6 +83,7 @@ pub(crate) fn resolve(con: &Connection, planet: &str) -> Result<(PlanetSearchRow
         canon: p.canon.is_some(),
         legends: p.legends.is_some(),
         status: p.status,
+        score: None,
     };
 
     Ok((row, aliases))
@@ -49,9 +91,9 @@ pub(crate) fn resolve(con: &Connection, planet: &str) -> Result<(PlanetSearchRow
 
 pub(crate) fn resolve_by_fid(con: &Connection, fid: i64) -> Result<(PlanetSearchRow, Vec<String>)> {
     let p = sw_galaxy_map_core::db::queries::get_planet_by_fid(con, fid)?
-        .ok_or_else(|| anyhow::anyhow!("No planet found with fid {}", fid))?;
+        .ok_or_else(|| not_found(format!("No planet found with fid {}", fid)))?;
 
-    let aliases = get_aliases(con, p.fid)?
+    let aliases = get_aliases(con, p.fid, None)?
         .into_iter()
         .map(|a| {
             let src = a.source.unwrap_or_else(|| "unknown".to_string());
@@ -71,19 +113,168 @@ pub(crate) fn resolve_by_fid(con: &Connection, fid: i64) -> Result<(PlanetSearch
         canon: p.canon.is_some(),
         legends: p.legends.is_some(),
         status: p.status,
+        score: None,
     };
 
     Ok((row, aliases))
 }
 
-pub fn run(con: &Connection, planet: String) -> Result<()> {
-    let pn = normalize_text(&planet);
-    let p = match find_planet_for_info(con, &pn)? {
-        Some(p) => p,
-        None => anyhow::bail!("No planet found matching '{}'", planet),
+/// The `n` planets closest to (`x`, `y`), excluding `fid` itself.
+pub(crate) fn nearest(
+    con: &Connection,
+    fid: i64,
+    x: f64,
+    y: f64,
+    n: usize,
+) -> Result<Vec<NearHit>> {
+    nearest_planets_excluding_fid(con, fid, x, y, n as i64)
+}
+
+/// `#id FROM → TO` lines for routes starting or ending at `fid`, plus the total.
+fn endpoint_routes(con: &Connection, fid: i64) -> Result<(Vec<String>, usize)> {
+    let (from_rows, from_total) = list_routes(
+        con,
+        RELATIONS_CAP,
+        None,
+        Some(fid),
+        None,
+        None,
+        None,
+        RouteListSort::Id,
+    )?;
+    let (to_rows, to_total) = list_routes(
+        con,
+        RELATIONS_CAP,
+        None,
+        None,
+        Some(fid),
+        None,
+        None,
+        RouteListSort::Id,
+    )?;
+
+    let lines = from_rows
+        .iter()
+        .chain(to_rows.iter())
+        .take(RELATIONS_CAP)
+        .map(|r| format!("#{} {} → {}", r.id, r.from_planet_name, r.to_planet_name))
+        .collect();
+
+    Ok((lines, from_total + to_total))
+}
+
+fn print_relations(con: &Connection, fid: i64, x: f64, y: f64) -> Result<()> {
+    println!();
+    println!("Relations:");
+
+    let (endpoint, endpoint_total) = endpoint_routes(con, fid)?;
+    if endpoint.is_empty() {
+        println!("  Routes from/to here: -");
+    } else {
+        println!("  Routes from/to here:");
+        for line in &endpoint {
+            println!("    - {}", line);
+        }
+        if endpoint_total > endpoint.len() {
+            println!("    {}", more_items_line(endpoint_total - endpoint.len()));
+        }
+    }
+
+    let passing = routes_passing_near(con, x, y, RELATIONS_PASS_RADIUS, Some(fid))?;
+    if passing.is_empty() {
+        println!("  Routes passing within {:.1} pc: -", RELATIONS_PASS_RADIUS);
+    } else {
+        println!("  Routes passing within {:.1} pc:", RELATIONS_PASS_RADIUS);
+        for r in passing.iter().take(RELATIONS_CAP) {
+            println!(
+                "    - #{} {} → {} (closest {:.3} pc)",
+                r.route_id, r.from_planet_name, r.to_planet_name, r.distance
+            );
+        }
+        if passing.len() > RELATIONS_CAP {
+            println!("    {}", more_items_line(passing.len() - RELATIONS_CAP));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct InfoJson<'a> {
+    #[serde(flatten)]
+    planet: &'a Planet,
+    aliases: &'a [AliasRow],
+    aliases_total: usize,
+    wiki_url: String,
+}
+
+/// `info --json`: the planet row (column names as keys) plus its aliases.
+pub fn run_json(con: &Connection, planet: &str, alias_limit: usize) -> Result<()> {
+    let p = find_info_planet(con, planet)?;
+
+    let aliases = get_aliases(con, p.fid, alias_cap(alias_limit))?;
+    let aliases_total = count_aliases(con, p.fid)?;
+
+    let doc = InfoJson {
+        wiki_url: p.info_planet_url(),
+        planet: &p,
+        aliases: &aliases,
+        aliases_total,
+    };
+
+    println!("{}", serde_json::to_string_pretty(&doc)?);
+    Ok(())
+}
+
+fn print_siblings(con: &Connection, p: &Planet) -> Result<()> {
+    let has = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+    let scope = if has(&p.system) {
+        format!("system {}", opt_str(p.system.as_deref()))
+    } else if has(&p.sector) {
+        format!("sector {}", opt_str(p.sector.as_deref()))
+    } else {
+        "unknown system/sector".to_string()
     };
 
-    let aliases = get_aliases(con, p.fid)?;
+    // One extra row tells whether the list was truncated.
+    let mut rows = list_planets_in_system(
+        con,
+        p.system.as_deref(),
+        p.sector.as_deref(),
+        p.fid,
+        SIBLINGS_CAP + 1,
+    )?;
+    let truncated = rows.len() > SIBLINGS_CAP;
+    rows.truncate(SIBLINGS_CAP);
+
+    println!();
+    println!("Siblings ({}):", scope);
+    if rows.is_empty() {
+        println!("  (no siblings)");
+    }
+    for (fid, name) in &rows {
+        println!("  {:>8} {}", fid, name);
+    }
+    if truncated {
+        println!("  (first {} shown)", SIBLINGS_CAP);
+    }
+
+    Ok(())
+}
+
+pub fn run(
+    con: &Connection,
+    planet: String,
+    show_nearest: Option<usize>,
+    alias_limit: usize,
+    relations: bool,
+    siblings: bool,
+    open: bool,
+) -> Result<()> {
+    let p = find_info_planet(con, &planet)?;
+
+    let aliases = get_aliases(con, p.fid, alias_cap(alias_limit))?;
+    let hidden_aliases = count_aliases(con, p.fid)?.saturating_sub(aliases.len());
 
     info("Planet Information");
     println!();
@@ -154,10 +345,52 @@ pub fn run(con: &Connection, planet: String) -> Result<()> {
             let src = a.source.as_deref().unwrap_or("unknown");
             println!("  - {:<label_w_new$} ({})", a.alias, src);
         }
+        if hidden_aliases > 0 {
+            println!("  {}", more_items_line(hidden_aliases));
+        }
     }
 
     println!();
-    println!("{:<LABEL_W$}: {}", "Info URL", p.info_planet_url());
+    println!("{:<LABEL_W$}: {}", "Wiki", p.info_planet_url());
+
+    if let Some(n) = show_nearest {
+        let hits = nearest(con, p.fid, p.x, p.y, n)?;
+        println!();
+        if hits.is_empty() {
+            println!("Nearest planets: -");
+        } else {
+            println!("Nearest planets:");
+            for h in hits {
+                println!(
+                    "  - {:<label_w_new$} FID={:<8} dist={:>10.3}",
+                    h.planet, h.fid, h.distance
+                );
+            }
+        }
+    }
+
+    if relations {
+        print_relations(con, p.fid, p.x, p.y)?;
+    }
+
+    if siblings {
+        print_siblings(con, &p)?;
+    }
+
+    if open {
+        open_wiki(&p.info_planet_url());
+    }
 
     Ok(())
 }
+
+/// Best effort: a missing browser (headless box, no `xdg-open`) only warns.
+fn open_wiki(url: &str) {
+    if let Err(e) = open::that(url) {
+        println!();
+        warning(format!(
+            "Could not open a browser ({}). Wiki page: {}",
+            e, url
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 pub(crate) mod db;
+pub mod distance;
+pub mod hyperspace;
 pub mod info;
 pub mod near;
+pub mod planets;
 pub mod route;
 pub mod search;
 pub mod unknown;
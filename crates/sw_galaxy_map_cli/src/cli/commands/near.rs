@@ -1,10 +1,13 @@
+use crate::cli::errors::not_found;
 use crate::ui::{info, warning};
 use anyhow::Result;
 use rusqlite::Connection;
 use sw_galaxy_map_core::db::queries::{
-    find_planet_for_info, get_unknown_planet_by_fid, near_planets, near_planets_excluding_fid,
+    count_near_planets_excluding, find_planet_for_info, get_unknown_planet_by_fid,
+    near_planets_excluding,
 };
-use sw_galaxy_map_core::model::{NearHit, PlanetSearchRow};
+use sw_galaxy_map_core::domain::NearSort;
+use sw_galaxy_map_core::model::{NearFilter, NearHit, PlanetSearchRow};
 use sw_galaxy_map_core::utils::normalize_text;
 
 fn col_width<T: AsRef<str>>(items: &[T], min: usize) -> usize {
@@ -16,6 +19,14 @@ fn col_width<T: AsRef<str>>(items: &[T], min: usize) -> usize {
         .max(min)
 }
 
+/// `-` for a null or blank value.
+fn dash(v: &Option<String>) -> &str {
+    match v.as_deref() {
+        Some(s) if !s.trim().is_empty() => s,
+        _ => "-",
+    }
+}
+
 /// Hint shown to users about negative number parsing by clap.
 fn print_negative_hint() {
     println!("Tip: for negative coordinates use --x=-190 / --y=-190 (with '=')\n");
@@ -27,6 +38,11 @@ pub(crate) enum NearReference {
     Coordinates { x: f64, y: f64 },
 }
 
+/// Resolve the near reference and its hits.
+///
+/// Also returns the total number of planets within `range`, regardless of `limit`.
+/// `filter` (inner radius, continuity, sort) applies to both.
+/// FIDs in `exclude` are left out of both the hits and the total.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn resolve(
     con: &Connection,
@@ -37,11 +53,13 @@ pub(crate) fn resolve(
     x: Option<f64>,
     y: Option<f64>,
     limit: i64,
-) -> Result<(NearReference, Vec<NearHit>)> {
+    exclude: &[i64],
+    filter: &NearFilter,
+) -> Result<(NearReference, Vec<NearHit>, i64)> {
     if unknown {
         let fid = fid.ok_or_else(|| anyhow::anyhow!("--fid is required with --unknown"))?;
         let unknown_planet = get_unknown_planet_by_fid(con, fid)?
-            .ok_or_else(|| anyhow::anyhow!("No unknown planet found for fid {}", fid))?;
+            .ok_or_else(|| not_found(format!("No unknown planet found for fid {}", fid)))?;
         let x = unknown_planet.x;
         let y = unknown_planet.y;
         let name = unknown_planet.planet;
@@ -65,17 +83,24 @@ pub(crate) fn resolve(
             canon: false,
             legends: false,
             status: None,
+            score: None,
         });
 
-        let rows = near_planets(con, origin_x, origin_y, range, limit)?;
-        return Ok((reference, rows));
+        let rows = near_planets_excluding(con, exclude, origin_x, origin_y, range, filter, limit)?;
+        let total = count_near_planets_excluding(con, exclude, origin_x, origin_y, range, filter)?;
+        return Ok((reference, rows, total));
     }
 
     if let Some(planet_name) = planet {
         let pn = normalize_text(&planet_name);
         let p = match find_planet_for_info(con, &pn)? {
             Some(p) => p,
-            None => anyhow::bail!("No planet found matching '{}'", planet_name),
+            None => {
+                return Err(not_found(format!(
+                    "No planet found matching '{}'",
+                    planet_name
+                )));
+            }
         };
 
         let reference = NearReference::Planet(PlanetSearchRow {
@@ -90,10 +115,17 @@ pub(crate) fn resolve(
             canon: p.canon.is_some(),
             legends: p.legends.is_some(),
             status: p.status,
+            score: None,
         });
 
-        let rows = near_planets_excluding_fid(con, p.fid, p.x, p.y, range, limit)?;
-        return Ok((reference, rows));
+        let mut excluded = exclude.to_vec();
+        if !excluded.contains(&p.fid) {
+            excluded.push(p.fid);
+        }
+
+        let rows = near_planets_excluding(con, &excluded, p.x, p.y, range, filter, limit)?;
+        let total = count_near_planets_excluding(con, &excluded, p.x, p.y, range, filter)?;
+        return Ok((reference, rows, total));
     }
 
     let x = x.ok_or_else(|| {
@@ -110,8 +142,9 @@ pub(crate) fn resolve(
     })?;
 
     let reference = NearReference::Coordinates { x, y };
-    let rows = near_planets(con, x, y, range, limit)?;
-    Ok((reference, rows))
+    let rows = near_planets_excluding(con, exclude, x, y, range, filter, limit)?;
+    let total = count_near_planets_excluding(con, exclude, x, y, range, filter)?;
+    Ok((reference, rows, total))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -124,8 +157,11 @@ pub fn run(
     x: Option<f64>,
     y: Option<f64>,
     limit: i64,
+    exclude: &[i64],
+    filter: &NearFilter,
 ) -> Result<()> {
-    let (reference, rows) = resolve(con, r, unknown, fid, planet, x, y, limit)?;
+    let (reference, rows, total) =
+        resolve(con, r, unknown, fid, planet, x, y, limit, exclude, filter)?;
 
     match &reference {
         NearReference::Planet(p) => {
@@ -135,8 +171,24 @@ pub fn run(
             println!("Center: (X={:.3}, Y={:.3})", x, y);
         }
     }
-    println!("Radius: {:.3} parsecs", r);
+    match filter.min_r {
+        Some(m) => println!("Radius: {:.3} to {:.3} parsecs", m, r),
+        None => println!("Radius: {:.3} parsecs", r),
+    }
     println!("Limit: {}", limit);
+    if filter.sort != NearSort::Distance {
+        println!("Sort: {}", format!("{:?}", filter.sort).to_lowercase());
+    }
+    match (filter.canon, filter.legends) {
+        (true, true) => println!("Continuity: Canon and Legends"),
+        (true, false) => println!("Continuity: Canon"),
+        (false, true) => println!("Continuity: Legends"),
+        (false, false) => {}
+    }
+    if !exclude.is_empty() {
+        let list: Vec<String> = exclude.iter().map(|f| f.to_string()).collect();
+        println!("Excluded FIDs: {}", list.join(", "));
+    }
     println!();
 
     if rows.is_empty() {
@@ -149,8 +201,10 @@ pub fn run(
     }
 
     info(format!(
-        "Found the following planets within {:.3} parsecs:",
-        r
+        "{} planet(s) within {:.3} parsecs (showing {}):",
+        total,
+        r,
+        rows.len()
     ));
     println!();
 
@@ -163,33 +217,50 @@ pub fn run(
     let y_vals: Vec<String> = rows.iter().map(|p| format!("{:.3}", p.y)).collect();
     let d_vals: Vec<String> = rows.iter().map(|p| format!("{:.3}", p.distance)).collect();
 
+    let region_vals: Vec<&str> = rows.iter().map(|p| dash(&p.region)).collect();
+    let sector_vals: Vec<&str> = rows.iter().map(|p| dash(&p.sector)).collect();
+
     let x_w = col_width(&x_vals, "X (pc)".len());
     let y_w = col_width(&y_vals, "Y (pc)".len());
     let d_w = col_width(&d_vals, "Distance (pc)".len());
+    let region_w = col_width(&region_vals, "Region".len());
+    let sector_w = col_width(&sector_vals, "Sector".len());
 
     println!(
-        "{fid:>fid_w$}   {name:<name_w$}  {x:<x_w$}  {y:<y_w$}  {d:<d_w$}",
+        "{fid:>fid_w$}   {name:<name_w$}  {x:<x_w$}  {y:<y_w$}  {d:<d_w$}  {region:>region_w$}  {sector:>sector_w$}",
         fid = "FID",
         name = "Planet",
         x = "X (pc)",
         y = "Y (pc)",
         d = "Distance (pc)",
+        region = "Region",
+        sector = "Sector",
     );
     println!(
-        "{:-<fid_w$}   {:-<name_w$}  {:-<x_w$}  {:-<y_w$}  {:-<d_w$}",
-        "", "", "", "", ""
+        "{:-<fid_w$}   {:-<name_w$}  {:-<x_w$}  {:-<y_w$}  {:-<d_w$}  {:-<region_w$}  {:-<sector_w$}",
+        "", "", "", "", "", "", ""
     );
 
+    let shown = rows.len();
     for p in rows {
         println!(
-            "{fid:>fid_w$}   {name:<name_w$}  {x:>x_w$}  {y:>y_w$}  {d:>d_w$}",
+            "{fid:>fid_w$}   {name:<name_w$}  {x:>x_w$}  {y:>y_w$}  {d:>d_w$}  {region:>region_w$}  {sector:>sector_w$}",
             fid = p.fid,
             name = p.planet,
             x = format!("{:.3}", p.x),
             y = format!("{:.3}", p.y),
             d = format!("{:.3}", p.distance),
+            region = dash(&p.region),
+            sector = dash(&p.sector),
         );
     }
 
+    if total > shown as i64 {
+        warning(format!(
+            "({} shown of {}; increase --limit to see more)",
+            shown, total
+        ));
+    }
+
     Ok(())
 }
@@ -0,0 +1,107 @@
+use crate::cli::args::PlanetsCmd;
+use crate::cli::errors::invalid;
+use crate::ui::success;
+use anyhow::Result;
+use rusqlite::Connection;
+use sw_galaxy_map_core::db::local_planets::{self, LocalPlanetInput, LocalPlanetUpdate};
+use sw_galaxy_map_core::model::Planet;
+
+/// Empty string clears the field; `None` leaves it untouched.
+fn optional_text_update(value: Option<&str>) -> Option<Option<String>> {
+    value.map(|raw| {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    })
+}
+
+fn optional_text(value: Option<&str>) -> Option<String> {
+    optional_text_update(value).flatten()
+}
+
+fn print_planet_line(p: &Planet) {
+    println!(
+        "  FID={} | {} | X={:.3} | Y={:.3} | region={} | sector={}",
+        p.fid,
+        p.planet,
+        p.x,
+        p.y,
+        p.region.as_deref().unwrap_or("-"),
+        p.sector.as_deref().unwrap_or("-"),
+    );
+}
+
+/// Runs local-planet commands.
+pub fn run(con: &mut Connection, cmd: &PlanetsCmd) -> Result<()> {
+    match cmd {
+        PlanetsCmd::Add {
+            name,
+            x,
+            y,
+            fid,
+            region,
+            sector,
+            system,
+            grid,
+            canon,
+            legend,
+        } => {
+            let input = LocalPlanetInput {
+                fid: *fid,
+                planet: name.clone(),
+                x: *x,
+                y: *y,
+                region: optional_text(region.as_deref()),
+                sector: optional_text(sector.as_deref()),
+                system: optional_text(system.as_deref()),
+                grid: optional_text(grid.as_deref()),
+                canon: canon.map(i64::from),
+                legends: legend.map(i64::from),
+            };
+
+            let p = local_planets::add(con, &input)?;
+            success(format!("Local planet '{}' added (FID={})", p.planet, p.fid));
+            print_planet_line(&p);
+            Ok(())
+        }
+
+        PlanetsCmd::Edit {
+            fid,
+            planet,
+            x,
+            y,
+            region,
+            sector,
+            system,
+            grid,
+            canon,
+            legend,
+        } => {
+            let update = LocalPlanetUpdate {
+                planet: planet.clone(),
+                x: *x,
+                y: *y,
+                region: optional_text_update(region.as_deref()),
+                sector: optional_text_update(sector.as_deref()),
+                system: optional_text_update(system.as_deref()),
+                grid: optional_text_update(grid.as_deref()),
+                canon: canon.map(|v| Some(i64::from(v))),
+                legends: legend.map(|v| Some(i64::from(v))),
+            };
+
+            if update.is_empty() {
+                return Err(invalid(
+                    "No changes requested. Pass at least one edit flag.",
+                ));
+            }
+
+            let p = local_planets::edit(con, *fid, &update)?;
+            success(format!("Local planet FID={} updated", p.fid));
+            print_planet_line(&p);
+            Ok(())
+        }
+    }
+}
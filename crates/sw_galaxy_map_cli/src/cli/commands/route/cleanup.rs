@@ -5,8 +5,7 @@ use std::io::{self, Write};
 use crate::cli::color::Colors;
 use crate::ui::Style;
 
-fn confirm_destructive(action: &str) -> Result<bool> {
-    let style = Style::default();
+fn confirm_destructive(action: &str, style: Style) -> Result<bool> {
     let c = Colors::new(&style);
 
     eprintln!("{}", c.warn("⚠️  DESTRUCTIVE OPERATION"));
@@ -21,15 +20,14 @@ fn confirm_destructive(action: &str) -> Result<bool> {
     Ok(input.trim().eq_ignore_ascii_case("YES"))
 }
 
-pub(crate) fn run_clear(con: &mut Connection, yes: bool) -> Result<()> {
-    let style = Style::default();
+pub(crate) fn run_clear(con: &mut Connection, yes: bool, style: Style) -> Result<()> {
     let c = Colors::new(&style);
 
     if !yes {
         let action =
             "This will DELETE ALL routes, route waypoints, and route detours from the database.";
 
-        if !confirm_destructive(action)? {
+        if !confirm_destructive(action, style)? {
             bail!("Aborted by user.");
         }
     }
@@ -59,8 +57,7 @@ pub(crate) fn run_clear(con: &mut Connection, yes: bool) -> Result<()> {
     Ok(())
 }
 
-pub(crate) fn run_prune(con: &mut Connection) -> Result<()> {
-    let style = Style::default();
+pub(crate) fn run_prune(con: &mut Connection, style: Style) -> Result<()> {
     let c = Colors::new(&style);
 
     let tx = con.transaction()?;
@@ -1,26 +1,483 @@
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 use rusqlite::Connection;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
 
 use super::types::RouteComputeTuiData;
 use crate::cli::args::RouteComputeArgs;
+use crate::cli::errors::not_found;
+use crate::ui::warning;
 use sw_galaxy_map_core::db::queries;
-use sw_galaxy_map_core::model::Planet;
-use sw_galaxy_map_core::routing::collision::Obstacle;
-use sw_galaxy_map_core::routing::geometry::Point;
+use sw_galaxy_map_core::model::{
+    Planet, RouteDetourRow, RouteLegOptions, RouteOptionsJson, Waypoint,
+};
+use sw_galaxy_map_core::routing::collision::{Obstacle, min_corridor_clearance};
+use sw_galaxy_map_core::routing::geometry::{Point, polyline_length_waypoints_parsec};
+use sw_galaxy_map_core::routing::obstacle_cache::ObstacleCache;
 use sw_galaxy_map_core::routing::route_debug::debug_print_route;
-use sw_galaxy_map_core::routing::router::{Route, RouteOptions, compute_route};
+use sw_galaxy_map_core::routing::router::{
+    CandidateScore, DEFAULT_MAX_WAYPOINTS, DEFAULT_PROXIMITY_EXPONENT, DetourDecision, Route,
+    RouteOptions, RouteTraceEvent, clamp_proximity_exponent, compute_route, compute_route_seeded,
+    compute_route_traced, merge_close_waypoints,
+};
 use sw_galaxy_map_core::utils::normalize_text;
 
+/// Recompute cap for `--min-corridor`.
+const MAX_CORRIDOR_RECOMPUTES: usize = 5;
+
+/// Outcome of the `--min-corridor` check for one leg.
+struct CorridorCheck {
+    target: f64,
+    /// Narrowest gap of the final route (`None` = no obstacle nearby).
+    achieved: Option<f64>,
+    recomputes: usize,
+    /// Extra radius added to every obstacle for the final computation.
+    inflation: f64,
+}
+
+impl CorridorCheck {
+    fn satisfied(&self) -> bool {
+        self.achieved.is_none_or(|c| c >= self.target)
+    }
+}
+
+/// Endpoint moved onto a known waypoint by `--snap-endpoints`.
+struct Snap {
+    endpoint: &'static str,
+    waypoint: String,
+    moved: f64,
+}
+
+/// Replace `p` with the nearest waypoint within `radius`, recording the move.
+fn snap_point(
+    con: &Connection,
+    p: Point,
+    radius: f64,
+    endpoint: &'static str,
+    snaps: &mut Vec<Snap>,
+) -> Result<Point> {
+    let Some((wp, moved)) = queries::nearest_waypoint(con, p.x, p.y, radius)? else {
+        return Ok(p);
+    };
+    snaps.push(Snap {
+        endpoint,
+        waypoint: wp.name,
+        moved,
+    });
+    Ok(Point::new(wp.x, wp.y))
+}
+
+/// JSON-lines sink for `--trace-file`.
+///
+/// Each router iteration becomes one line tagged with the leg and the pass
+/// (`--min-corridor` may compute a leg several times).
+struct TraceWriter {
+    out: BufWriter<fs::File>,
+    leg: usize,
+    pass: usize,
+    /// First write error; the router callback cannot propagate it.
+    error: Option<std::io::Error>,
+}
+
+impl TraceWriter {
+    fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::File::create(path)
+            .with_context(|| format!("Unable to create trace file: {}", path.display()))?;
+        Ok(Self {
+            out: BufWriter::new(file),
+            leg: 0,
+            pass: 0,
+            error: None,
+        })
+    }
+
+    fn record(&mut self, ev: &RouteTraceEvent) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut line = serde_json::json!({ "leg": self.leg, "pass": self.pass });
+        if let (Some(obj), Ok(serde_json::Value::Object(ev))) =
+            (line.as_object_mut(), serde_json::to_value(ev))
+        {
+            obj.extend(ev);
+        }
+        if let Err(e) = writeln!(self.out, "{}", line) {
+            self.error = Some(e);
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e).context("Failed to write trace file");
+        }
+        self.out.flush().context("Failed to write trace file")
+    }
+}
+
+/// `compute_route` (or `compute_route_seeded` from `seed`), reporting each
+/// iteration to `trace` when set.
+fn route_with_trace(
+    start: Point,
+    end: Point,
+    obstacles: &[Obstacle],
+    opts: RouteOptions,
+    seed: Option<&Route>,
+    trace: Option<&mut TraceWriter>,
+) -> Result<Route> {
+    let Some(t) = trace else {
+        return match seed {
+            Some(seed) => compute_route_seeded(seed.clone(), obstacles, opts, None),
+            None => compute_route(start, end, obstacles, opts),
+        };
+    };
+    t.pass += 1;
+    let mut on_iteration = |ev: &RouteTraceEvent| t.record(ev);
+    let route = match seed {
+        Some(seed) => compute_route_seeded(seed.clone(), obstacles, opts, Some(&mut on_iteration)),
+        None => compute_route_traced(start, end, obstacles, opts, &mut on_iteration),
+    };
+    // Keep the partial trace of a failed computation: it is the interesting one.
+    t.finish()?;
+    route
+}
+
+/// `--waypoint` value resolved by id (when numeric) or by normalized name.
+fn resolve_via(con: &Connection, key: &str) -> Result<Waypoint> {
+    let wp = match key.trim().parse::<i64>() {
+        Ok(id) => queries::find_waypoint_by_id(con, id)?,
+        Err(_) => queries::find_waypoint_by_norm(con, &normalize_text(key))?,
+    };
+    wp.ok_or_else(|| not_found(format!("Waypoint not found: {}", key)))
+}
+
+/// Route `start` → `vias...` → `end`, one router run per stretch, joined into one route.
+///
+/// Detour segment indexes and iterations are shifted so they stay unique along the
+/// joined polyline. `seed` only applies to a direct route (no vias).
+fn route_through(
+    start: Point,
+    vias: &[Point],
+    end: Point,
+    obstacles: &[Obstacle],
+    opts: RouteOptions,
+    seed: Option<&Route>,
+    mut trace: Option<&mut TraceWriter>,
+) -> Result<Route> {
+    let mut stops = Vec::with_capacity(vias.len() + 2);
+    stops.push(start);
+    stops.extend_from_slice(vias);
+    stops.push(end);
+    stops.dedup();
+
+    if stops.len() == 2 {
+        return route_with_trace(start, end, obstacles, opts, seed, trace);
+    }
+
+    let mut joined = Route {
+        waypoints: vec![start],
+        length: 0.0,
+        iterations: 0,
+        detours: Vec::new(),
+    };
+    for stretch in stops.windows(2) {
+        let part = route_with_trace(
+            stretch[0],
+            stretch[1],
+            obstacles,
+            opts,
+            None,
+            trace.as_deref_mut(),
+        )?;
+        let segment_offset = joined.waypoints.len() - 1;
+        let iteration_offset = joined.iterations;
+
+        joined.detours.extend(part.detours.into_iter().map(|mut d| {
+            d.segment_index += segment_offset;
+            d.iteration += iteration_offset;
+            d
+        }));
+        joined.waypoints.extend(part.waypoints.into_iter().skip(1));
+        joined.length += part.length;
+        joined.iterations += part.iterations;
+    }
+
+    Ok(joined)
+}
+
 struct ComputedLeg {
     from_p: Planet,
     to_p: Planet,
     route: Route,
-    route_id: i64,
+    route_id: Option<i64>,
+    /// Detours of the leg (taken from the mirror route when reused).
+    detour_count: usize,
+    /// Set when the leg was served by reversing the persisted TO→FROM route.
+    mirrored: bool,
+    corridor: Option<CorridorCheck>,
+    snaps: Vec<Snap>,
+    /// Waypoints dropped by `--min-spacing` (`None` when not requested).
+    merged: Option<usize>,
+    /// Planets forced in as obstacles by `--avoid`.
+    avoided: Vec<String>,
+    /// Waypoints the route was pinned through by `--waypoint`.
+    vias: Vec<String>,
+    /// Wall-clock time of the computation (`None` when the mirror route was reused).
+    compute_ms: Option<i64>,
+    /// Stored route the computation was warm-started from (`--seed-route`).
+    seeded: Option<i64>,
+}
+
+fn same_options(stored: &RouteOptionsJson, opts: &RouteOptions, leg: &RouteLegOptions) -> bool {
+    const EPS: f64 = 1e-9;
+    let close = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() < EPS,
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    (stored.clearance - opts.clearance).abs() < EPS
+        && stored.max_iters == opts.max_iters
+        && stored.max_waypoints.unwrap_or(DEFAULT_MAX_WAYPOINTS) == opts.max_waypoints
+        && stored.max_offset_tries == opts.max_offset_tries
+        && (stored.offset_growth - opts.offset_growth).abs() < EPS
+        && (stored.turn_weight - opts.turn_weight).abs() < EPS
+        && (stored.back_weight - opts.back_weight).abs() < EPS
+        && (stored.proximity_weight - opts.proximity_weight).abs() < EPS
+        && (stored.proximity_margin - opts.proximity_margin).abs() < EPS
+        && (clamp_proximity_exponent(
+            stored
+                .proximity_exponent
+                .unwrap_or(DEFAULT_PROXIMITY_EXPONENT),
+        ) - clamp_proximity_exponent(opts.proximity_exponent))
+        .abs()
+            < EPS
+        && stored.explore_both_sides == opts.explore_both_sides
+        && stored.metric == opts.metric
+        && stored.leg.vias == leg.vias
+        && stored.leg.avoid == leg.avoid
+        && close(stored.leg.snap_radius, leg.snap_radius)
+        && close(stored.leg.min_spacing, leg.min_spacing)
+        && close(stored.leg.safety, leg.safety)
+        && close(stored.leg.bbox_margin, leg.bbox_margin)
+        && stored.leg.max_obstacles == leg.max_obstacles
+}
+
+/// Load the persisted TO→FROM route and reverse it, if it matches the current options.
+///
+/// `leg` describes the FROM→TO request; the mirror must have been pinned through
+/// the same waypoints in reverse order.
+///
+/// Returns the reversed route, the mirror route id and its detour count.
+fn mirror_route(
+    con: &Connection,
+    from_p: &Planet,
+    to_p: &Planet,
+    opts: &RouteOptions,
+    leg: &RouteLegOptions,
+) -> Result<Option<(Route, i64, usize)>> {
+    let Some(row) = queries::get_route_by_from_to(con, to_p.fid, from_p.fid)? else {
+        return Ok(None);
+    };
+    if row.status != "ok" {
+        return Ok(None);
+    }
+    let reversed = RouteLegOptions {
+        vias: leg.vias.iter().rev().cloned().collect(),
+        ..leg.clone()
+    };
+    let matches = serde_json::from_str::<RouteOptionsJson>(&row.options_json)
+        .is_ok_and(|stored| same_options(&stored, opts, &reversed));
+    if !matches {
+        return Ok(None);
+    }
+    let Some(loaded) = queries::load_route(con, row.id)? else {
+        return Ok(None);
+    };
+
+    let mirror = loaded.reversed();
+    let waypoints = mirror
+        .waypoints
+        .iter()
+        .map(|w| Point::new(w.x, w.y))
+        .collect::<Vec<_>>();
+    let length = mirror
+        .route
+        .length
+        .unwrap_or_else(|| polyline_length_waypoints_parsec(&waypoints, |p| (p.x, p.y)));
+
+    let route = Route {
+        waypoints,
+        length,
+        iterations: mirror.route.iterations.unwrap_or(0).max(0) as usize,
+        detours: Vec::new(),
+    };
+
+    Ok(Some((route, row.id, mirror.detours.len())))
+}
+
+fn detour_from_row(d: &RouteDetourRow) -> DetourDecision {
+    DetourDecision {
+        iteration: d.iteration.max(0) as usize,
+        segment_index: d.segment_index.max(0) as usize,
+        obstacle_id: d.obstacle_id,
+        obstacle_name: d.obstacle_name.clone(),
+        obstacle_center: Point::new(d.obstacle_x, d.obstacle_y),
+        obstacle_radius: d.obstacle_radius,
+        closest_t: d.closest_t,
+        closest_q: Point::new(d.closest_qx, d.closest_qy),
+        closest_dist: d.closest_dist,
+        offset_used: d.offset_used,
+        waypoint: Point::new(d.wp_x, d.wp_y),
+        score: CandidateScore {
+            base: d.score_base,
+            turn: d.score_turn,
+            back: d.score_back,
+            proximity: d.score_proximity,
+        },
+        tries_used: d.tries_used.unwrap_or(0).max(0) as usize,
+        tries_exhausted: d.tries_exhausted != 0,
+    }
+}
+
+/// Loads `--seed-route` as the starting polyline of the FROM→TO leg.
+///
+/// Returns `None` after a warning when the stored route joins other planets, was
+/// pinned through other waypoints or avoided other planets (`leg`), or has no usable
+/// polyline. Other router options may differ: `compute_route_seeded` repairs the
+/// seed under the current ones. The seed's endpoints are replaced by `start`/`end`,
+/// which may have been snapped.
+fn load_seed_route(
+    con: &Connection,
+    id: i64,
+    from_p: &Planet,
+    to_p: &Planet,
+    start: Point,
+    end: Point,
+    leg: &RouteLegOptions,
+) -> Result<Option<Route>> {
+    let loaded = queries::load_route(con, id)?
+        .ok_or_else(|| not_found(format!("Route not found: {}", id)))?;
+
+    if loaded.route.from_planet_fid != from_p.fid || loaded.route.to_planet_fid != to_p.fid {
+        warning(format!(
+            "Seed route #{} goes {} → {}, not {} → {}; computing from scratch.",
+            id,
+            loaded.route.from_planet_name,
+            loaded.route.to_planet_name,
+            from_p.planet,
+            to_p.planet
+        ));
+        return Ok(None);
+    }
+    let matches = serde_json::from_str::<RouteOptionsJson>(&loaded.route.options_json)
+        .is_ok_and(|stored| stored.leg.vias == leg.vias && stored.leg.avoid == leg.avoid);
+    if !matches {
+        warning(format!(
+            "Seed route #{} used different --waypoint/--avoid planets; computing from scratch.",
+            id
+        ));
+        return Ok(None);
+    }
+    if loaded.waypoints.len() < 2 {
+        warning(format!(
+            "Seed route #{} has no stored polyline ({}); computing from scratch.",
+            id, loaded.route.status
+        ));
+        return Ok(None);
+    }
+
+    let mut waypoints = loaded
+        .waypoints
+        .iter()
+        .map(|w| Point::new(w.x, w.y))
+        .collect::<Vec<_>>();
+    let last = waypoints.len() - 1;
+    waypoints[0] = start;
+    waypoints[last] = end;
+
+    Ok(Some(Route {
+        waypoints,
+        length: 0.0,
+        iterations: 0,
+        detours: loaded.detours.iter().map(detour_from_row).collect(),
+    }))
+}
+
+/// Routes the leg, then enforces `--min-corridor` (`min_corridor`) by recomputing
+/// with wider obstacles.
+#[allow(clippy::too_many_arguments)]
+fn route_with_corridor(
+    start: Point,
+    via_points: &[Point],
+    end: Point,
+    obstacles: &[Obstacle],
+    opts: RouteOptions,
+    min_corridor: Option<f64>,
+    seed: Option<&Route>,
+    mut trace: Option<&mut TraceWriter>,
+) -> Result<(Route, Option<CorridorCheck>)> {
+    let mut route = route_through(
+        start,
+        via_points,
+        end,
+        obstacles,
+        opts,
+        seed,
+        trace.as_deref_mut(),
+    )?;
+
+    // Enforce --min-corridor: per-detour clearance only applies to obstacles the
+    // route actually hits, so widen every obstacle by the shortfall and recompute.
+    let corridor = match min_corridor {
+        None => None,
+        Some(target) => {
+            let mut achieved = min_corridor_clearance(&route.waypoints, obstacles);
+            let mut recomputes = 0;
+            let mut inflation = 0.0;
+
+            while let Some(gap) = achieved.filter(|g| *g < target) {
+                if recomputes >= MAX_CORRIDOR_RECOMPUTES {
+                    break;
+                }
+                recomputes += 1;
+                inflation += (target - gap).max(target * 0.1).max(1e-3);
+
+                let inflated = obstacles
+                    .iter()
+                    .map(|o| o.inflated(inflation))
+                    .collect::<Vec<_>>();
+                route = route_through(
+                    start,
+                    via_points,
+                    end,
+                    &inflated,
+                    opts,
+                    seed,
+                    trace.as_deref_mut(),
+                )?;
+                achieved = min_corridor_clearance(&route.waypoints, obstacles);
+            }
+
+            Some(CorridorCheck {
+                target,
+                achieved,
+                recomputes,
+                inflation,
+            })
+        }
+    };
+
+    Ok((route, corridor))
 }
 
 fn compute_leg(
     con: &mut Connection,
     args: &RouteComputeArgs,
+    cache: &mut ObstacleCache,
+    trace: Option<&mut TraceWriter>,
     from: &str,
     to: &str,
 ) -> Result<ComputedLeg> {
@@ -29,13 +486,22 @@ fn compute_leg(
     let to_norm = normalize_text(to);
 
     let from_p = queries::find_planet_for_info(con, &from_norm)?
-        .ok_or_else(|| anyhow::anyhow!("Planet not found: {}", from))?;
+        .ok_or_else(|| not_found(format!("Planet not found: {}", from)))?;
 
     let to_p = queries::find_planet_for_info(con, &to_norm)?
-        .ok_or_else(|| anyhow::anyhow!("Planet not found: {}", to))?;
+        .ok_or_else(|| not_found(format!("Planet not found: {}", to)))?;
 
-    let start = Point::new(from_p.x, from_p.y);
-    let end = Point::new(to_p.x, to_p.y);
+    let mut start = Point::new(from_p.x, from_p.y);
+    let mut end = Point::new(to_p.x, to_p.y);
+
+    let mut snaps = Vec::new();
+    if args.snap_endpoints {
+        if !args.snap_radius.is_finite() || args.snap_radius <= 0.0 {
+            bail!("--snap-radius must be a finite number > 0");
+        }
+        start = snap_point(con, start, args.snap_radius, "start", &mut snaps)?;
+        end = snap_point(con, end, args.snap_radius, "end", &mut snaps)?;
+    }
 
     if start == end {
         bail!(
@@ -44,17 +510,34 @@ fn compute_leg(
         );
     }
 
-    // 2) Fetch candidate obstacles in a bbox around the segment (cheap prefilter)
-    let min_x = start.x.min(end.x) - args.bbox_margin;
-    let max_x = start.x.max(end.x) + args.bbox_margin;
-    let min_y = start.y.min(end.y) - args.bbox_margin;
-    let max_y = start.y.max(end.y) + args.bbox_margin;
+    let vias = args
+        .via
+        .iter()
+        .map(|key| resolve_via(con, key))
+        .collect::<Result<Vec<_>>>()?;
+    let via_points = vias
+        .iter()
+        .map(|w| Point::new(w.x, w.y))
+        .collect::<Vec<_>>();
+
+    // 2) Fetch candidate obstacles in a bbox around the segment (cheap prefilter);
+    // forced waypoints may lie outside it, so the box grows to cover them.
+    let stops = || {
+        std::iter::once(start)
+            .chain(via_points.iter().copied())
+            .chain([end])
+    };
+    let min_x = stops().map(|p| p.x).fold(f64::INFINITY, f64::min) - args.bbox_margin;
+    let max_x = stops().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max) + args.bbox_margin;
+    let min_y = stops().map(|p| p.y).fold(f64::INFINITY, f64::min) - args.bbox_margin;
+    let max_y = stops().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max) + args.bbox_margin;
 
     // Prefer DB-annotated obstacles (waypoint_planets.role), but fall back to the legacy
     // behavior if none are configured yet.
     let mut obstacles: Vec<Obstacle> = Vec::new();
 
-    let raw_db = queries::list_routing_obstacles_in_bbox(
+    // Legs of one trip often overlap, so the bbox query goes through the cache.
+    let raw_db = cache.routing_obstacles(
         con,
         min_x,
         max_x,
@@ -75,6 +558,7 @@ fn compute_leg(
                 name: ob.planet.clone(),
                 center: Point::new(ob.x, ob.y),
                 radius: ob.radius,
+                radius_y: None,
             });
         }
     } else {
@@ -90,46 +574,224 @@ fn compute_leg(
                 name: name.clone(),
                 center: Point::new(x, y),
                 radius: args.safety,
+                radius_y: None,
             });
         }
     }
 
+    // 2b) --avoid: force the named planets in, even outside the bbox or unflagged.
+    let mut avoided = Vec::new();
+    for name in &args.avoid {
+        let p = queries::find_planet_for_info(con, &normalize_text(name))?
+            .ok_or_else(|| not_found(format!("Planet not found: {}", name)))?;
+        if p.fid == from_p.fid || p.fid == to_p.fid {
+            continue;
+        }
+        if !obstacles.iter().any(|o| o.id == p.fid) {
+            obstacles.push(Obstacle {
+                id: p.fid,
+                name: p.planet.clone(),
+                center: Point::new(p.x, p.y),
+                radius: args.safety,
+                radius_y: None,
+            });
+        }
+        if !avoided.contains(&p.planet) {
+            avoided.push(p.planet);
+        }
+    }
+
     // 3) Build routing options
     let opts = RouteOptions {
         clearance: args.clearance,
         max_iters: args.max_iters,
+        max_waypoints: args.max_waypoints,
         max_offset_tries: args.max_offset_tries,
         offset_growth: args.offset_growth,
         turn_weight: args.turn_weight,
         back_weight: args.back_weight,
         proximity_weight: args.proximity_weight,
         proximity_margin: args.proximity_margin,
+        proximity_exponent: clamp_proximity_exponent(args.proximity_exponent),
+        explore_both_sides: args.explore_both_sides,
+        metric: args.metric,
+    };
+
+    if !args.proximity_exponent.is_finite() {
+        bail!("--proximity-exponent must be a finite number");
+    }
+    if let Some(w) = args.min_corridor
+        && (!w.is_finite() || w < 0.0)
+    {
+        bail!("--min-corridor must be a finite number >= 0");
+    }
+    if let Some(d) = args.min_spacing
+        && (!d.is_finite() || d <= 0.0)
+    {
+        bail!("--min-spacing must be a finite number > 0");
+    }
+
+    // Persisted with the route, so it is only reused for the same request.
+    let mut avoid = avoided.clone();
+    avoid.sort();
+    let leg = RouteLegOptions {
+        vias: vias.iter().map(|w| w.name.clone()).collect(),
+        avoid,
+        snap_radius: args.snap_endpoints.then_some(args.snap_radius),
+        min_spacing: args.min_spacing,
+        safety: Some(args.safety),
+        bbox_margin: Some(args.bbox_margin),
+        max_obstacles: Some(args.max_obstacles),
+    };
+
+    // 4) Compute route (or reuse the mirror one when --symmetric). The mirror must
+    // have been computed with the same options, obstacle inputs, waypoints,
+    // avoidances and snapping.
+    if args.symmetric
+        && let Some((route, mirror_id, detour_count)) =
+            mirror_route(con, &from_p, &to_p, &opts, &leg)?
+    {
+        let corridor = args.min_corridor.map(|target| CorridorCheck {
+            target,
+            achieved: min_corridor_clearance(&route.waypoints, &obstacles),
+            recomputes: 0,
+            inflation: 0.0,
+        });
+
+        // A mirror that misses the corridor is recomputed below instead.
+        if corridor.as_ref().is_none_or(CorridorCheck::satisfied) {
+            return Ok(ComputedLeg {
+                from_p,
+                to_p,
+                route,
+                route_id: Some(mirror_id),
+                detour_count,
+                mirrored: true,
+                corridor,
+                snaps,
+                merged: None,
+                avoided,
+                vias: leg.vias,
+                compute_ms: None,
+                seeded: None,
+            });
+        }
+    }
+
+    // 4b) --seed-route: warm-start from a stored polyline of the same leg.
+    let seed = match args.seed_route {
+        Some(id) if !vias.is_empty() => {
+            warning(format!(
+                "Seed route #{} ignored: --waypoint routes are computed per stretch.",
+                id
+            ));
+            None
+        }
+        Some(id) => load_seed_route(con, id, &from_p, &to_p, start, end, &leg)?.map(|r| (id, r)),
+        None => None,
+    };
+
+    let started = Instant::now();
+    let (mut route, corridor) = match route_with_corridor(
+        start,
+        &via_points,
+        end,
+        &obstacles,
+        opts,
+        args.min_corridor,
+        seed.as_ref().map(|(_, r)| r),
+        trace,
+    ) {
+        Ok(v) => v,
+        // Record the failure so `route last` / `route list --status failed` show it.
+        Err(e) if args.persist && !args.dry_run => {
+            let id = queries::persist_route_failure(
+                con,
+                from_p.fid,
+                to_p.fid,
+                opts,
+                &leg,
+                &format!("{:#}", e),
+            )?;
+            return Err(e.context(format!(
+                "Route {} → {} recorded as failed (route #{})",
+                from_p.planet, to_p.planet, id
+            )));
+        }
+        Err(e) => return Err(e),
     };
 
-    // 4) Compute route
-    let route = compute_route(start, end, &obstacles, opts)?;
+    // 4c) --min-spacing: shortcuts are checked against the obstacles the final route
+    // was computed with, so the corridor guarantee is kept as well.
+    let merged = args.min_spacing.map(|d| {
+        let inflation = corridor.as_ref().map_or(0.0, |c| c.inflation);
+        let checked = obstacles
+            .iter()
+            .map(|o| o.inflated(inflation))
+            .collect::<Vec<_>>();
+        merge_close_waypoints(&mut route, d, &checked, opts.metric)
+    });
+    let compute_ms = i64::try_from(started.elapsed().as_millis()).unwrap_or(i64::MAX);
+
+    // 5) Persist route (unless --persist=false or --dry-run)
+    let route_id = if args.persist && !args.dry_run {
+        Some(queries::persist_route(
+            con,
+            from_p.fid,
+            to_p.fid,
+            opts,
+            &leg,
+            &route,
+            args.coord_precision,
+            Some(compute_ms),
+        )?)
+    } else {
+        None
+    };
 
-    // 5) Persist route
-    let route_id = queries::persist_route(con, from_p.fid, to_p.fid, opts, &route)?;
+    let detour_count = route.detours.len();
 
     Ok(ComputedLeg {
         from_p,
         to_p,
         route,
         route_id,
+        detour_count,
+        mirrored: false,
+        corridor,
+        snaps,
+        merged,
+        avoided,
+        vias: leg.vias,
+        compute_ms: Some(compute_ms),
+        seeded: seed.map(|(id, _)| id),
     })
 }
 
 pub(crate) fn run_compute(con: &mut Connection, args: &RouteComputeArgs) -> Result<()> {
+    if !args.via.is_empty() && args.planets.len() > 2 {
+        bail!("--waypoint is only supported for a single FROM→TO leg");
+    }
+
     let mut total_length = 0.0;
     let mut total_waypoints = 0usize;
     let mut total_detours = 0usize;
     let mut route_ids = Vec::new();
+    let mut cache = ObstacleCache::default();
+    let mut trace = args
+        .trace_file
+        .as_deref()
+        .map(TraceWriter::create)
+        .transpose()?;
 
     for (idx, leg) in args.planets.windows(2).enumerate() {
         let from = &leg[0];
         let to = &leg[1];
-        let computed = compute_leg(con, args, from, to)?;
+        if let Some(t) = trace.as_mut() {
+            t.leg = idx + 1;
+            t.pass = 0;
+        }
+        let computed = compute_leg(con, args, &mut cache, trace.as_mut(), from, to)?;
 
         if args.planets.len() > 2 {
             println!(
@@ -146,18 +808,65 @@ pub(crate) fn run_compute(con: &mut Connection, args: &RouteComputeArgs) -> Resu
             );
         }
 
-        println!("Route ID: {}", computed.route_id);
+        match computed.route_id {
+            Some(id) if computed.mirrored => {
+                println!("Route ID: {} (reversed from persisted mirror route)", id)
+            }
+            Some(id) => println!("Route ID: {}", id),
+            None if args.dry_run => println!("Route ID: - (dry-run: not persisted)"),
+            None => println!("Route ID: - (not persisted)"),
+        }
+        for snap in &computed.snaps {
+            println!(
+                "Snapped {} to waypoint '{}' (moved {:.3} parsec)",
+                snap.endpoint, snap.waypoint, snap.moved
+            );
+        }
+        if let Some(id) = computed.seeded {
+            println!(
+                "Seeded from route #{} ({} repair iteration(s))",
+                id, computed.route.iterations
+            );
+        }
+        if !computed.vias.is_empty() {
+            println!("Via: {}", computed.vias.join(" → "));
+        }
+        if !computed.avoided.is_empty() {
+            println!("Avoiding: {}", computed.avoided.join(", "));
+        }
         println!("Waypoints: {}", computed.route.waypoints.len());
-        println!("Detours: {}", computed.route.detours.len());
+        if let (Some(n), Some(d)) = (computed.merged, args.min_spacing) {
+            println!("Merged waypoints: {} (closer than {:.3} parsec)", n, d);
+        }
+        println!("Detours: {}", computed.detour_count);
+        if let Some(d) = computed
+            .route
+            .detours
+            .iter()
+            .max_by(|a, b| a.score.total().total_cmp(&b.score.total()))
+        {
+            println!(
+                "Worst detour: {} [{}] score={:.3}",
+                d.obstacle_name,
+                d.obstacle_id,
+                d.score.total()
+            );
+        }
         println!("Length: {:.3} parsec", computed.route.length);
+        if let Some(ms) = computed.compute_ms {
+            println!("Computed in {} ms", ms);
+        }
+        if let Some(check) = &computed.corridor {
+            print_corridor(check);
+        }
         if args.planets.len() > 2 && idx + 1 < args.planets.len() - 1 {
             println!();
         }
 
         total_length += computed.route.length;
         total_waypoints += computed.route.waypoints.len();
-        total_detours += computed.route.detours.len();
-        route_ids.push(computed.route_id);
+        total_detours += computed.detour_count;
+        route_ids.extend(computed.route_id);
 
         // Debug details (only in debug builds)
         debug_print_route(&computed.route);
@@ -176,12 +885,38 @@ pub(crate) fn run_compute(con: &mut Connection, args: &RouteComputeArgs) -> Resu
             total_detours,
             total_length
         );
-        println!("Route IDs: {}", route_ids_txt);
+        if !route_ids.is_empty() {
+            println!("Route IDs: {}", route_ids_txt);
+        }
+    }
+
+    if let (Some(t), Some(path)) = (trace.as_mut(), args.trace_file.as_deref()) {
+        t.finish()?;
+        eprintln!("Trace written to {}", path.display());
     }
 
     Ok(())
 }
 
+fn print_corridor(check: &CorridorCheck) {
+    let achieved = match check.achieved {
+        Some(gap) => format!("{:.3} parsec", gap),
+        None => "unobstructed".to_string(),
+    };
+
+    println!(
+        "Min corridor: {} (target {:.3}, obstacle inflation {:.3}, {} recompute(s))",
+        achieved, check.target, check.inflation, check.recomputes
+    );
+
+    if !check.satisfied() {
+        warning(format!(
+            "Corridor target not reached after {} recompute(s); route kept as computed.",
+            check.recomputes
+        ));
+    }
+}
+
 pub(crate) fn resolve_compute_for_tui(
     con: &mut Connection,
     args: &RouteComputeArgs,
@@ -192,9 +927,218 @@ pub(crate) fn resolve_compute_for_tui(
 
     let from = &args.planets[0];
     let to = &args.planets[1];
-    let computed = compute_leg(con, args, from, to)?;
+    let computed = compute_leg(con, args, &mut ObstacleCache::default(), None, from, to)?;
+    let Some(route_id) = computed.route_id else {
+        bail!("TUI route compute requires a persisted route (drop --persist=false / --dry-run).");
+    };
 
-    Ok(RouteComputeTuiData {
-        route_id: computed.route_id,
-    })
+    Ok(RouteComputeTuiData { route_id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_seed_route, mirror_route, same_options};
+    use rusqlite::Connection;
+    use sw_galaxy_map_core::db::{provision, queries};
+    use sw_galaxy_map_core::model::{RouteLegOptions, RouteOptionsJson};
+    use sw_galaxy_map_core::routing::collision::{Obstacle, min_corridor_clearance};
+    use sw_galaxy_map_core::routing::geometry::Point;
+    use sw_galaxy_map_core::routing::router::{RouteOptions, compute_route, compute_route_seeded};
+
+    #[test]
+    fn same_options_compares_the_leg_inputs() {
+        let opts = RouteOptions::default();
+        // Stored before the leg fields existed: they read back empty.
+        let stored: RouteOptionsJson = serde_json::from_value(serde_json::json!({
+            "clearance": opts.clearance,
+            "max_iters": opts.max_iters,
+            "max_offset_tries": opts.max_offset_tries,
+            "offset_growth": opts.offset_growth,
+            "turn_weight": opts.turn_weight,
+            "back_weight": opts.back_weight,
+            "proximity_weight": opts.proximity_weight,
+            "proximity_margin": opts.proximity_margin,
+        }))
+        .expect("legacy options");
+
+        let plain = RouteLegOptions::default();
+        assert!(same_options(&stored, &opts, &plain));
+
+        for leg in [
+            RouteLegOptions {
+                vias: vec!["Gate".to_string()],
+                ..Default::default()
+            },
+            RouteLegOptions {
+                avoid: vec!["Kessel".to_string()],
+                ..Default::default()
+            },
+            RouteLegOptions {
+                snap_radius: Some(1.0),
+                ..Default::default()
+            },
+            RouteLegOptions {
+                min_spacing: Some(0.5),
+                ..Default::default()
+            },
+            RouteLegOptions {
+                safety: Some(2.0),
+                ..Default::default()
+            },
+            RouteLegOptions {
+                bbox_margin: Some(80.0),
+                ..Default::default()
+            },
+            RouteLegOptions {
+                max_obstacles: Some(8000),
+                ..Default::default()
+            },
+        ] {
+            assert!(!same_options(&stored, &opts, &leg), "{:?}", leg);
+
+            let with_leg = RouteOptionsJson {
+                leg: leg.clone(),
+                ..stored.clone()
+            };
+            assert!(same_options(&with_leg, &opts, &leg), "{:?}", leg);
+            assert!(!same_options(&with_leg, &opts, &plain), "{:?}", leg);
+        }
+    }
+
+    #[test]
+    fn same_options_clamps_the_proximity_exponent() {
+        let opts = RouteOptions {
+            proximity_exponent: 4.0,
+            ..Default::default()
+        };
+        let stored: RouteOptionsJson = serde_json::from_value(serde_json::json!({
+            "clearance": opts.clearance,
+            "max_iters": opts.max_iters,
+            "max_offset_tries": opts.max_offset_tries,
+            "offset_growth": opts.offset_growth,
+            "turn_weight": opts.turn_weight,
+            "back_weight": opts.back_weight,
+            "proximity_weight": opts.proximity_weight,
+            "proximity_margin": opts.proximity_margin,
+            "proximity_exponent": 10.0,
+        }))
+        .expect("options");
+        let leg = RouteLegOptions::default();
+
+        assert!(same_options(&stored, &opts, &leg));
+        let unclamped = RouteOptions {
+            proximity_exponent: 10.0,
+            ..opts
+        };
+        assert!(same_options(&stored, &unclamped, &leg));
+        let linear = RouteOptions {
+            proximity_exponent: 1.0,
+            ..opts
+        };
+        assert!(!same_options(&stored, &linear, &leg));
+    }
+
+    #[test]
+    fn seed_from_other_options_is_repaired() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Start', 'start', 0.0, 0.0, 'h1'),
+                (2, 'End', 'end', 100.0, 0.0, 'h2'),
+                (3, 'Block', 'block', 50.0, 0.0, 'h3');
+            "#,
+        )
+        .expect("planets");
+        let (start, end) = (Point::new(0.0, 0.0), Point::new(100.0, 0.0));
+        let obstacles = [Obstacle {
+            id: 3,
+            name: "Block".into(),
+            center: Point::new(50.0, 0.0),
+            radius: 5.0,
+            radius_y: None,
+        }];
+        let leg = RouteLegOptions::default();
+
+        let stored = RouteOptions::default();
+        let route = compute_route(start, end, &obstacles, stored).expect("route");
+        let id = queries::persist_route(&mut con, 1, 2, stored, &leg, &route, None, None)
+            .expect("persist");
+
+        let from_p = queries::find_planet_by_fid(&con, "1")
+            .unwrap()
+            .expect("from");
+        let to_p = queries::find_planet_by_fid(&con, "2").unwrap().expect("to");
+        let seed = load_seed_route(&con, id, &from_p, &to_p, start, end, &leg)
+            .unwrap()
+            .expect("seed with other options is still used");
+
+        let wider = RouteOptions {
+            clearance: stored.clearance + 3.0,
+            ..stored
+        };
+        let repaired = compute_route_seeded(seed, &obstacles, wider, None).expect("repaired");
+        assert_eq!(repaired.waypoints.first(), Some(&start));
+        assert_eq!(repaired.waypoints.last(), Some(&end));
+        let gap = min_corridor_clearance(&repaired.waypoints, &obstacles).expect("gap");
+        assert!(gap > 0.0, "gap {gap}");
+
+        // A seed pinned through other waypoints is still ignored.
+        let pinned = RouteLegOptions {
+            vias: vec!["Gate".to_string()],
+            ..Default::default()
+        };
+        assert!(
+            load_seed_route(&con, id, &from_p, &to_p, start, end, &pinned)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn mirror_is_not_reused_across_safety_radii() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Start', 'start', 0.0, 0.0, 'h1'),
+                (2, 'End', 'end', 100.0, 0.0, 'h2');
+            "#,
+        )
+        .expect("planets");
+        let opts = RouteOptions::default();
+        let leg = RouteLegOptions {
+            safety: Some(2.0),
+            bbox_margin: Some(80.0),
+            max_obstacles: Some(8000),
+            ..Default::default()
+        };
+
+        // Stored TO→FROM.
+        let route =
+            compute_route(Point::new(100.0, 0.0), Point::new(0.0, 0.0), &[], opts).expect("route");
+        queries::persist_route(&mut con, 2, 1, opts, &leg, &route, None, None).expect("persist");
+
+        let from_p = queries::find_planet_by_fid(&con, "1")
+            .unwrap()
+            .expect("from");
+        let to_p = queries::find_planet_by_fid(&con, "2").unwrap().expect("to");
+        assert!(
+            mirror_route(&con, &from_p, &to_p, &opts, &leg)
+                .unwrap()
+                .is_some()
+        );
+
+        let safer = RouteLegOptions {
+            safety: Some(3.0),
+            ..leg.clone()
+        };
+        assert!(
+            mirror_route(&con, &from_p, &to_p, &opts, &safer)
+                .unwrap()
+                .is_none()
+        );
+    }
 }
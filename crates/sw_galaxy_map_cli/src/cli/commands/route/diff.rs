@@ -0,0 +1,256 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+use crate::cli::color::Colors;
+use crate::cli::errors::not_found;
+use crate::ui::Style;
+
+use sw_galaxy_map_core::db::queries;
+use sw_galaxy_map_core::model::{RouteDetourRow, RouteLoaded};
+
+const LABEL_W: usize = 12;
+const COL_W: usize = 14;
+
+/// Detours of both routes grouped by obstacle.
+///
+/// When an obstacle is avoided more than once, detours are paired in `idx` order;
+/// the surplus of the longer side ends up in `only_a` / `only_b`.
+struct DetourDiff<'a> {
+    matched: Vec<(&'a RouteDetourRow, &'a RouteDetourRow)>,
+    only_a: Vec<&'a RouteDetourRow>,
+    only_b: Vec<&'a RouteDetourRow>,
+}
+
+fn diff_detours<'a>(a: &'a [RouteDetourRow], b: &'a [RouteDetourRow]) -> DetourDiff<'a> {
+    let mut by_obstacle: BTreeMap<i64, (Vec<&RouteDetourRow>, Vec<&RouteDetourRow>)> =
+        BTreeMap::new();
+    for d in a {
+        by_obstacle.entry(d.obstacle_id).or_default().0.push(d);
+    }
+    for d in b {
+        by_obstacle.entry(d.obstacle_id).or_default().1.push(d);
+    }
+
+    let mut out = DetourDiff {
+        matched: Vec::new(),
+        only_a: Vec::new(),
+        only_b: Vec::new(),
+    };
+    for (da, db) in by_obstacle.into_values() {
+        let n = da.len().min(db.len());
+        out.matched
+            .extend(da.iter().copied().zip(db.iter().copied()));
+        out.only_a.extend(da.into_iter().skip(n));
+        out.only_b.extend(db.into_iter().skip(n));
+    }
+    out
+}
+
+fn fmt_opt(v: Option<f64>) -> String {
+    v.map_or_else(|| "-".to_string(), |x| format!("{:.3}", x))
+}
+
+fn fmt_delta(a: f64, b: f64) -> String {
+    format!("{:+.3}", b - a)
+}
+
+fn print_row(label: &str, a: &str, b: &str, delta: &str) {
+    println!(
+        "{:<LABEL_W$} {:>COL_W$} {:>COL_W$} {:>COL_W$}",
+        label, a, b, delta
+    );
+}
+
+pub(crate) fn run_diff(con: &Connection, a_id: i64, b_id: i64, style: Style) -> Result<()> {
+    let a = queries::load_route(con, a_id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", a_id)))?;
+    let b = queries::load_route(con, b_id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", b_id)))?;
+
+    print_diff(&a, &b, style);
+    Ok(())
+}
+
+fn print_diff(a: &RouteLoaded, b: &RouteLoaded, style: Style) {
+    let c = Colors::new(&style);
+    let (ra, rb) = (&a.route, &b.route);
+
+    println!(
+        "Route diff — A=#{} ({} → {}) vs B=#{} ({} → {})",
+        ra.id,
+        ra.from_planet_name,
+        ra.to_planet_name,
+        rb.id,
+        rb.from_planet_name,
+        rb.to_planet_name
+    );
+    if (ra.from_planet_fid, ra.to_planet_fid) != (rb.from_planet_fid, rb.to_planet_fid) {
+        println!(
+            "{}",
+            c.warn("Note: the routes connect different endpoints; deltas are not like-for-like.")
+        );
+    }
+    println!();
+
+    print_row(
+        "",
+        &format!("A #{}", ra.id),
+        &format!("B #{}", rb.id),
+        "B - A",
+    );
+    print_row(
+        "Length",
+        &fmt_opt(ra.length),
+        &fmt_opt(rb.length),
+        &match (ra.length, rb.length) {
+            (Some(la), Some(lb)) => fmt_delta(la, lb),
+            _ => "-".to_string(),
+        },
+    );
+    let iters = |v: Option<i64>| v.map_or_else(|| "-".to_string(), |n| n.to_string());
+    print_row(
+        "Iterations",
+        &iters(ra.iterations),
+        &iters(rb.iterations),
+        &match (ra.iterations, rb.iterations) {
+            (Some(ia), Some(ib)) => format!("{:+}", ib - ia),
+            _ => "-".to_string(),
+        },
+    );
+    print_row(
+        "Detours",
+        &a.detours.len().to_string(),
+        &b.detours.len().to_string(),
+        &format!("{:+}", b.detours.len() as i64 - a.detours.len() as i64),
+    );
+    print_row(
+        "Status",
+        &ra.status,
+        &rb.status,
+        if ra.status == rb.status { "" } else { "≠" },
+    );
+    println!();
+
+    match (ra.length, rb.length) {
+        (Some(la), Some(lb)) if lb < la => println!(
+            "{}",
+            c.ok(format!(
+                "B is shorter by {:.3} parsec ({:.2}%)",
+                la - lb,
+                (la - lb) / la * 100.0
+            ))
+        ),
+        (Some(la), Some(lb)) if lb > la => println!(
+            "{}",
+            c.err(format!(
+                "B is longer by {:.3} parsec ({:.2}%)",
+                lb - la,
+                (lb - la) / la * 100.0
+            ))
+        ),
+        (Some(_), Some(_)) => println!("{}", c.dim("Both routes have the same length")),
+        _ => println!("{}", c.dim("Length unavailable for at least one route")),
+    }
+
+    let diff = diff_detours(&a.detours, &b.detours);
+
+    if !diff.matched.is_empty() {
+        println!();
+        println!("Detours on shared obstacles (B - A):");
+        for (da, db) in &diff.matched {
+            println!(
+                "  {} (id={})  offset {:.3} → {:.3} ({})  closest {:.3} → {:.3} ({})  score {:.3} → {:.3} ({})",
+                c.obstacle(&da.obstacle_name),
+                da.obstacle_id,
+                da.offset_used,
+                db.offset_used,
+                fmt_delta(da.offset_used, db.offset_used),
+                da.closest_dist,
+                db.closest_dist,
+                fmt_delta(da.closest_dist, db.closest_dist),
+                da.score_total,
+                db.score_total,
+                fmt_delta(da.score_total, db.score_total)
+            );
+        }
+    }
+
+    for (label, rows) in [("A", &diff.only_a), ("B", &diff.only_b)] {
+        if rows.is_empty() {
+            continue;
+        }
+        println!();
+        println!("Obstacles avoided only by {}:", label);
+        for d in rows {
+            println!(
+                "  {} (id={})  detour #{}  offset {:.3}  score {:.3}",
+                c.obstacle(&d.obstacle_name),
+                d.obstacle_id,
+                d.idx,
+                d.offset_used,
+                d.score_total
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_detours;
+    use sw_galaxy_map_core::model::RouteDetourRow;
+
+    fn detour(idx: i64, obstacle_id: i64) -> RouteDetourRow {
+        RouteDetourRow {
+            idx,
+            iteration: idx,
+            segment_index: 0,
+            obstacle_id,
+            obstacle_name: format!("Obstacle {}", obstacle_id),
+            obstacle_x: 0.0,
+            obstacle_y: 0.0,
+            obstacle_radius: 1.0,
+            closest_t: 0.5,
+            closest_qx: 0.0,
+            closest_qy: 0.0,
+            closest_dist: 0.5,
+            offset_used: 2.0,
+            wp_x: 0.0,
+            wp_y: 2.0,
+            waypoint_id: None,
+            score_base: 1.0,
+            score_turn: 0.0,
+            score_back: 0.0,
+            score_proximity: 0.0,
+            score_total: 1.0,
+            tries_used: Some(1),
+            tries_exhausted: 0,
+        }
+    }
+
+    #[test]
+    fn detours_pair_by_obstacle_and_keep_the_surplus() {
+        // A avoids 7 twice and 5 once; B avoids 7 once and 9 once, in another order.
+        let a = [detour(0, 7), detour(1, 5), detour(2, 7)];
+        let b = [detour(0, 9), detour(1, 7)];
+
+        let diff = diff_detours(&a, &b);
+
+        let matched: Vec<_> = diff
+            .matched
+            .iter()
+            .map(|(da, db)| (da.obstacle_id, da.idx, db.idx))
+            .collect();
+        assert_eq!(matched, vec![(7, 0, 1)]);
+
+        let ids = |rows: &[&RouteDetourRow]| -> Vec<(i64, i64)> {
+            rows.iter().map(|d| (d.obstacle_id, d.idx)).collect()
+        };
+        assert_eq!(ids(&diff.only_a), vec![(5, 1), (7, 2)]);
+        assert_eq!(ids(&diff.only_b), vec![(9, 0)]);
+
+        let same = diff_detours(&a, &a);
+        assert_eq!(same.matched.len(), a.len());
+        assert!(same.only_a.is_empty() && same.only_b.is_empty());
+    }
+}
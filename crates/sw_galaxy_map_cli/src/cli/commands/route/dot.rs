@@ -0,0 +1,99 @@
+use std::fmt::Write as _;
+
+use sw_galaxy_map_core::model::{RouteDetourRow, RouteLoaded, RouteWaypointRow};
+use sw_galaxy_map_core::routing::geometry::{Point, dist as geom_dist};
+
+/// Quote a string as a DOT label (newlines become `\n` line breaks).
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// Detour that produced waypoint `w` (by computed waypoint id, else by position).
+fn detour_for<'a>(
+    w: &RouteWaypointRow,
+    detours: &'a [RouteDetourRow],
+) -> Option<&'a RouteDetourRow> {
+    const EPS: f64 = 1e-6;
+
+    detours
+        .iter()
+        .find(|d| w.waypoint_id.is_some() && d.waypoint_id == w.waypoint_id)
+        .or_else(|| {
+            detours
+                .iter()
+                .find(|d| (d.wp_x - w.x).abs() < EPS && (d.wp_y - w.y).abs() < EPS)
+        })
+}
+
+/// Render the route as a Graphviz digraph: one node per waypoint, edges labeled
+/// with segment lengths, detour nodes annotated with the obstacle they avoid.
+pub(crate) fn route_to_dot(loaded: &RouteLoaded) -> String {
+    let mut out = String::new();
+    let last = loaded.waypoints.len().saturating_sub(1);
+
+    let _ = writeln!(out, "digraph route_{} {{", loaded.route.id);
+    let _ = writeln!(out, "  rankdir=LR;");
+    let _ = writeln!(
+        out,
+        "  label={};",
+        quote(&format!(
+            "Route #{}: {} → {}",
+            loaded.route.id, loaded.route.from_planet_name, loaded.route.to_planet_name
+        ))
+    );
+    let _ = writeln!(out, "  node [fontname=\"Helvetica\"];");
+    let _ = writeln!(out, "  edge [fontname=\"Helvetica\"];");
+    let _ = writeln!(out);
+
+    for (i, w) in loaded.waypoints.iter().enumerate() {
+        let coords = format!("({:.3}, {:.3})", w.x, w.y);
+
+        let (label, attrs) = if i == 0 {
+            (
+                format!("Start: {}\n{}", loaded.route.from_planet_name, coords),
+                "shape=doublecircle, style=filled, fillcolor=palegreen",
+            )
+        } else if i == last {
+            (
+                format!("End: {}\n{}", loaded.route.to_planet_name, coords),
+                "shape=doublecircle, style=filled, fillcolor=lightblue",
+            )
+        } else if let Some(d) = detour_for(w, &loaded.detours) {
+            (
+                format!(
+                    "Detour #{}\n{}\navoids {} [{}] r={:.3}",
+                    w.seq, coords, d.obstacle_name, d.obstacle_id, d.obstacle_radius
+                ),
+                "shape=box, style=filled, fillcolor=moccasin",
+            )
+        } else {
+            let name = w.waypoint_name.as_deref().unwrap_or("waypoint");
+            (format!("{}\n{}", name, coords), "shape=box")
+        };
+
+        let _ = writeln!(out, "  wp{} [label={}, {}];", w.seq, quote(&label), attrs);
+    }
+
+    if !loaded.waypoints.is_empty() {
+        let _ = writeln!(out);
+    }
+
+    for pair in loaded.waypoints.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let len = geom_dist(Point::new(a.x, a.y), Point::new(b.x, b.y));
+        let _ = writeln!(
+            out,
+            "  wp{} -> wp{} [label={}];",
+            a.seq,
+            b.seq,
+            quote(&format!("{:.3} pc", len))
+        );
+    }
+
+    out.push_str("}\n");
+    out
+}
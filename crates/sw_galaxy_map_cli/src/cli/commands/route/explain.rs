@@ -7,19 +7,21 @@ use std::path::Path;
 
 use crate::cli::args::RouteExplainArgs;
 use crate::cli::color::Colors;
+use crate::cli::errors::not_found;
 use crate::cli::export::{
-    ExplainClosest, ExplainDetour, ExplainDominantPenalty, ExplainEndpoint, ExplainExport,
-    ExplainNote, ExplainObstacle, ExplainRouteMeta, ExplainScore, ExplainWaypoint,
+    ExplainClosest, ExplainDetour, ExplainDominantPenalty, ExplainDriver, ExplainEndpoint,
+    ExplainExport, ExplainNote, ExplainObstacle, ExplainRouteMeta, ExplainScore, ExplainWaypoint,
 };
 use crate::ui::Style;
 
 use sw_galaxy_map_core::db::queries;
+use sw_galaxy_map_core::domain::Metric;
 use sw_galaxy_map_core::model::{RouteLoaded, RouteOptionsJson};
 use sw_galaxy_map_core::routing::geometry::Point;
 use sw_galaxy_map_core::routing::geometry::{dist as geom_dist, polyline_length_waypoints_parsec};
 use sw_galaxy_map_core::routing::hyperspace::{
-    DetourPenaltyParams, GalacticRegion, detour_penalty_multiplier, estimate_travel_time_hours,
-    extract_galactic_region,
+    CompressionTable, DetourPenaltyParams, GalacticRegion, detour_penalty_multiplier,
+    estimate_travel_time_hours, extract_galactic_region,
 };
 use sw_galaxy_map_core::routing::sublight::estimate_sublight_time_hours;
 
@@ -78,6 +80,7 @@ pub(crate) fn compute_eta_summary(
     blend: RegionBlend,
     detour_count_base: f64,
     severity_k: f64,
+    table: &CompressionTable,
 ) -> Option<String> {
     if loaded.waypoints.len() < 2 {
         return None;
@@ -129,8 +132,8 @@ pub(crate) fn compute_eta_summary(
     let rf = from_region.unwrap_or(GalacticRegion::OuterRim);
     let rt = to_region.unwrap_or(GalacticRegion::OuterRim);
 
-    let cf_from = rf.base_compression_factor();
-    let cf_to = rt.base_compression_factor();
+    let cf_from = table.factor(rf);
+    let cf_to = table.factor(rt);
 
     let cf_base_eff = match blend {
         RegionBlend::Avg => (cf_from + cf_to) / 2.0,
@@ -158,24 +161,54 @@ pub(crate) fn compute_eta_summary(
     ))
 }
 
-pub(crate) fn analyze_detour_drivers(
+/// One decision driver behind a detour, classified by category.
+#[derive(Debug, Clone)]
+pub(crate) struct DetourDriver {
+    /// One of `constraint`, `limit` or `cost`.
+    pub category: &'static str,
+    pub message: String,
+    /// Key figure of the driver (breach, offset, penalty...), when available.
+    pub value: Option<f64>,
+}
+
+impl DetourDriver {
+    fn new(category: &'static str, message: String, value: f64) -> Self {
+        Self {
+            category,
+            message,
+            value: value.is_finite().then_some(value),
+        }
+    }
+
+    /// Free-form representation (`<category>: <message>`), as printed by the text output.
+    pub(crate) fn line(&self) -> String {
+        format!("{}: {}", self.category, self.message)
+    }
+}
+
+pub(crate) fn detour_drivers(
     d: &sw_galaxy_map_core::model::RouteDetourRow,
     opts: Option<&RouteOptionsJson>,
-) -> Vec<String> {
+) -> Vec<DetourDriver> {
     let mut out = Vec::new();
 
     let clearance = opts.map(|o| o.clearance).unwrap_or(0.0);
     let required = d.obstacle_radius + clearance;
     let breach = required - d.closest_dist;
     if breach > 0.0 {
-        out.push(format!(
-            "constraint: safety breach {:.3} (closest {:.3} < required {:.3})",
-            breach, d.closest_dist, required
+        out.push(DetourDriver::new(
+            "constraint",
+            format!(
+                "safety breach {:.3} (closest {:.3} < required {:.3})",
+                breach, d.closest_dist, required
+            ),
+            breach,
         ));
     } else {
-        out.push(format!(
-            "constraint: no breach at logging time (margin {:.3})",
-            -breach
+        out.push(DetourDriver::new(
+            "constraint",
+            format!("no breach at logging time (margin {:.3})", -breach),
+            -breach,
         ));
     }
 
@@ -184,9 +217,13 @@ pub(crate) fn analyze_detour_drivers(
         let theo_max = required * o.offset_growth.powi((tries - 1).max(0));
 
         if theo_max.is_finite() && theo_max > 0.0 && d.offset_used >= theo_max * 0.90 {
-            out.push(format!(
-                "limit: offset near theoretical max (offset_used {:.3} ≈ max {:.3}); likely limited by max_offset_tries={} and offset_growth={:.3}",
-                d.offset_used, theo_max, o.max_offset_tries, o.offset_growth
+            out.push(DetourDriver::new(
+                "limit",
+                format!(
+                    "offset near theoretical max (offset_used {:.3} ≈ max {:.3}); likely limited by max_offset_tries={} and offset_growth={:.3}",
+                    d.offset_used, theo_max, o.max_offset_tries, o.offset_growth
+                ),
+                d.offset_used,
             ));
         }
     }
@@ -200,34 +237,75 @@ pub(crate) fn analyze_detour_drivers(
     let (name, val) = comps[0];
 
     if val.abs() > 1e-9 {
-        out.push(format!(
-            "cost: dominant penalty component is {} ({:.3})",
-            name, val
+        out.push(DetourDriver::new(
+            "cost",
+            format!("dominant penalty component is {} ({:.3})", name, val),
+            val,
         ));
     }
 
     let penalties = d.score_turn + d.score_back + d.score_proximity;
     if penalties > d.score_base * 0.25 {
-        out.push(format!(
-            "cost: penalties are significant ({:.3}) vs base ({:.3})",
-            penalties, d.score_base
+        out.push(DetourDriver::new(
+            "cost",
+            format!(
+                "penalties are significant ({:.3}) vs base ({:.3})",
+                penalties, d.score_base
+            ),
+            penalties,
         ));
     } else {
-        out.push(format!(
-            "cost: route length dominates (base {:.3}, penalties {:.3})",
-            d.score_base, penalties
+        out.push(DetourDriver::new(
+            "cost",
+            format!(
+                "route length dominates (base {:.3}, penalties {:.3})",
+                d.score_base, penalties
+            ),
+            d.score_base,
         ));
     }
 
     out
 }
 
-pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<()> {
+pub(crate) fn analyze_detour_drivers(
+    d: &sw_galaxy_map_core::model::RouteDetourRow,
+    opts: Option<&RouteOptionsJson>,
+) -> Vec<String> {
+    detour_drivers(d, opts)
+        .iter()
+        .map(DetourDriver::line)
+        .collect()
+}
+
+/// Cells of the `--gauge` bar.
+const GAUGE_WIDTH: usize = 30;
+
+/// ASCII bar over `[0, required]`: `#` cells are the breach (required - closest),
+/// `-` cells the part of the safety radius the straight line kept.
+pub(crate) fn breach_gauge(closest_dist: f64, required: f64) -> String {
+    if required <= 0.0 {
+        return format!("[{}] n/a (required=0)", "-".repeat(GAUGE_WIDTH));
+    }
+
+    let breach = ((required - closest_dist) / required).clamp(0.0, 1.0);
+    let filled = (breach * GAUGE_WIDTH as f64).round() as usize;
+
+    format!(
+        "[{}{}] breach {:>5.1}% of required",
+        "#".repeat(filled),
+        "-".repeat(GAUGE_WIDTH - filled),
+        breach * 100.0
+    )
+}
+
+pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs, style: Style) -> Result<()> {
     let loaded = queries::load_route(con, args.route_id)?
-        .ok_or_else(|| anyhow::anyhow!("Route not found: id={}", args.route_id))?;
+        .ok_or_else(|| not_found(format!("Route not found: id={}", args.route_id)))?;
 
     let opts: Option<RouteOptionsJson> = serde_json::from_str(&loaded.route.options_json).ok();
     let clearance = opts.as_ref().map(|o| o.clearance).unwrap_or(0.0);
+    let table = CompressionTable::load(args.compression_config.as_deref())?;
 
     if args.json {
         let mut detours_out = Vec::with_capacity(loaded.detours.len());
@@ -247,7 +325,7 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
                 value: comps[0].1,
             };
 
-            let drivers = analyze_detour_drivers(d, opts.as_ref());
+            let drivers = detour_drivers(d, opts.as_ref());
             let tries_exhausted = d.tries_exhausted == 1;
 
             detours_out.push(ExplainDetour {
@@ -290,7 +368,15 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
                 tries_exhausted,
 
                 dominant_penalty: dom,
-                decision_drivers: drivers,
+                decision_drivers: drivers.iter().map(DetourDriver::line).collect(),
+                drivers: drivers
+                    .into_iter()
+                    .map(|dr| ExplainDriver {
+                        category: dr.category.to_string(),
+                        message: dr.message,
+                        value: dr.value,
+                    })
+                    .collect(),
             });
         }
 
@@ -339,13 +425,35 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
         return Ok(());
     }
 
+    if args.detours_csv {
+        if let Some(path) = &args.file {
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                fs::create_dir_all(parent)?;
+            }
+
+            let mut f = fs::File::create(path)?;
+            write_detours_csv(&mut f, &loaded, clearance)?;
+            eprintln!("CSV detours written to {}", path.display());
+        } else {
+            write_detours_csv(&mut std::io::stdout().lock(), &loaded, clearance)?;
+        }
+
+        return Ok(());
+    }
+
+    if args.dot {
+        print!("{}", super::dot::route_to_dot(&loaded));
+        return Ok(());
+    }
+
     if let Some(csv_path) = &args.csv {
         export_polyline_csv(&loaded, csv_path)?;
         eprintln!("CSV polyline written to {}", csv_path.display());
         return Ok(());
     }
 
-    let style = Style::default();
     let c = Colors::new(&style);
 
     let status_txt = loaded.route.status.as_str();
@@ -378,6 +486,7 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
             parse_region_blend(&args.region_blend),
             DEFAULT_DETOUR_COUNT_BASE,
             DEFAULT_SEVERITY_K,
+            &table,
             &c,
         );
 
@@ -406,13 +515,38 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
         println!("Router params:");
         println!("  clearance={:.3}", o.clearance);
         println!(
-            "  limits: max_iters={}  max_offset_tries={}  offset_growth={:.3}",
-            o.max_iters, o.max_offset_tries, o.offset_growth
+            "  limits: max_iters={}  max_waypoints={}  max_offset_tries={}  offset_growth={:.3}",
+            o.max_iters,
+            o.max_waypoints
+                .map_or_else(|| "-".to_string(), |m| m.to_string()),
+            o.max_offset_tries,
+            o.offset_growth
         );
         println!(
             "  weights: turn={:.3}  back={:.3}  proximity={:.3}  proximity_margin={:.3}",
             o.turn_weight, o.back_weight, o.proximity_weight, o.proximity_margin
         );
+        if let Some(e) = o.proximity_exponent {
+            println!("  proximity_exponent={:.3}", e);
+        }
+        if o.explore_both_sides {
+            println!("  detours: explore_both_sides");
+        }
+        if o.metric != Metric::Euclidean {
+            println!("  metric: {}", format!("{:?}", o.metric).to_lowercase());
+        }
+        if !o.leg.vias.is_empty() {
+            println!("  waypoints: {}", o.leg.vias.join(" → "));
+        }
+        if !o.leg.avoid.is_empty() {
+            println!("  avoid: {}", o.leg.avoid.join(", "));
+        }
+        if let Some(r) = o.leg.snap_radius {
+            println!("  snap_radius={:.3}", r);
+        }
+        if let Some(d) = o.leg.min_spacing {
+            println!("  min_spacing={:.3}", d);
+        }
     }
 
     println!();
@@ -489,6 +623,9 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
             why_plain
         };
         println!("    why: {}", why_out);
+        if args.gauge {
+            println!("    gauge: {}", breach_gauge(d.closest_dist, required));
+        }
 
         let wp_plain = format!("({:.3},{:.3})", d.wp_x, d.wp_y);
         let wp_out = c.waypoint(wp_plain);
@@ -550,6 +687,7 @@ pub(crate) fn run_explain(con: &Connection, args: &RouteExplainArgs) -> Result<(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn print_eta_breakdown(
     con: &Connection,
     loaded: &RouteLoaded,
@@ -557,6 +695,7 @@ pub(crate) fn print_eta_breakdown(
     blend: RegionBlend,
     detour_count_base: f64,
     severity_k: f64,
+    table: &CompressionTable,
     c: &Colors,
 ) {
     if loaded.waypoints.len() < 2 || hyperdrive_class <= 0.0 {
@@ -590,8 +729,8 @@ pub(crate) fn print_eta_breakdown(
     let from_region = extract_galactic_region(&from_p).unwrap_or(GalacticRegion::OuterRim);
     let to_region = extract_galactic_region(&to_p).unwrap_or(GalacticRegion::OuterRim);
 
-    let cf_from = from_region.base_compression_factor();
-    let cf_to = to_region.base_compression_factor();
+    let cf_from = table.factor(from_region);
+    let cf_to = table.factor(to_region);
 
     let cf_base = match blend {
         RegionBlend::Avg => (cf_from + cf_to) / 2.0,
@@ -807,6 +946,47 @@ pub(crate) fn print_detour_summary(loaded: &RouteLoaded, c: &Colors) {
     }
 }
 
+/// Writes one CSV row per detour (numeric fields only, plus the obstacle name).
+fn write_detours_csv(out: &mut impl Write, loaded: &RouteLoaded, clearance: f64) -> Result<()> {
+    writeln!(
+        out,
+        "idx,iteration,obstacle_id,obstacle_name,closest_dist,required,violated_by,offset_used,\
+         score_base,score_turn,score_back,score_proximity,score_total,tries_used,tries_exhausted"
+    )?;
+
+    for d in &loaded.detours {
+        let required = d.obstacle_radius + clearance;
+        let name = if d.obstacle_name.contains(',') || d.obstacle_name.contains('"') {
+            format!("\"{}\"", d.obstacle_name.replace('"', "\"\""))
+        } else {
+            d.obstacle_name.clone()
+        };
+        let tries_used = d.tries_used.map(|n| n.to_string()).unwrap_or_default();
+
+        writeln!(
+            out,
+            "{},{},{},{},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{},{}",
+            d.idx,
+            d.iteration,
+            d.obstacle_id,
+            name,
+            d.closest_dist,
+            required,
+            required - d.closest_dist,
+            d.offset_used,
+            d.score_base,
+            d.score_turn,
+            d.score_back,
+            d.score_proximity,
+            d.score_total,
+            tries_used,
+            d.tries_exhausted
+        )?;
+    }
+
+    Ok(())
+}
+
 pub(crate) fn export_polyline_csv(loaded: &RouteLoaded, path: &Path) -> Result<()> {
     if let Some(parent) = path.parent()
         && !parent.as_os_str().is_empty()
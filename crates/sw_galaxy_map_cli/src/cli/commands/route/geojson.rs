@@ -0,0 +1,155 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use sw_galaxy_map_core::model::{RouteLoaded, RouteOptionsJson};
+
+/// Number of vertices used to approximate an obstacle circle.
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// Closed ring approximating a circle as an N-gon (first vertex repeated at the end).
+pub(crate) fn circle_ring(cx: f64, cy: f64, radius: f64, segments: usize) -> Vec<[f64; 2]> {
+    let n = segments.max(3);
+    let mut ring = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        let a = std::f64::consts::TAU * (i as f64) / (n as f64);
+        ring.push([cx + radius * a.cos(), cy + radius * a.sin()]);
+    }
+    ring.push(ring[0]);
+    ring
+}
+
+/// Route polyline as a GeoJSON `LineString` feature.
+pub(crate) fn route_line_feature(loaded: &RouteLoaded) -> Value {
+    let coords: Vec<[f64; 2]> = loaded.waypoints.iter().map(|w| [w.x, w.y]).collect();
+
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": coords,
+        },
+        "properties": {
+            "kind": "route",
+            "route_id": loaded.route.id,
+            "from": loaded.route.from_planet_name,
+            "from_fid": loaded.route.from_planet_fid,
+            "to": loaded.route.to_planet_name,
+            "to_fid": loaded.route.to_planet_fid,
+            "status": loaded.route.status,
+            "length_parsec": loaded.route.length,
+        },
+    })
+}
+
+/// Obstacles that drove a detour, as GeoJSON `Polygon` features (one per obstacle).
+pub(crate) fn obstacle_features(loaded: &RouteLoaded) -> Vec<Value> {
+    let clearance = serde_json::from_str::<RouteOptionsJson>(&loaded.route.options_json)
+        .map(|o| o.clearance)
+        .unwrap_or(0.0);
+
+    // The same obstacle may be detoured more than once: emit it only once.
+    let mut by_id = BTreeMap::new();
+    for d in &loaded.detours {
+        by_id.entry(d.obstacle_id).or_insert(d);
+    }
+
+    by_id
+        .values()
+        .map(|d| {
+            let ring = circle_ring(
+                d.obstacle_x,
+                d.obstacle_y,
+                d.obstacle_radius,
+                CIRCLE_SEGMENTS,
+            );
+            json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [ring],
+                },
+                "properties": {
+                    "kind": "obstacle",
+                    "obstacle_id": d.obstacle_id,
+                    "name": d.obstacle_name,
+                    "center": [d.obstacle_x, d.obstacle_y],
+                    "radius": d.obstacle_radius,
+                    "clearance": clearance,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Detour waypoints as GeoJSON `Point` features, tagged with the obstacle they avoid.
+pub(crate) fn detour_point_features(loaded: &RouteLoaded) -> Vec<Value> {
+    loaded
+        .detours
+        .iter()
+        .map(|d| {
+            json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": [d.wp_x, d.wp_y],
+                },
+                "properties": {
+                    "kind": "detour",
+                    "idx": d.idx,
+                    "obstacle_id": d.obstacle_id,
+                    "obstacle": d.obstacle_name,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Write a GeoJSON document to `file` (creating parent directories) or to stdout.
+pub(crate) fn write_geojson(doc: &Value, file: Option<&Path>) -> Result<()> {
+    let s = serde_json::to_string_pretty(doc)?;
+
+    if let Some(path) = file {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut f = fs::File::create(path)?;
+        f.write_all(s.as_bytes())?;
+        f.write_all(b"\n")?;
+        eprintln!("GeoJSON written to {}", path.display());
+    } else {
+        println!("{}", s);
+    }
+
+    Ok(())
+}
+
+/// Route polyline plus the obstacle field that shaped it, in one `FeatureCollection`.
+pub(crate) fn run_show_obstacles_geojson(loaded: &RouteLoaded, file: Option<&Path>) -> Result<()> {
+    let mut features = vec![route_line_feature(loaded)];
+    features.extend(obstacle_features(loaded));
+
+    let doc = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+
+    write_geojson(&doc, file)
+}
+
+/// Route polyline plus one point per detour waypoint, in one `FeatureCollection`.
+pub(crate) fn run_export_geojson(loaded: &RouteLoaded, file: Option<&Path>) -> Result<()> {
+    let mut features = vec![route_line_feature(loaded)];
+    features.extend(detour_point_features(loaded));
+
+    let doc = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+
+    write_geojson(&doc, file)
+}
@@ -8,15 +8,27 @@ use crate::ui::Style;
 use sw_galaxy_map_core::db::queries;
 use sw_galaxy_map_core::domain::RouteListSort;
 use sw_galaxy_map_core::utils::formatting::truncate_ellipsis;
+use sw_galaxy_map_core::utils::normalize_text;
 
 use super::types::{
     RouteListEndpoint, RouteListExport, RouteListItem, RouteListOptions, RouteListTuiItem,
 };
+use crate::cli::errors::not_found;
+
+/// Resolve the `--near-obstacle` planet to its FID.
+fn obstacle_fid(con: &Connection, planet: Option<&str>) -> Result<Option<i64>> {
+    let Some(planet) = planet else {
+        return Ok(None);
+    };
+    let p = queries::find_planet_for_info(con, &normalize_text(planet))?
+        .ok_or_else(|| not_found(format!("Planet not found: {}", planet)))?;
+    Ok(Some(p.fid))
+}
 
-pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>) -> Result<()> {
-    let style = Style::default();
+pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>, style: Style) -> Result<()> {
     let c = Colors::new(&style);
 
+    let near_obstacle = obstacle_fid(con, opts.near_obstacle)?;
     let (rows, rows_count) = queries::list_routes(
         con,
         opts.limit,
@@ -24,6 +36,7 @@ pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>) -> Result<(
         opts.from,
         opts.to,
         opts.wp,
+        near_obstacle,
         opts.sort,
     )?;
 
@@ -44,6 +57,7 @@ pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>) -> Result<(
                     status: r.status,
                     length_parsec: r.length,
                     iterations: r.iterations,
+                    compute_ms: r.compute_ms,
                     created_at: r.created_at,
                     updated_at: r.updated_at,
                     waypoints_count: r.waypoints_count,
@@ -98,8 +112,8 @@ pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>) -> Result<(
     }
 
     println!(
-        "{:>6}  {:<26}  {:<26}  {:<8}  {:>10}  {:>6}  {:>4}  {:>4}  UPDATED",
-        "ID", "FROM", "TO", "STATUS", "LENGTH", "ITERS", "WP", "DET"
+        "{:>6}  {:<26}  {:<26}  {:<8}  {:>10}  {:>6}  {:>7}  {:>4}  {:>4}  UPDATED",
+        "ID", "FROM", "TO", "STATUS", "LENGTH", "ITERS", "MS", "WP", "DET"
     );
 
     for r in rows {
@@ -118,6 +132,11 @@ pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>) -> Result<(
             .map(|v| format!("{:>6}", v))
             .unwrap_or_else(|| format!("{:>6}", "-"));
 
+        let ms_txt = r
+            .compute_ms
+            .map(|v| format!("{:>7}", v))
+            .unwrap_or_else(|| format!("{:>7}", "-"));
+
         let status_plain = format!("{:<8}", r.status);
 
         let status_txt = if r.status.eq_ignore_ascii_case("ok") {
@@ -144,14 +163,15 @@ pub(crate) fn run_list(con: &Connection, opts: RouteListOptions<'_>) -> Result<(
         let upd = r.updated_at.clone().unwrap_or_else(|| r.created_at.clone());
 
         println!(
-            "{:>6}  {:<26}  {:<26}  {}  {}  {}  {}  {}  {}",
-            r.id, from, to, status_txt, len_txt, it_txt, wp_txt, det_txt, upd
+            "{:>6}  {:<26}  {:<26}  {}  {}  {}  {}  {}  {}  {}",
+            r.id, from, to, status_txt, len_txt, it_txt, ms_txt, wp_txt, det_txt, upd
         );
     }
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn resolve_list_for_tui(
     con: &Connection,
     limit: usize,
@@ -159,9 +179,12 @@ pub(crate) fn resolve_list_for_tui(
     from: Option<i64>,
     to: Option<i64>,
     wp: Option<usize>,
+    near_obstacle: Option<&str>,
     sort: RouteListSort,
 ) -> Result<Vec<RouteListTuiItem>> {
-    let (rows, _rows_count) = queries::list_routes(con, limit, status, from, to, wp, sort)?;
+    let near_obstacle = obstacle_fid(con, near_obstacle)?;
+    let (rows, _rows_count) =
+        queries::list_routes(con, limit, status, from, to, wp, near_obstacle, sort)?;
 
     let items = rows
         .into_iter()
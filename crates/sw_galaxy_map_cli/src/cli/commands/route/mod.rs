@@ -1,14 +1,20 @@
 mod cleanup;
 mod compute;
+mod diff;
+mod dot;
 pub(crate) mod explain;
+mod geojson;
 pub(crate) mod list;
 mod show;
+mod transfer;
 pub(crate) mod types;
 
 use cleanup::{run_clear, run_prune};
 use compute::run_compute;
+use diff::run_diff;
 use list::run_list;
-use show::{run_last, run_show};
+use show::{run_export_geojson, run_last, run_show, run_show_obstacles_geojson};
+use transfer::{run_export, run_import};
 use types::RouteListOptions;
 
 pub(crate) use compute::resolve_compute_for_tui;
@@ -16,39 +22,81 @@ pub(crate) use explain::{RegionBlend, compute_eta_summary, run_explain};
 pub(crate) use show::resolve_show_for_tui;
 
 use crate::cli::args::RouteCmd;
+use crate::ui::Style;
 
 use anyhow::Result;
 use rusqlite::Connection;
+use sw_galaxy_map_core::routing::hyperspace::CompressionTable;
 use sw_galaxy_map_core::validate;
 
 // ETA model defaults (not exposed to CLI yet)
-pub fn run(con: &mut Connection, cmd: &RouteCmd) -> Result<()> {
+pub fn run(con: &mut Connection, cmd: &RouteCmd, style: Style) -> Result<()> {
     match cmd {
         RouteCmd::Compute(args) => {
             validate::validate_route_planets(&args.planets)?;
         }
-        RouteCmd::Show { route_id } => {
+        RouteCmd::Show {
+            route_id,
+            hyperdrive_class,
+            ..
+        } => {
             validate::validate_route_id(*route_id, "show")?;
+            if !hyperdrive_class.is_finite() || *hyperdrive_class <= 0.0 {
+                anyhow::bail!("--hyperdrive-class must be a finite number > 0");
+            }
         }
         RouteCmd::Explain(args) => {
             validate::validate_route_id(args.route_id, "explain")?;
         }
+        RouteCmd::Export { route_id, .. } => {
+            validate::validate_route_id(*route_id, "export")?;
+        }
         RouteCmd::Last { from, to } => {
             validate::validate_route_compute(from, to)?;
         }
         RouteCmd::List { limit, .. } => {
             validate::validate_limit(*limit as i64, "list")?;
         }
+        RouteCmd::Diff { a, b } => {
+            validate::validate_route_id(*a, "diff")?;
+            validate::validate_route_id(*b, "diff")?;
+        }
         _ => {}
     }
 
     match cmd {
         RouteCmd::Compute(args) => run_compute(con, args),
-        RouteCmd::Show { route_id } => run_show(con, *route_id),
-        RouteCmd::Explain(args) => run_explain(con, args),
-        RouteCmd::Clear { yes } => run_clear(con, *yes),
-        RouteCmd::Prune => run_prune(con),
-        RouteCmd::Last { from, to } => run_last(con, from, to),
+        RouteCmd::Show {
+            route_id,
+            obstacles_geojson,
+            file,
+            hyperdrive_class,
+            compression_config,
+        } => {
+            if *obstacles_geojson {
+                run_show_obstacles_geojson(con, *route_id, file.as_deref())
+            } else {
+                let table = CompressionTable::load(compression_config.as_deref())?;
+                run_show(con, *route_id, *hyperdrive_class, &table, style)
+            }
+        }
+        RouteCmd::Explain(args) => run_explain(con, args, style),
+        RouteCmd::Clear { yes } => run_clear(con, *yes, style),
+        RouteCmd::Prune => run_prune(con, style),
+        RouteCmd::Export {
+            route_id,
+            geojson,
+            file,
+        } => {
+            if *geojson {
+                run_export_geojson(con, *route_id, file.as_deref())
+            } else {
+                run_export(con, *route_id, file.as_deref())
+            }
+        }
+        RouteCmd::Import { file } => run_import(con, file),
+        RouteCmd::Last { from, to } => run_last(con, from, to, style),
+        RouteCmd::Diff { a, b } => run_diff(con, *a, *b, style),
         RouteCmd::List {
             json,
             file,
@@ -57,6 +105,7 @@ pub fn run(con: &mut Connection, cmd: &RouteCmd) -> Result<()> {
             from,
             to,
             wp,
+            near_obstacle,
             sort,
         } => {
             let opts = RouteListOptions {
@@ -67,10 +116,11 @@ pub fn run(con: &mut Connection, cmd: &RouteCmd) -> Result<()> {
                 from: *from,
                 to: *to,
                 wp: *wp,
+                near_obstacle: near_obstacle.as_deref(),
                 sort: *sort,
             };
 
-            run_list(con, opts)
+            run_list(con, opts, style)
         }
     }
 }
@@ -1,25 +1,58 @@
 use anyhow::Result;
 use rusqlite::Connection;
+use std::path::Path;
 
 use crate::cli::color::Colors;
+use crate::cli::errors::not_found;
 use crate::ui::Style;
 
+use super::geojson;
 use super::types::RouteShowTuiData;
 use super::{RegionBlend, compute_eta_summary};
 use sw_galaxy_map_core::db::queries;
+use sw_galaxy_map_core::model::RouteLoaded;
+use sw_galaxy_map_core::routing::geometry::{Point, dist as geom_dist};
+use sw_galaxy_map_core::routing::hyperspace::{CompressionTable, extract_galactic_region};
 use sw_galaxy_map_core::utils::normalize_text;
 
-pub(crate) fn run_show(con: &Connection, route_id: i64) -> Result<()> {
+/// Hyperdrive class assumed by `route last`, which has no `--hyperdrive-class`.
+const SHOW_DEFAULT_HYPERDRIVE_CLASS: f64 = 1.0;
+
+pub(crate) fn run_show(
+    con: &Connection,
+    route_id: i64,
+    hyperdrive_class: f64,
+    table: &CompressionTable,
+    style: Style,
+) -> Result<()> {
+    let loaded = queries::load_route(con, route_id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", route_id)))?;
+
+    print_route(con, &loaded, hyperdrive_class, table, style)
+}
+
+/// Endpoints without usable region data (the ETA falls back to the Outer Rim factor).
+fn endpoints_missing_region(con: &Connection, loaded: &RouteLoaded) -> Vec<String> {
+    [loaded.route.from_planet_fid, loaded.route.to_planet_fid]
+        .into_iter()
+        .filter_map(|fid| queries::get_planet_by_fid(con, fid).ok().flatten())
+        .filter(|p| extract_galactic_region(p).is_none())
+        .map(|p| p.planet)
+        .collect()
+}
+
+fn print_route(
+    con: &Connection,
+    loaded: &RouteLoaded,
+    hyperdrive_class: f64,
+    table: &CompressionTable,
+    style: Style,
+) -> Result<()> {
     // ETA model defaults for `route show`
-    const SHOW_DEFAULT_HYPERDRIVE_CLASS: f64 = 1.0;
     const SHOW_DEFAULT_DETOUR_COUNT_BASE: f64 = 0.97;
     const SHOW_DEFAULT_SEVERITY_K: f64 = 0.35;
     const SHOW_DEFAULT_REGION_BLEND: RegionBlend = RegionBlend::Avg;
 
-    let loaded = queries::load_route(con, route_id)?
-        .ok_or_else(|| anyhow::anyhow!("Route not found: id={}", route_id))?;
-
-    let style = Style::default();
     let c = Colors::new(&style);
 
     // ---- Header -------------------------------------------------------------
@@ -42,6 +75,9 @@ pub(crate) fn run_show(con: &Connection, route_id: i64) -> Result<()> {
         loaded.route.to_planet_fid,
         status
     );
+    if let Some(err) = loaded.route.error.as_deref() {
+        println!("Error: {}", c.err(err));
+    }
 
     let route_len = loaded.route.length;
     if let Some(len) = route_len {
@@ -49,18 +85,33 @@ pub(crate) fn run_show(con: &Connection, route_id: i64) -> Result<()> {
 
         if let Some(eta) = compute_eta_summary(
             con,
-            &loaded,
-            SHOW_DEFAULT_HYPERDRIVE_CLASS,
+            loaded,
+            hyperdrive_class,
             SHOW_DEFAULT_REGION_BLEND,
             SHOW_DEFAULT_DETOUR_COUNT_BASE,
             SHOW_DEFAULT_SEVERITY_K,
+            table,
         ) {
             println!("{}", eta);
+
+            let missing = endpoints_missing_region(con, loaded);
+            if !missing.is_empty() {
+                println!(
+                    "{}",
+                    c.dim(format!(
+                        "Note: no region data for {}; ETA uses the Outer Rim compression factor.",
+                        missing.join(", ")
+                    ))
+                );
+            }
         }
     }
     if let Some(it) = loaded.route.iterations {
         println!("Iterations: {}", it);
     }
+    if let Some(ms) = loaded.route.compute_ms {
+        println!("Computed in: {} ms", ms);
+    }
     if let Some(upd) = loaded.route.updated_at.as_deref() {
         println!("Updated: {}", upd);
     } else {
@@ -71,7 +122,17 @@ pub(crate) fn run_show(con: &Connection, route_id: i64) -> Result<()> {
     let last_seq = loaded.waypoints.len().saturating_sub(1);
 
     println!("Waypoints: {}", loaded.waypoints.len());
-    for w in &loaded.waypoints {
+    let mut cumulative = 0.0_f64;
+    for (i, w) in loaded.waypoints.iter().enumerate() {
+        // seg_len = distance from the previous waypoint; cum = running total from start.
+        let seg_len = if i == 0 {
+            None
+        } else {
+            let prev = &loaded.waypoints[i - 1];
+            Some(geom_dist(Point::new(prev.x, prev.y), Point::new(w.x, w.y)))
+        };
+        cumulative += seg_len.unwrap_or(0.0);
+
         let is_start = w.seq as usize == 0;
         let is_end = w.seq as usize == last_seq;
 
@@ -109,9 +170,13 @@ pub(crate) fn run_show(con: &Connection, route_id: i64) -> Result<()> {
             label
         };
 
+        let seg_str = seg_len
+            .map(|d| format!("{:.3}", d))
+            .unwrap_or_else(|| "-".to_string());
+
         println!(
-            "  {:>3}: ({:>10.3}, {:>10.3}) {}",
-            w.seq, w.x, w.y, colored_label
+            "  {:>3}: ({:>10.3}, {:>10.3}) seg_len={:>10} cum={:>10.3} {}",
+            w.seq, w.x, w.y, seg_str, cumulative, colored_label
         );
     }
 
@@ -154,32 +219,90 @@ pub(crate) fn run_show(con: &Connection, route_id: i64) -> Result<()> {
         }
     }
 
+    // The obstacle that cost the most: where to look when a route is long.
+    if let Some(d) = loaded
+        .detours
+        .iter()
+        .max_by(|a, b| a.score_total.total_cmp(&b.score_total))
+    {
+        println!(
+            "Worst detour: {} [{}] score={:.3}",
+            d.obstacle_name, d.obstacle_id, d.score_total
+        );
+    }
+
     Ok(())
 }
 
-pub(crate) fn run_last(con: &Connection, from: &str, to: &str) -> Result<()> {
+pub(crate) fn run_show_obstacles_geojson(
+    con: &Connection,
+    route_id: i64,
+    file: Option<&Path>,
+) -> Result<()> {
+    let loaded = queries::load_route(con, route_id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", route_id)))?;
+
+    geojson::run_show_obstacles_geojson(&loaded, file)
+}
+
+pub(crate) fn run_export_geojson(
+    con: &Connection,
+    route_id: i64,
+    file: Option<&Path>,
+) -> Result<()> {
+    let loaded = queries::load_route(con, route_id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", route_id)))?;
+
+    geojson::run_export_geojson(&loaded, file)
+}
+
+pub(crate) fn run_last(con: &Connection, from: &str, to: &str, style: Style) -> Result<()> {
     let from_norm = normalize_text(from);
     let to_norm = normalize_text(to);
 
     let from_p = queries::find_planet_for_info(con, &from_norm)?
-        .ok_or_else(|| anyhow::anyhow!("Planet not found: {}", from))?;
+        .ok_or_else(|| not_found(format!("Planet not found: {}", from)))?;
     let to_p = queries::find_planet_for_info(con, &to_norm)?
-        .ok_or_else(|| anyhow::anyhow!("Planet not found: {}", to))?;
+        .ok_or_else(|| not_found(format!("Planet not found: {}", to)))?;
+
+    let (r, mirrored) = queries::get_route_by_pair_symmetric(con, from_p.fid, to_p.fid)?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No persisted route found for {} → {}",
+                from_p.planet,
+                to_p.planet
+            )
+        })?;
+
+    if !mirrored {
+        return run_show(
+            con,
+            r.id,
+            SHOW_DEFAULT_HYPERDRIVE_CLASS,
+            &CompressionTable::default(),
+            style,
+        );
+    }
 
-    let r = queries::get_route_by_from_to(con, from_p.fid, to_p.fid)?.ok_or_else(|| {
-        anyhow::anyhow!(
-            "No persisted route found for {} → {}",
-            from_p.planet,
-            to_p.planet
-        )
-    })?;
+    let loaded = queries::load_route(con, r.id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", r.id)))?;
 
-    run_show(con, r.id)
+    crate::ui::info(format!(
+        "Serving mirror route #{} ({} → {}) reversed.",
+        r.id, r.from_planet_name, r.to_planet_name
+    ));
+    print_route(
+        con,
+        &loaded.reversed(),
+        SHOW_DEFAULT_HYPERDRIVE_CLASS,
+        &CompressionTable::default(),
+        style,
+    )
 }
 
 pub(crate) fn resolve_show_for_tui(con: &Connection, route_id: i64) -> Result<RouteShowTuiData> {
     let loaded = queries::load_route(con, route_id)?
-        .ok_or_else(|| anyhow::anyhow!("Route not found: id={}", route_id))?;
+        .ok_or_else(|| not_found(format!("Route not found: id={}", route_id)))?;
 
     Ok(RouteShowTuiData { loaded })
 }
@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::cli::errors::not_found;
+use crate::ui::{success, warning};
+use sw_galaxy_map_core::db::queries::{self, RouteImportOutcome};
+use sw_galaxy_map_core::model::RouteExport;
+
+pub(crate) fn run_export(con: &Connection, route_id: i64, file: Option<&Path>) -> Result<()> {
+    let export = queries::export_route(con, route_id)?
+        .ok_or_else(|| not_found(format!("Route not found: id={}", route_id)))?;
+
+    let s = serde_json::to_string_pretty(&export)?;
+
+    if let Some(path) = file {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut f = fs::File::create(path)?;
+        f.write_all(s.as_bytes())?;
+        f.write_all(b"\n")?;
+        success(format!(
+            "Route #{} ({} → {}) exported to {}",
+            route_id,
+            export.route.from_planet_name,
+            export.route.to_planet_name,
+            path.display()
+        ));
+    } else {
+        println!("{}", s);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn run_import(con: &mut Connection, file: &Path) -> Result<()> {
+    let raw = fs::read_to_string(file)
+        .with_context(|| format!("Unable to read route file: {}", file.display()))?;
+    let export: RouteExport = serde_json::from_str(&raw)
+        .with_context(|| format!("Invalid route export JSON: {}", file.display()))?;
+
+    let from_name = &export.route.from_planet_name;
+    let to_name = &export.route.to_planet_name;
+
+    match queries::import_route(con, &export)? {
+        RouteImportOutcome::MissingEndpoints { missing } => {
+            let list: Vec<String> = missing.iter().map(|f| f.to_string()).collect();
+            warning(format!(
+                "Skipped route {} → {}: endpoint FID(s) {} not found in this database.",
+                from_name,
+                to_name,
+                list.join(", ")
+            ));
+        }
+        RouteImportOutcome::Imported {
+            route_id,
+            replaced,
+            missing_obstacles,
+        } => {
+            if replaced {
+                success(format!(
+                    "Route {} → {} imported as #{} (replaced the existing route)",
+                    from_name, to_name, route_id
+                ));
+            } else {
+                success(format!(
+                    "Route {} → {} imported as #{}",
+                    from_name, to_name, route_id
+                ));
+            }
+
+            if !missing_obstacles.is_empty() {
+                let list: Vec<String> = missing_obstacles.iter().map(|f| f.to_string()).collect();
+                warning(format!(
+                    "Detoured obstacle FID(s) {} are not in this database; detours kept as exported.",
+                    list.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -26,6 +26,7 @@ pub(crate) struct RouteListItem {
     pub status: String,
     pub length_parsec: Option<f64>,
     pub iterations: Option<i64>,
+    pub compute_ms: Option<i64>,
     pub created_at: String,
     pub updated_at: Option<String>,
     pub waypoints_count: i64,
@@ -58,5 +59,6 @@ pub(crate) struct RouteListOptions<'a> {
     pub from: Option<i64>,
     pub to: Option<i64>,
     pub wp: Option<usize>,
+    pub near_obstacle: Option<&'a str>,
     pub sort: RouteListSort,
 }
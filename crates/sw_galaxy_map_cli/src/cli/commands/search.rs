@@ -1,8 +1,12 @@
 use anyhow::Result;
 use rusqlite::Connection;
+use serde_json::json;
 
-use crate::ui::{info, warning};
-use sw_galaxy_map_core::db::queries::{fuzzy_search_filtered, search_planets_filtered};
+use crate::cli::errors::invalid;
+use crate::ui::{info, limit_reached_hint, warning};
+use sw_galaxy_map_core::db::queries::{
+    fts_expected_but_missing, fuzzy_search_filtered, search_planets_filtered,
+};
 use sw_galaxy_map_core::model::SearchFilter;
 use sw_galaxy_map_core::utils::normalize_text;
 
@@ -40,6 +44,9 @@ fn describe_filter(filter: &SearchFilter) -> String {
     if let Some(st) = filter.status.as_deref() {
         parts.push(format!("status={}", st));
     }
+    if let Some(rf) = filter.reference.as_deref() {
+        parts.push(format!("ref={}", rf));
+    }
     if filter.canon == Some(true) {
         parts.push("canon".to_string());
     }
@@ -49,6 +56,12 @@ fn describe_filter(filter: &SearchFilter) -> String {
     if filter.fuzzy {
         parts.push("fuzzy".to_string());
     }
+    if filter.prefix {
+        parts.push("prefix".to_string());
+    }
+    if filter.offset > 0 {
+        parts.push(format!("offset={}", filter.offset));
+    }
 
     if parts.is_empty() {
         "(no criteria)".to_string()
@@ -57,7 +70,20 @@ fn describe_filter(filter: &SearchFilter) -> String {
     }
 }
 
-fn print_table(rows: &[sw_galaxy_map_core::model::PlanetSearchRow]) {
+fn print_json(rows: &[sw_galaxy_map_core::model::PlanetSearchRow]) -> Result<()> {
+    let items: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|p| json!({ "fid": p.fid, "planet": p.name, "score": p.score }))
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&items)?);
+    Ok(())
+}
+
+fn score_cell(score: Option<f64>) -> String {
+    score.map_or_else(|| "-".to_string(), |v| format!("{:.3}", v))
+}
+
+fn print_table(rows: &[sw_galaxy_map_core::model::PlanetSearchRow], show_scores: bool) {
     let fid_w: usize = 8;
 
     let name_vals: Vec<&str> = rows.iter().map(|p| p.name.as_str()).collect();
@@ -82,7 +108,11 @@ fn print_table(rows: &[sw_galaxy_map_core::model::PlanetSearchRow]) {
     let x_w = col_width_from_strs(&x_refs, "X".len().max(8));
     let y_w = col_width_from_strs(&y_refs, "Y".len().max(8));
 
-    println!(
+    let score_vals: Vec<String> = rows.iter().map(|p| score_cell(p.score)).collect();
+    let score_refs: Vec<&str> = score_vals.iter().map(String::as_str).collect();
+    let score_w = col_width_from_strs(&score_refs, "Score".len().max(8));
+
+    let mut header = format!(
         "{fid:>fid_w$}   {name:<name_w$}  {region:<region_w$}  {sector:<sector_w$}  {system:<system_w$}  {grid:<grid_w$}  {status:<status_w$}  {x:>x_w$}  {y:>y_w$}",
         fid = "FID",
         name = "Planet",
@@ -94,14 +124,19 @@ fn print_table(rows: &[sw_galaxy_map_core::model::PlanetSearchRow]) {
         x = "X",
         y = "Y",
     );
-
-    println!(
+    let mut rule = format!(
         "{:-<fid_w$}   {:-<name_w$}  {:-<region_w$}  {:-<sector_w$}  {:-<system_w$}  {:-<grid_w$}  {:-<status_w$}  {:-<x_w$}  {:-<y_w$}",
         "", "", "", "", "", "", "", "", ""
     );
+    if show_scores {
+        header.push_str(&format!("  {:>score_w$}", "Score"));
+        rule.push_str(&format!("  {:-<score_w$}", ""));
+    }
+    println!("{}", header);
+    println!("{}", rule);
 
-    for p in rows {
-        println!(
+    for (p, score) in rows.iter().zip(&score_vals) {
+        let mut line = format!(
             "{fid:>fid_w$}   {name:<name_w$}  {region:<region_w$}  {sector:<sector_w$}  {system:<system_w$}  {grid:<grid_w$}  {status:<status_w$}  {x:>x_w$}  {y:>y_w$}",
             fid = p.fid,
             name = p.name,
@@ -113,10 +148,98 @@ fn print_table(rows: &[sw_galaxy_map_core::model::PlanetSearchRow]) {
             x = format!("{:.2}", p.x),
             y = format!("{:.2}", p.y),
         );
+        if show_scores {
+            line.push_str(&format!("  {:>score_w$}", score));
+        }
+        println!("{}", line);
     }
 }
 
-pub fn run(con: &Connection, filter: SearchFilter) -> Result<()> {
+/// When the text query matches planets but none in the requested region/sector,
+/// say so explicitly instead of the generic "no results" line.
+fn location_miss(con: &Connection, filter: &SearchFilter) -> Result<Option<String>> {
+    let Some(query) = filter.query.as_deref().filter(|q| !q.trim().is_empty()) else {
+        return Ok(None);
+    };
+    if filter.region.is_none() && filter.sector.is_none() {
+        return Ok(None);
+    }
+
+    let anywhere = SearchFilter {
+        region: None,
+        sector: None,
+        limit: 1,
+        offset: 0,
+        ..filter.clone()
+    };
+    if search_planets_filtered(con, &anywhere)?.is_empty() {
+        return Ok(None);
+    }
+
+    let mut scope: Vec<String> = Vec::new();
+    if let Some(r) = filter.region.as_deref() {
+        scope.push(format!("region {}", r));
+    }
+    if let Some(s) = filter.sector.as_deref() {
+        scope.push(format!("sector {}", s));
+    }
+
+    Ok(Some(format!(
+        "No results for \"{}\" in {}",
+        query,
+        scope.join(", ")
+    )))
+}
+
+/// Tells the user when a text search is about to use the LIKE fallback because
+/// the FTS index is gone; an error under `--strict-fts`.
+///
+/// The warning goes to stderr so piped results stay clean.
+fn check_fts_fallback(con: &Connection, filter: &SearchFilter, strict: bool) -> Result<()> {
+    let has_query = filter
+        .query
+        .as_deref()
+        .is_some_and(|q| !q.trim().is_empty());
+    if filter.fuzzy || !has_query || !fts_expected_but_missing(con)? {
+        return Ok(());
+    }
+
+    let msg = "FTS is enabled (meta.fts_enabled=1) but the planets_fts index is missing; \
+        this search uses the LIKE fallback (different ranking). \
+        Run `sw_galaxy_map db rebuild-search` to restore it.";
+    if strict {
+        anyhow::bail!(msg);
+    }
+    eprintln!("⚠️ {}", msg);
+    Ok(())
+}
+
+pub fn run(
+    con: &Connection,
+    filter: SearchFilter,
+    show_scores: bool,
+    json: bool,
+    strict_fts: bool,
+) -> Result<()> {
+    check_fts_fallback(con, &filter, strict_fts)?;
+
+    if json {
+        let query_text = filter.query.as_deref().unwrap_or("");
+        let rows = if !filter.fuzzy {
+            search_planets_filtered(con, &filter)?
+        } else if query_text.trim().is_empty() {
+            return Err(invalid("--fuzzy requires a text query"));
+        } else {
+            fuzzy_search_filtered(
+                con,
+                &normalize_text(query_text),
+                FUZZY_MAX_DISTANCE,
+                &filter,
+            )?
+        };
+        return print_json(&rows);
+    }
+
     let description = describe_filter(&filter);
 
     // --- Explicit fuzzy mode: skip exact search, go straight to fuzzy ---
@@ -140,9 +263,10 @@ pub fn run(con: &Connection, filter: SearchFilter) -> Result<()> {
 
         info(format!("Fuzzy search results for: {}", description));
         println!();
-        print_table(&rows);
+        print_table(&rows, show_scores);
 
         println!("\n{} fuzzy match(es) for: {}", rows.len(), description);
+        limit_reached_hint(rows.len(), filter.limit);
 
         return Ok(());
     }
@@ -151,7 +275,10 @@ pub fn run(con: &Connection, filter: SearchFilter) -> Result<()> {
     let rows = search_planets_filtered(con, &filter)?;
 
     if rows.is_empty() {
-        warning(format!("No results found for: {}", description));
+        match location_miss(con, &filter)? {
+            Some(msg) => warning(msg),
+            None => warning(format!("No results found for: {}", description)),
+        }
 
         // --- Automatic "Did you mean?" suggestion ---
         if let Some(query_text) = filter.query.as_deref().filter(|s| !s.trim().is_empty()) {
@@ -172,8 +299,9 @@ pub fn run(con: &Connection, filter: SearchFilter) -> Result<()> {
         return Ok(());
     }
 
-    print_table(&rows);
+    print_table(&rows, show_scores);
     println!("\n{} result(s) for: {}", rows.len(), description);
+    limit_reached_hint(rows.len(), filter.limit);
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use crate::cli::args::UnknownCmd;
+use crate::cli::errors::{invalid, not_found};
 use crate::ui::{info, success, warning};
 use anyhow::Result;
 use rusqlite::Connection;
@@ -95,7 +96,7 @@ pub fn run(con: &Connection, cmd: &UnknownCmd) -> Result<()> {
             }
 
             let source = find_planet_by_norm(con, planet)?
-                .ok_or_else(|| anyhow::anyhow!("Planet '{}' not found.", planet))?;
+                .ok_or_else(|| not_found(format!("Planet '{}' not found.", planet)))?;
 
             let hits = near_unknown_planets(con, source.x, source.y, *range, *limit as i64)?;
 
@@ -302,7 +303,9 @@ fn run_edit(
         || update.notes.is_some();
 
     if !touched {
-        anyhow::bail!("No changes requested. Pass at least one edit flag.");
+        return Err(invalid(
+            "No changes requested. Pass at least one edit flag.",
+        ));
     }
 
     let updated = update_unknown_planet(con, id, &update)?;
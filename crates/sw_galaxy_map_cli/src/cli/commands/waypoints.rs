@@ -3,12 +3,17 @@ use crate::cli::color::Colors;
 use crate::ui;
 use crate::ui::Style;
 use sw_galaxy_map_core::db::queries;
-use sw_galaxy_map_core::model::Planet;
+use sw_galaxy_map_core::model::{Planet, Waypoint, WaypointPlanetLink};
 use sw_galaxy_map_core::utils::formatting::truncate_ellipsis;
 use sw_galaxy_map_core::utils::normalize_text;
 
-use anyhow::{Result, bail};
+use crate::cli::errors::{invalid, not_found};
+use anyhow::{Context, Result, bail};
 use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
 
 // Resolve planet by name/alias (normalized)
 fn resolve_planet_for_waypoint(con: &Connection, input: &str) -> Result<Planet> {
@@ -16,21 +21,50 @@ fn resolve_planet_for_waypoint(con: &Connection, input: &str) -> Result<Planet>
 
     match queries::find_planet_for_info(con, &norm)? {
         Some(p) => Ok(p),
-        None => bail!("Planet not found: {}", input),
+        None => Err(not_found(format!("Planet not found: {}", input))),
     }
 }
 
-pub fn run_waypoint(con: &mut Connection, cmd: &WaypointCmd) -> Result<()> {
+pub fn run_waypoint(con: &mut Connection, cmd: &WaypointCmd, style: Style) -> Result<()> {
     match cmd {
         WaypointCmd::Add {
             name,
             x,
             y,
+            from_planet,
+            dx,
+            dy,
             kind,
             note,
         } => {
             let name_norm = normalize_text(name);
 
+            let (x, y) = match from_planet {
+                Some(planet) => {
+                    let p = resolve_planet_for_waypoint(con, planet)?;
+                    ui::info(format!(
+                        "Placing waypoint relative to '{}' (fid={}) at offset ({:.3}, {:.3})",
+                        p.planet, p.fid, dx, dy
+                    ));
+                    (p.x + dx, p.y + dy)
+                }
+                None => match (x, y) {
+                    (Some(x), Some(y)) => (*x, *y),
+                    _ => {
+                        return Err(invalid(
+                            "Missing coordinates: provide <X> <Y> or --from-planet",
+                        ));
+                    }
+                },
+            };
+
+            if !x.is_finite() || !y.is_finite() {
+                return Err(invalid(format!(
+                    "Invalid waypoint coordinates: x={} y={}",
+                    x, y
+                )));
+            }
+
             // Avoid duplicates (friendly)
             if let Some(existing) = queries::find_waypoint_by_norm(con, &name_norm)? {
                 bail!(
@@ -41,22 +75,21 @@ pub fn run_waypoint(con: &mut Connection, cmd: &WaypointCmd) -> Result<()> {
                 );
             }
 
-            let id =
-                queries::insert_waypoint(con, name, &name_norm, *x, *y, kind, note.as_deref())?;
+            let id = queries::insert_waypoint(con, name, &name_norm, x, y, kind, note.as_deref())?;
             ui::info(format!("Waypoint created: id={} name='{}'", id, name));
             Ok(())
         }
 
-        WaypointCmd::List { limit, offset } => run_list(con, *limit, *offset),
+        WaypointCmd::List { limit, offset } => run_list(con, *limit, *offset, style),
 
-        WaypointCmd::Show { key } => run_show(con, key),
+        WaypointCmd::Show { key, json } => run_show(con, key, *json, style),
 
         WaypointCmd::Delete { id } => {
             // Optional: show what you're deleting
             if let Some(w) = queries::find_waypoint_by_id(con, *id)? {
                 ui::warning(format!("Deleting waypoint: {}", w.fmt_short()));
             } else {
-                bail!("Waypoint not found: id={}", id);
+                return Err(not_found(format!("Waypoint not found: id={}", id)));
             }
 
             // Before deleting waypoint, remove links (even if ON DELETE CASCADE should handle it)
@@ -70,15 +103,98 @@ pub fn run_waypoint(con: &mut Connection, cmd: &WaypointCmd) -> Result<()> {
             Ok(())
         }
 
+        WaypointCmd::Near {
+            planet,
+            x,
+            y,
+            range,
+            limit,
+        } => run_near(con, planet.as_deref(), *x, *y, *range, *limit),
+
+        WaypointCmd::Move { id, dx, dy } => {
+            let Some(before) = queries::find_waypoint_by_id(con, *id)? else {
+                return Err(not_found(format!("Waypoint not found: id={}", id)));
+            };
+            if before.kind == "computed" {
+                bail!(
+                    "Waypoint id={} is a computed detour owned by the router; it cannot be moved",
+                    id
+                );
+            }
+
+            let update = queries::WaypointUpdate {
+                x: Some(before.x + dx),
+                y: Some(before.y + dy),
+                ..Default::default()
+            };
+            let after = queries::update_waypoint(con, *id, &update)?;
+            ui::success(format!(
+                "Waypoint '{}' moved: ({:.3}, {:.3}) -> ({:.3}, {:.3})",
+                after.name, before.x, before.y, after.x, after.y
+            ));
+            Ok(())
+        }
+
+        WaypointCmd::Rename { id, new_name } => {
+            let Some(before) = queries::find_waypoint_by_id(con, *id)? else {
+                return Err(not_found(format!("Waypoint not found: id={}", id)));
+            };
+            let after = queries::rename_waypoint(con, *id, new_name)?;
+            ui::success(format!(
+                "Waypoint id={} renamed: '{}' -> '{}'",
+                after.id, before.name, after.name
+            ));
+            Ok(())
+        }
+
+        WaypointCmd::Update {
+            id,
+            name,
+            x,
+            y,
+            kind,
+            note,
+        } => {
+            let update = queries::WaypointUpdate {
+                name: name.clone(),
+                x: *x,
+                y: *y,
+                kind: kind.clone(),
+                note: note.clone(),
+            };
+            if update.is_empty() {
+                return Err(invalid(
+                    "Nothing to update: pass at least one of --name, --x, --y, --kind, --note",
+                ));
+            }
+
+            let w = queries::update_waypoint(con, *id, &update)?;
+            ui::success(format!("Waypoint updated: {}", w.fmt_short()));
+            Ok(())
+        }
+
+        WaypointCmd::Link {
+            from_file: Some(path),
+            role,
+            ..
+        } => run_link_batch(con, path, role),
+
         WaypointCmd::Link {
             waypoint_id,
             planet,
             role,
             distance,
+            ..
         } => {
+            let (Some(waypoint_id), Some(planet)) = (waypoint_id, planet) else {
+                return Err(invalid(
+                    "Missing arguments: provide <WAYPOINT_ID> <PLANET> or --from-file",
+                ));
+            };
+
             // Ensure waypoint exists
             let Some(wp) = queries::find_waypoint_by_id(con, *waypoint_id)? else {
-                bail!("Waypoint not found: id={}", waypoint_id);
+                return Err(not_found(format!("Waypoint not found: id={}", waypoint_id)));
             };
 
             // Resolve planet (name or alias)
@@ -93,7 +209,11 @@ pub fn run_waypoint(con: &mut Connection, cmd: &WaypointCmd) -> Result<()> {
             Ok(())
         }
 
-        WaypointCmd::Links { waypoint_id } => run_waypoint_links(con, *waypoint_id),
+        WaypointCmd::Export { file, .. } => run_export(con, file.as_deref()),
+
+        WaypointCmd::Import { file, dry_run } => run_import(con, file, *dry_run),
+
+        WaypointCmd::Links { waypoint_id } => run_waypoint_links(con, *waypoint_id, style),
 
         WaypointCmd::ForPlanet {
             planet,
@@ -126,29 +246,331 @@ pub fn run_waypoint(con: &mut Connection, cmd: &WaypointCmd) -> Result<()> {
         WaypointCmd::Unlink {
             waypoint_id,
             planet,
+            role,
         } => {
             let p = resolve_planet_for_waypoint(con, planet)?;
-            let n = queries::unlink_waypoint_from_planet(con, *waypoint_id, p.fid)?;
+            let n =
+                queries::unlink_waypoint_from_planet(con, *waypoint_id, p.fid, role.as_deref())?;
             if n == 0 {
-                bail!(
-                    "No link found for waypoint_id={} planet_fid={}",
-                    waypoint_id,
-                    p.fid
-                );
+                match role {
+                    Some(r) => bail!(
+                        "No link found for waypoint_id={} planet_fid={} role='{}'",
+                        waypoint_id,
+                        p.fid,
+                        r
+                    ),
+                    None => bail!(
+                        "No link found for waypoint_id={} planet_fid={}",
+                        waypoint_id,
+                        p.fid
+                    ),
+                }
             }
-            ui::success("Link removed");
+            ui::success(format!("Link removed ({} row(s))", n));
             Ok(())
         }
 
         WaypointCmd::Prune {
             dry_run,
             include_linked,
-        } => run_waypoint_prune(con, *dry_run, *include_linked),
+            older_than,
+        } => run_waypoint_prune(con, *dry_run, *include_linked, *older_than, style),
+    }
+}
+
+/// Parses one `waypoint_id,planet,role[,distance]` record.
+fn parse_link_record<'a>(
+    rec: &'a csv::StringRecord,
+    default_role: &'a str,
+) -> Result<(i64, &'a str, &'a str, Option<f64>)> {
+    let field = |i: usize| rec.get(i).map(str::trim).unwrap_or("");
+
+    let waypoint_id = field(0)
+        .parse::<i64>()
+        .map_err(|_| anyhow::anyhow!("invalid waypoint_id '{}'", field(0)))?;
+
+    let planet = field(1);
+    if planet.is_empty() {
+        bail!("missing planet");
+    }
+
+    let role = match field(2) {
+        "" => default_role,
+        r => r,
+    };
+
+    let distance = match field(3) {
+        "" => None,
+        d => Some(
+            d.parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| anyhow::anyhow!("invalid distance '{}'", d))?,
+        ),
+    };
+
+    Ok((waypoint_id, planet, role, distance))
+}
+
+/// `waypoint link --from-file`: all rows in one transaction; bad rows are
+/// reported and skipped, the others are committed.
+fn run_link_batch(con: &mut Connection, path: &std::path::Path, default_role: &str) -> Result<()> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .comment(Some(b'#'))
+        .from_path(path)
+        .with_context(|| format!("Unable to open link file: {}", path.display()))?;
+
+    let tx = con
+        .transaction()
+        .context("Failed to start waypoint link transaction")?;
+
+    let mut linked = 0usize;
+    let mut failed = 0usize;
+
+    for (idx, rec) in rdr.records().enumerate() {
+        let line = idx + 1;
+        let outcome = rec.map_err(anyhow::Error::from).and_then(|rec| {
+            // Optional header row.
+            if line == 1
+                && rec
+                    .get(0)
+                    .is_some_and(|f| f.trim().eq_ignore_ascii_case("waypoint_id"))
+            {
+                return Ok(None);
+            }
+
+            let (waypoint_id, planet, role, distance) = parse_link_record(&rec, default_role)?;
+            let Some(wp) = queries::find_waypoint_by_id(&tx, waypoint_id)? else {
+                return Err(not_found(format!("waypoint not found: id={}", waypoint_id)));
+            };
+            let p = resolve_planet_for_waypoint(&tx, planet)?;
+            queries::link_waypoint_to_planet(&tx, wp.id, p.fid, role, distance)?;
+            Ok(Some((wp.id, p.planet, p.fid, role.to_string())))
+        });
+
+        match outcome {
+            Ok(None) => {}
+            Ok(Some((wp_id, planet, fid, role))) => {
+                linked += 1;
+                println!(
+                    "  row {}: waypoint id={} -> '{}' (fid={}) role={}",
+                    line, wp_id, planet, fid, role
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                ui::warning(format!("row {}: {:#}", line, e));
+            }
+        }
     }
+
+    tx.commit()
+        .context("Failed to commit waypoint link transaction")?;
+
+    if linked == 0 && failed > 0 {
+        bail!("No links created: all {} row(s) failed", failed);
+    }
+
+    if failed > 0 {
+        ui::warning(format!(
+            "Linked {} row(s), {} row(s) failed",
+            linked, failed
+        ));
+    } else {
+        ui::success(format!("Linked {} row(s)", linked));
+    }
+
+    Ok(())
 }
 
-fn run_list(con: &Connection, limit: usize, offset: usize) -> Result<()> {
-    let style = Style::default();
+/// Catalog entry of `waypoint export`: the waypoint plus its planet links.
+#[derive(Serialize)]
+struct WaypointExport<'a> {
+    #[serde(flatten)]
+    waypoint: &'a Waypoint,
+    links: Vec<&'a WaypointPlanetLink>,
+}
+
+fn run_export(con: &Connection, file: Option<&std::path::Path>) -> Result<()> {
+    let waypoints = queries::list_all_waypoints(con)?;
+    let links = queries::list_all_waypoint_links(con)?;
+
+    let mut by_waypoint: HashMap<i64, Vec<&WaypointPlanetLink>> = HashMap::new();
+    for l in &links {
+        by_waypoint.entry(l.waypoint_id).or_default().push(l);
+    }
+    let export: Vec<WaypointExport> = waypoints
+        .iter()
+        .map(|w| WaypointExport {
+            waypoint: w,
+            links: by_waypoint.remove(&w.id).unwrap_or_default(),
+        })
+        .collect();
+
+    let s = serde_json::to_string_pretty(&export)?;
+
+    if let Some(path) = file {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut f = fs::File::create(path)?;
+        f.write_all(s.as_bytes())?;
+        f.write_all(b"\n")?;
+        ui::success(format!(
+            "{} waypoint(s) exported to {}",
+            export.len(),
+            path.display()
+        ));
+    } else {
+        println!("{}", s);
+    }
+
+    Ok(())
+}
+
+/// One waypoint parsed from a `name,x,y[,kind][,note]` import row.
+struct ImportRow {
+    name: String,
+    x: f64,
+    y: f64,
+    kind: String,
+    note: Option<String>,
+}
+
+fn parse_import_record(rec: &csv::StringRecord) -> Result<ImportRow> {
+    let field = |i: usize| rec.get(i).map(str::trim).unwrap_or("");
+    let coord = |i: usize, label: &str| {
+        field(i)
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| anyhow::anyhow!("invalid {} '{}'", label, field(i)))
+    };
+
+    let name = field(0);
+    if name.is_empty() {
+        bail!("missing name");
+    }
+
+    Ok(ImportRow {
+        name: name.to_string(),
+        x: coord(1, "x")?,
+        y: coord(2, "y")?,
+        kind: match field(3) {
+            "" => "manual".to_string(),
+            k => k.to_string(),
+        },
+        note: Some(field(4)).filter(|n| !n.is_empty()).map(str::to_string),
+    })
+}
+
+/// `waypoint import`: all rows in one transaction; duplicates and bad rows are
+/// reported and skipped, the others are committed (nothing is written on dry runs).
+fn run_import(con: &mut Connection, path: &std::path::Path, dry_run: bool) -> Result<()> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .comment(Some(b'#'))
+        .from_path(path)
+        .with_context(|| format!("Unable to open waypoint file: {}", path.display()))?;
+
+    if dry_run {
+        ui::warning("DRY-RUN mode enabled: no waypoints will be written");
+    }
+
+    let tx = con
+        .transaction()
+        .context("Failed to start waypoint import transaction")?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut inserted = 0usize;
+    let mut skipped = 0usize;
+    let mut failed = 0usize;
+
+    for (idx, rec) in rdr.records().enumerate() {
+        let line = idx + 1;
+        let rec = match rec {
+            Ok(rec) => rec,
+            Err(e) => {
+                failed += 1;
+                ui::warning(format!("row {}: {:#}", line, e));
+                continue;
+            }
+        };
+        // Optional header row.
+        if line == 1
+            && rec
+                .get(0)
+                .is_some_and(|f| f.trim().eq_ignore_ascii_case("name"))
+        {
+            continue;
+        }
+
+        let row = match parse_import_record(&rec) {
+            Ok(row) => row,
+            Err(e) => {
+                failed += 1;
+                ui::warning(format!("row {}: {:#}", line, e));
+                continue;
+            }
+        };
+
+        let name_norm = normalize_text(&row.name);
+        if !seen.insert(name_norm.clone()) {
+            skipped += 1;
+            ui::warning(format!(
+                "row {}: duplicate of an earlier row: '{}'",
+                line, row.name
+            ));
+            continue;
+        }
+        if let Some(existing) = queries::find_waypoint_by_norm(&tx, &name_norm)? {
+            skipped += 1;
+            ui::warning(format!(
+                "row {}: waypoint already exists: '{}' (id={})",
+                line, existing.name, existing.id
+            ));
+            continue;
+        }
+
+        if !dry_run {
+            let id = queries::insert_waypoint(
+                &tx,
+                &row.name,
+                &name_norm,
+                row.x,
+                row.y,
+                &row.kind,
+                row.note.as_deref(),
+            )?;
+            println!("  row {}: '{}' -> id={}", line, row.name, id);
+        }
+        inserted += 1;
+    }
+
+    if dry_run {
+        drop(tx);
+        ui::success("Dry-run completed (no changes written)");
+        ui::info(format!("would insert: {}", inserted));
+        ui::info(format!("would skip: {}", skipped));
+    } else {
+        tx.commit()
+            .context("Failed to commit waypoint import transaction")?;
+        ui::success("Import completed");
+        ui::info(format!("inserted: {}", inserted));
+        ui::info(format!("skipped: {}", skipped));
+    }
+    if failed > 0 {
+        ui::warning(format!("invalid rows: {}", failed));
+    }
+
+    Ok(())
+}
+
+fn run_list(con: &Connection, limit: usize, offset: usize, style: Style) -> Result<()> {
     let c = Colors::new(&style);
 
     let (items, total) = queries::list_waypoints(con, limit, offset)?;
@@ -229,10 +651,42 @@ fn run_list(con: &Connection, limit: usize, offset: usize) -> Result<()> {
     Ok(())
 }
 
-fn run_show(con: &Connection, key: &String) -> Result<()> {
-    let style = Style::default();
-    let c = Colors::new(&style);
+fn run_near(
+    con: &Connection,
+    planet: Option<&str>,
+    x: Option<f64>,
+    y: Option<f64>,
+    range: f64,
+    limit: usize,
+) -> Result<()> {
+    let (cx, cy, center) = match (planet, x, y) {
+        (Some(planet), _, _) => {
+            let p = resolve_planet_for_waypoint(con, planet)?;
+            (p.x, p.y, format!("{} (fid={})", p.planet, p.fid))
+        }
+        (None, Some(x), Some(y)) => (x, y, format!("({:.3}, {:.3})", x, y)),
+        _ => {
+            return Err(invalid(
+                "Missing center: provide --planet or both --x and --y",
+            ));
+        }
+    };
+
+    let hits = queries::near_waypoints(con, cx, cy, range, limit)?;
 
+    println!("Waypoints within {:.3} parsec of {}:", range, center);
+    if hits.is_empty() {
+        println!("(none)");
+        return Ok(());
+    }
+    for (w, d) in &hits {
+        println!("  d={:>10.3}  {}", d, w.fmt_short());
+    }
+
+    Ok(())
+}
+
+fn run_show(con: &Connection, key: &String, json: bool, style: Style) -> Result<()> {
     let wp = if let Ok(id) = key.parse::<i64>() {
         queries::find_waypoint_by_id(con, id)?
     } else {
@@ -241,9 +695,16 @@ fn run_show(con: &Connection, key: &String) -> Result<()> {
     };
 
     let Some(w) = wp else {
-        bail!("Waypoint not found: {}", key);
+        return Err(not_found(format!("Waypoint not found: {}", key)));
     };
 
+    if json {
+        println!("{}", serde_json::to_string_pretty(&w)?);
+        return Ok(());
+    }
+
+    let c = Colors::new(&style);
+
     println!("{}", c.ok("Waypoint details:"));
     println!();
 
@@ -270,8 +731,7 @@ fn run_show(con: &Connection, key: &String) -> Result<()> {
     Ok(())
 }
 
-pub fn run_waypoint_links(con: &Connection, waypoint_id: i64) -> Result<()> {
-    let style = Style::default();
+pub fn run_waypoint_links(con: &Connection, waypoint_id: i64, style: Style) -> Result<()> {
     let c = Colors::new(&style);
 
     // Header: show waypoint if exists
@@ -360,10 +820,15 @@ pub fn run_waypoint_links(con: &Connection, waypoint_id: i64) -> Result<()> {
     Ok(())
 }
 
-pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: bool) -> Result<()> {
+pub fn run_waypoint_prune(
+    con: &mut Connection,
+    dry_run: bool,
+    include_linked: bool,
+    older_than_days: Option<u32>,
+    style: Style,
+) -> Result<()> {
     use anyhow::Context;
 
-    let style = Style::default();
     let c = Colors::new(&style);
 
     #[derive(Debug)]
@@ -374,6 +839,10 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
         links_count: i64,
     }
 
+    // SQLite date modifier for the age cutoff (NULL = no age filter). julianday() parses
+    // both the `datetime('now')` and the ISO-8601 timestamps stored in waypoints.
+    let age_modifier: Option<String> = older_than_days.map(|d| format!("-{} days", d));
+
     // 1) Load candidates in a scope so stmt is dropped before transaction
     let candidates: Vec<Candidate> = {
         let sql = if include_linked {
@@ -387,6 +856,7 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
             WHERE
               w.kind = 'computed'
               AND NOT EXISTS (SELECT 1 FROM route_waypoints rw WHERE rw.waypoint_id = w.id)
+              AND (?1 IS NULL OR julianday(COALESCE(w.updated_at, w.created_at)) < julianday('now', ?1))
             ORDER BY updated DESC, w.id DESC
             "#
         } else {
@@ -401,12 +871,13 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
               w.kind = 'computed'
               AND NOT EXISTS (SELECT 1 FROM route_waypoints rw WHERE rw.waypoint_id = w.id)
               AND NOT EXISTS (SELECT 1 FROM waypoint_planets wp WHERE wp.waypoint_id = w.id)
+              AND (?1 IS NULL OR julianday(COALESCE(w.updated_at, w.created_at)) < julianday('now', ?1))
             ORDER BY updated DESC, w.id DESC
             "#
         };
 
         let mut stmt = con.prepare(sql)?;
-        stmt.query_map([], |row| {
+        stmt.query_map([&age_modifier], |row| {
             Ok(Candidate {
                 id: row.get(0)?,
                 name: row.get(1)?,
@@ -430,6 +901,15 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
             if dry_run { " (dry-run)" } else { "" }
         ))
     );
+    if let Some(days) = older_than_days {
+        println!(
+            "{}",
+            c.dim(format!(
+                "Only waypoints not updated in the last {} day(s).",
+                days
+            ))
+        );
+    }
     println!(
         "{}",
         c.dim(format!(
@@ -481,9 +961,10 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
               WHERE
                 w.kind = 'computed'
                 AND NOT EXISTS (SELECT 1 FROM route_waypoints rw WHERE rw.waypoint_id = w.id)
+                AND (?1 IS NULL OR julianday(COALESCE(w.updated_at, w.created_at)) < julianday('now', ?1))
             )
             "#,
-            [],
+            [&age_modifier],
         )?;
     }
 
@@ -495,8 +976,9 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
             WHERE
               kind = 'computed'
               AND NOT EXISTS (SELECT 1 FROM route_waypoints rw WHERE rw.waypoint_id = waypoints.id)
+              AND (?1 IS NULL OR julianday(COALESCE(waypoints.updated_at, waypoints.created_at)) < julianday('now', ?1))
             "#,
-            [],
+            [&age_modifier],
         )?
     } else {
         tx.execute(
@@ -506,8 +988,9 @@ pub fn run_waypoint_prune(con: &mut Connection, dry_run: bool, include_linked: b
               kind = 'computed'
               AND NOT EXISTS (SELECT 1 FROM route_waypoints rw WHERE rw.waypoint_id = waypoints.id)
               AND NOT EXISTS (SELECT 1 FROM waypoint_planets wp WHERE wp.waypoint_id = waypoints.id)
+              AND (?1 IS NULL OR julianday(COALESCE(waypoints.updated_at, waypoints.created_at)) < julianday('now', ?1))
             "#,
-            [],
+            [&age_modifier],
         )?
     };
 
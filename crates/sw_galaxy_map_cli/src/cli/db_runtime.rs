@@ -1,7 +1,12 @@
 use crate::cli::print_db_init_report;
+use crate::cli::progress::DownloadProgress;
 use crate::ui::warning;
 use std::path::Path;
+use std::sync::{Once, OnceLock};
 use sw_galaxy_map_core::db::db_status::resolve_db_path;
+use sw_galaxy_map_core::db::migrate::MigrationReport;
+use sw_galaxy_map_core::provision::arcgis::RetryPolicy;
+use sw_galaxy_map_core::provision::cancel;
 
 pub(crate) fn open_db_raw(db_arg: Option<String>) -> anyhow::Result<rusqlite::Connection> {
     let db_path = resolve_db_path(db_arg)?;
@@ -9,12 +14,80 @@ pub(crate) fn open_db_raw(db_arg: Option<String>) -> anyhow::Result<rusqlite::Co
     sw_galaxy_map_core::db::open_db(&db_path.to_string_lossy())
 }
 
+static STRICT_SCHEMA: OnceLock<bool> = OnceLock::new();
+
+/// Sets the process-wide `--strict` mode used by [`open_db_migrating`].
+///
+/// Only the first call has an effect.
+pub(crate) fn init_strict_schema(strict: bool) {
+    let _ = STRICT_SCHEMA.set(strict);
+}
+
+fn strict_schema() -> bool {
+    STRICT_SCHEMA.get().copied().unwrap_or(false)
+}
+
 pub(crate) fn open_db_migrating(db_arg: Option<String>) -> anyhow::Result<rusqlite::Connection> {
     let mut con = open_db_raw(db_arg)?;
-    let _ = sw_galaxy_map_core::db::migrate::run(&mut con, false, false)?;
+    let report = sw_galaxy_map_core::db::migrate::run(&mut con, false, false)?;
+    check_newer_schema(&report)?;
     Ok(con)
 }
 
+/// `db migrate` variant: the report already shows the warning, so only `--strict` matters.
+pub(crate) fn ensure_schema_not_newer(report: &MigrationReport) -> anyhow::Result<()> {
+    if report.newer_than_binary() && strict_schema() {
+        anyhow::bail!(
+            "Refusing to continue with --strict: database schema is newer than this binary"
+        );
+    }
+    Ok(())
+}
+
+/// Warns (once per process) about a database created by a newer version, or
+/// fails under `--strict`.
+fn check_newer_schema(report: &MigrationReport) -> anyhow::Result<()> {
+    static WARNED: Once = Once::new();
+
+    let Some(msg) = report.newer_schema_warning() else {
+        return Ok(());
+    };
+    if STRICT_SCHEMA.get().copied().unwrap_or(false) {
+        anyhow::bail!("{} (refusing to continue with --strict)", msg);
+    }
+    // stderr, so commands printing JSON on stdout stay parseable.
+    WARNED.call_once(|| eprintln!("⚠️ {}", msg));
+    Ok(())
+}
+
+/// Retries used by `db init` / `db update` unless `--max-retries` says otherwise.
+pub(crate) const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// ArcGIS retry policy that reports each retry as a warning.
+pub(crate) fn fetch_retry_policy(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        on_retry: Some(|msg| warning(msg)),
+        ..Default::default()
+    }
+}
+
+/// Route Ctrl-C to the provisioning cancel flag (idempotent).
+///
+/// The first SIGINT stops `db init` / `db update` at the next safe point; a
+/// second one exits immediately.
+pub(crate) fn install_interrupt_handler() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let _ = ctrlc::set_handler(|| {
+            if cancel::request() {
+                std::process::exit(130);
+            }
+            eprintln!("\nInterrupt received: stopping at the next safe point...");
+        });
+    });
+}
+
 fn ensure_db_ready(db_path: &Path) -> anyhow::Result<()> {
     if db_path.exists() {
         return Ok(());
@@ -26,8 +99,17 @@ fn ensure_db_ready(db_path: &Path) -> anyhow::Result<()> {
         db_path.display()
     ));
 
-    let report =
-        sw_galaxy_map_core::db::db_init::run(Some(db_path.to_string_lossy().to_string()), false)?;
+    install_interrupt_handler();
+    let mut progress = DownloadProgress::new();
+    let report = sw_galaxy_map_core::db::db_init::run(
+        Some(db_path.to_string_lossy().to_string()),
+        false,
+        None,
+        None,
+        &fetch_retry_policy(DEFAULT_MAX_RETRIES),
+        &mut |n, total| progress.update(n, total),
+    )?;
+    drop(progress);
     print_db_init_report(&report);
     Ok(())
 }
@@ -1,6 +1,9 @@
+use crate::cli::db_runtime::{fetch_retry_policy, install_interrupt_handler};
+use crate::cli::progress::DownloadProgress;
 use crate::cli::{
-    args, commands, open_db_migrating, open_db_raw, print_db_init_report, print_db_status_report,
-    print_db_update_report, print_galaxy_stats, print_migration_report,
+    args, commands, open_db_migrating, open_db_raw, print_csv_import_report, print_db_init_report,
+    print_db_status_report, print_db_update_jsonl, print_db_update_report, print_distinct_values,
+    print_galaxy_stats, print_migration_report,
 };
 use crate::ui::{info, success};
 use sw_galaxy_map_core::validate;
@@ -8,14 +11,34 @@ use sw_galaxy_map_core::validate;
 pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Result<()> {
     match cmd {
         args::Commands::Db { cmd } => match cmd {
-            args::DbCommands::Init { out, force } => {
-                let report = sw_galaxy_map_core::db::db_init::run(out.clone(), *force)?;
+            args::DbCommands::Init {
+                out,
+                force,
+                dedupe,
+                service_url,
+                max_retries,
+            } => {
+                install_interrupt_handler();
+                let mut progress = DownloadProgress::new();
+                let report = sw_galaxy_map_core::db::db_init::run(
+                    out.clone(),
+                    *force,
+                    *dedupe,
+                    service_url.as_deref(),
+                    &fetch_retry_policy(*max_retries),
+                    &mut |n, total| progress.update(n, total),
+                )?;
+                drop(progress);
                 print_db_init_report(&report);
                 Ok(())
             }
 
-            args::DbCommands::Status => {
-                let report = sw_galaxy_map_core::db::db_status::run(cli.db.clone())?;
+            args::DbCommands::Status { sizes, json } => {
+                let report = sw_galaxy_map_core::db::db_status::run(cli.db.clone(), *sizes)?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
                 print_db_status_report(&report);
                 Ok(())
             }
@@ -23,17 +46,45 @@ pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Res
             args::DbCommands::Update {
                 prune,
                 dry_run,
+                no_mark_deleted,
                 stats,
                 stats_limit,
+                stats_json,
+                since,
+                bench,
+                parallel_hash,
+                service_url,
+                max_retries,
             } => {
+                use sw_galaxy_map_core::db::db_update::{UpdateSince, parse_since};
+
+                let since = match since {
+                    None => None,
+                    Some(None) => Some(UpdateSince::Stored),
+                    Some(Some(s)) => Some(UpdateSince::At(parse_since(s)?)),
+                };
                 let mut con = open_db_migrating(cli.db.clone())?;
+                install_interrupt_handler();
+                let mut progress = DownloadProgress::new();
                 let report = sw_galaxy_map_core::db::db_update::run(
                     &mut con,
                     *prune,
                     *dry_run,
+                    !*no_mark_deleted,
                     *stats,
                     *stats_limit,
+                    *stats_json,
+                    since,
+                    *bench,
+                    *parallel_hash,
+                    service_url.as_deref(),
+                    &fetch_retry_policy(*max_retries),
+                    &mut |n, total| progress.update(n, total),
                 )?;
+                drop(progress);
+                if *stats_json {
+                    return print_db_update_jsonl(&report);
+                }
                 print_db_update_report(&report);
                 Ok(())
             }
@@ -43,12 +94,13 @@ pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Res
                 sw_galaxy_map_core::db::db_skipped_planets::run(&mut con)
             }
 
-            args::DbCommands::Migrate { dry_run } => {
+            args::DbCommands::Migrate { dry_run, to } => {
                 // IMPORTANT: do not auto-migrate before running migrate
                 let mut con = open_db_raw(cli.db.clone())?;
-                let report = sw_galaxy_map_core::db::migrate::run(&mut con, *dry_run, true)?;
+                let report =
+                    sw_galaxy_map_core::db::migrate::run_to(&mut con, *dry_run, true, *to)?;
                 print_migration_report(&report);
-                Ok(())
+                crate::cli::db_runtime::ensure_schema_not_newer(&report)
             }
 
             args::DbCommands::RebuildSearch => {
@@ -59,6 +111,32 @@ pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Res
                 Ok(())
             }
 
+            args::DbCommands::Verify => {
+                // Do not auto-migrate: an outdated schema is one of the checks.
+                let con = open_db_raw(cli.db.clone())?;
+                commands::db::verify::run(&con)
+            }
+
+            args::DbCommands::ImportCsv {
+                file,
+                dry_run,
+                mark_deleted,
+            } => {
+                let mut con = open_db_migrating(cli.db.clone())?;
+                let report = sw_galaxy_map_core::db::db_import_csv::run(
+                    &mut con,
+                    file,
+                    *dry_run,
+                    *mark_deleted,
+                )?;
+                print_csv_import_report(&report);
+                Ok(())
+            }
+
+            args::DbCommands::Vacuum => commands::db::maintenance::run_vacuum(cli.db.clone()),
+
+            args::DbCommands::Optimize => commands::db::maintenance::run_optimize(cli.db.clone()),
+
             args::DbCommands::Stats { top } => {
                 let con = open_db_migrating(cli.db.clone())?;
                 let s = sw_galaxy_map_core::db::queries::galaxy_stats(&con, *top)?;
@@ -66,6 +144,18 @@ pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Res
                 Ok(())
             }
 
+            args::DbCommands::History { limit, json } => {
+                let con = open_db_migrating(cli.db.clone())?;
+                commands::db::history::run(&con, *limit, *json)
+            }
+
+            args::DbCommands::Values { column } => {
+                let con = open_db_migrating(cli.db.clone())?;
+                let rows = sw_galaxy_map_core::db::queries::distinct_values(&con, *column)?;
+                print_distinct_values(*column, &rows);
+                Ok(())
+            }
+
             args::DbCommands::Sync {
                 csv,
                 table,
@@ -131,10 +221,16 @@ pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Res
             sector,
             grid,
             status,
+            by_ref,
             canon,
             legends,
             fuzzy,
+            strict_fts,
+            prefix,
+            scores,
+            json,
             limit,
+            offset,
         } => {
             let filter = sw_galaxy_map_core::model::SearchFilter {
                 query: query.clone(),
@@ -142,48 +238,122 @@ pub(crate) fn run_one_shot(cli: &args::Cli, cmd: &args::Commands) -> anyhow::Res
                 sector: sector.clone(),
                 grid: grid.clone(),
                 status: status.clone(),
+                reference: by_ref.clone(),
                 canon: if *canon { Some(true) } else { None },
                 legends: if *legends { Some(true) } else { None },
                 fuzzy: *fuzzy,
+                prefix: *prefix,
                 limit: *limit,
+                offset: *offset,
             };
             validate::validate_search(&filter)?;
             let con = open_db_migrating(cli.db.clone())?;
-            commands::search::run(&con, filter)
+            commands::search::run(&con, filter, *scores, *json, *strict_fts)
         }
 
-        args::Commands::Info { planet } => {
+        args::Commands::Info {
+            planet,
+            show_nearest,
+            alias_limit,
+            relations,
+            siblings,
+            json,
+            open,
+        } => {
             let con = open_db_migrating(cli.db.clone())?;
-            commands::info::run(&con, planet.clone())
+            if *json {
+                commands::info::run_json(&con, planet, *alias_limit)
+            } else {
+                commands::info::run(
+                    &con,
+                    planet.clone(),
+                    *show_nearest,
+                    *alias_limit,
+                    *relations,
+                    *siblings,
+                    *open,
+                )
+            }
         }
 
         args::Commands::Near {
             range,
+            min_r,
             unknown,
             fid,
             planet,
             x,
             y,
             limit,
+            exclude_fid,
+            sort,
+            canon,
+            legends,
         } => {
             validate::validate_near(*unknown, fid, planet, x, y)?;
+            validate::validate_near_radius(*range, *min_r)?;
+            let filter = sw_galaxy_map_core::model::NearFilter {
+                min_r: *min_r,
+                sort: *sort,
+                canon: *canon,
+                legends: *legends,
+            };
+            let con = open_db_migrating(cli.db.clone())?;
+            commands::near::run(
+                &con,
+                *range,
+                *unknown,
+                *fid,
+                planet.clone(),
+                *x,
+                *y,
+                *limit,
+                exclude_fid,
+                &filter,
+            )
+        }
+
+        args::Commands::Distance { from, to, json } => {
             let con = open_db_migrating(cli.db.clone())?;
-            commands::near::run(&con, *range, *unknown, *fid, planet.clone(), *x, *y, *limit)
+            commands::distance::run(&con, from, to, *json)
+        }
+
+        args::Commands::Hyperspace {
+            from,
+            to,
+            class,
+            region_override,
+            compression_config,
+        } => {
+            let con = open_db_migrating(cli.db.clone())?;
+            commands::hyperspace::run(
+                &con,
+                from,
+                to,
+                *class,
+                region_override.as_deref(),
+                compression_config.as_deref(),
+            )
         }
 
         args::Commands::Waypoint { cmd } => {
             let mut con = open_db_migrating(cli.db.clone())?;
-            commands::waypoints::run_waypoint(&mut con, cmd)
+            commands::waypoints::run_waypoint(&mut con, cmd, cli.style())
         }
 
         args::Commands::Route { cmd } => {
             let mut con = open_db_migrating(cli.db.clone())?;
-            commands::route::run(&mut con, cmd)
+            commands::route::run(&mut con, cmd, cli.style())
         }
 
         args::Commands::Unknown { cmd } => {
             let con = open_db_migrating(cli.db.clone())?;
             commands::unknown::run(&con, cmd)
         }
+
+        args::Commands::Planets { cmd } => {
+            let mut con = open_db_migrating(cli.db.clone())?;
+            commands::planets::run(&mut con, cmd)
+        }
     }
 }
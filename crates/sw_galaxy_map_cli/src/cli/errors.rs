@@ -0,0 +1,121 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Error payload printed on stdout when a `--json` command fails.
+#[derive(Debug, Serialize)]
+pub struct JsonError {
+    pub error: String,
+    pub kind: &'static str,
+}
+
+/// Failure category a command attaches to an error it raises itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The requested planet, route, waypoint or file does not exist.
+    NotFound,
+    /// The arguments are inconsistent or out of range.
+    Validation,
+}
+
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Validation => "validation",
+        }
+    }
+}
+
+/// An error message tagged with its [`ErrorKind`], read back by [`error_kind`]
+/// with a downcast instead of matching on the wording.
+#[derive(Debug)]
+pub struct KindError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl fmt::Display for KindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for KindError {}
+
+/// A `not_found` error with `message`.
+pub fn not_found(message: impl Into<String>) -> anyhow::Error {
+    KindError {
+        kind: ErrorKind::NotFound,
+        message: message.into(),
+    }
+    .into()
+}
+
+/// A `validation` error with `message`.
+pub fn invalid(message: impl Into<String>) -> anyhow::Error {
+    KindError {
+        kind: ErrorKind::Validation,
+        message: message.into(),
+    }
+    .into()
+}
+
+/// Coarse classification of an error, for programmatic consumers.
+///
+/// The outermost typed cause in the chain decides: a [`KindError`] raised by a
+/// command, or a database / io / serialization error from below.
+pub fn error_kind(err: &anyhow::Error) -> &'static str {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<KindError>() {
+            return e.kind.as_str();
+        }
+        if cause.is::<rusqlite::Error>() {
+            return "database";
+        }
+        if cause.is::<std::io::Error>() {
+            return "io";
+        }
+        if cause.is::<serde_json::Error>() {
+            return "serialization";
+        }
+    }
+    "error"
+}
+
+pub fn json_error(err: &anyhow::Error) -> JsonError {
+    JsonError {
+        error: format!("{:#}", err),
+        kind: error_kind(err),
+    }
+}
+
+/// Print the JSON error object on stdout.
+pub fn print_json_error(err: &anyhow::Error) {
+    match serde_json::to_string_pretty(&json_error(err)) {
+        Ok(s) => println!("{}", s),
+        Err(_) => println!(
+            "{{\"error\": {:?}, \"kind\": \"error\"}}",
+            format!("{:#}", err)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_comes_from_the_error_type_not_the_message() {
+        assert_eq!(error_kind(&not_found("Route not found: id=3")), "not_found");
+        assert_eq!(
+            error_kind(&invalid("Missing center").context("waypoint near")),
+            "validation"
+        );
+        // Wording alone no longer classifies an error.
+        assert_eq!(error_kind(&anyhow::anyhow!("Planet not found: x")), "error");
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        let wrapped = anyhow::Error::from(io).context("Invalid export path");
+        assert_eq!(error_kind(&wrapped), "io");
+    }
+}
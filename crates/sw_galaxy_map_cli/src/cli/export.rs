@@ -46,6 +46,15 @@ pub struct ExplainDetour {
 
     pub dominant_penalty: ExplainDominantPenalty,
     pub decision_drivers: Vec<String>,
+    pub drivers: Vec<ExplainDriver>,
+}
+
+/// Structured form of a `decision_drivers` entry.
+#[derive(Debug, Serialize)]
+pub struct ExplainDriver {
+    pub category: String,
+    pub message: String,
+    pub value: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
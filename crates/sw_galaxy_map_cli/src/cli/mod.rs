@@ -3,7 +3,9 @@ pub mod color;
 pub mod commands;
 pub(crate) mod db_runtime;
 pub(crate) mod dispatch;
+pub mod errors;
 pub mod export;
+pub(crate) mod progress;
 pub(crate) mod reports;
 pub(crate) mod shell;
 pub mod typewriter;
@@ -11,8 +13,8 @@ pub mod typewriter;
 pub(crate) use crate::cli::db_runtime::{open_db_migrating, open_db_raw};
 use crate::cli::dispatch::run_one_shot;
 pub(crate) use crate::cli::reports::{
-    print_db_init_report, print_db_status_report, print_db_update_report, print_galaxy_stats,
-    print_migration_report,
+    print_csv_import_report, print_db_init_report, print_db_status_report, print_db_update_jsonl,
+    print_db_update_report, print_distinct_values, print_galaxy_stats, print_migration_report,
 };
 use crate::cli::shell::run_interactive_shell;
 use anyhow::Result;
@@ -20,6 +22,8 @@ use clap::Parser;
 
 pub fn run() -> Result<()> {
     let cli = args::Cli::parse();
+    crate::ui::init_style(cli.style());
+    db_runtime::init_strict_schema(cli.strict);
 
     if cli.cmd.is_none() {
         return run_interactive_shell(cli.db.clone());
@@ -30,5 +34,15 @@ pub fn run() -> Result<()> {
         .as_ref()
         .ok_or_else(|| anyhow::anyhow!("missing command"))?;
 
-    run_one_shot(&cli, cmd)
+    let result = run_one_shot(&cli, cmd);
+
+    // Keep the JSON contract on the failure path too: error object on stdout, exit 1.
+    if let Err(e) = &result
+        && cli.wants_json()
+    {
+        errors::print_json_error(e);
+        std::process::exit(1);
+    }
+
+    result
 }
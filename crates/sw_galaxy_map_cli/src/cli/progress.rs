@@ -0,0 +1,58 @@
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::IsTerminal;
+
+/// Download progress for `db init` / `db update`, drawn on stderr.
+///
+/// Disabled when stdout is not a terminal, so piped or redirected output
+/// (including `--stats-json`) stays clean.
+pub(crate) struct DownloadProgress {
+    bar: Option<ProgressBar>,
+}
+
+impl DownloadProgress {
+    pub(crate) fn new() -> Self {
+        if !std::io::stdout().is_terminal() {
+            return Self { bar: None };
+        }
+
+        let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr());
+        bar.set_prefix("Downloading");
+        bar.set_style(Self::spinner_style());
+        Self { bar: Some(bar) }
+    }
+
+    fn spinner_style() -> ProgressStyle {
+        ProgressStyle::with_template("{prefix:<14}: {spinner} {pos} features")
+            .expect("valid progress template")
+    }
+
+    fn bar_style() -> ProgressStyle {
+        ProgressStyle::with_template(
+            "{prefix:<14}: [{percent:>3}%] {bar:40.cyan/blue} {pos}/{len} features",
+        )
+        .expect("valid progress template")
+        .progress_chars("#.-")
+    }
+
+    /// Progress callback for the ArcGIS download: `fetched` so far, `total` when known.
+    pub(crate) fn update(&mut self, fetched: usize, total: Option<usize>) {
+        let Some(bar) = &self.bar else {
+            return;
+        };
+        if let Some(total) = total
+            && bar.length() != Some(total as u64)
+        {
+            bar.set_length(total as u64);
+            bar.set_style(Self::bar_style());
+        }
+        bar.set_position(fetched as u64);
+    }
+}
+
+impl Drop for DownloadProgress {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+    }
+}
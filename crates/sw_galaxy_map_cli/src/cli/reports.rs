@@ -1,8 +1,10 @@
 use crate::tui::TuiCommandOutput;
 use crate::ui::{error, info, success, warning};
+use sw_galaxy_map_core::db::db_import_csv::CsvImportReport;
 use sw_galaxy_map_core::db::db_status::{DbHealth, DbStatusReport};
 use sw_galaxy_map_core::db::db_update::{ChangeKind, DbUpdateReport};
-use sw_galaxy_map_core::db::migrate::MigrationReport;
+use sw_galaxy_map_core::db::migrate::{LATEST_SCHEMA_VERSION, MigrationReport};
+use sw_galaxy_map_core::domain::{DedupePolicy, PlanetColumn};
 
 pub(crate) fn print_db_init_report(report: &sw_galaxy_map_core::db::db_init::DbInitReport) {
     println!(
@@ -10,13 +12,44 @@ pub(crate) fn print_db_init_report(report: &sw_galaxy_map_core::db::db_init::DbI
         report.out_path.display()
     );
     if report.overwritten_existing {
-        info("Existing database overwritten.");
+        match &report.backup_path {
+            Some(bak) => info(format!(
+                "Existing database overwritten (previous copy kept at {}).",
+                bak.display()
+            )),
+            None => info("Existing database overwritten."),
+        }
+    }
+    if report.recovered_incomplete {
+        let kept = report
+            .backup_path
+            .as_ref()
+            .map(|bak| format!(" (previous copy kept at {})", bak.display()))
+            .unwrap_or_default();
+        warning(format!(
+            "Existing database was left incomplete by an interrupted init: rebuilding it{}.",
+            kept
+        ));
     }
     println!("Downloading data from remote service...");
     info(format!(
         "Downloaded {} features.",
         report.downloaded_features
     ));
+    if let Some(policy) = report.dedupe {
+        let keep = match policy {
+            DedupePolicy::First => "first",
+            DedupePolicy::Last => "last",
+        };
+        if report.dropped_duplicates > 0 {
+            warning(format!(
+                "Dropped {} duplicate FID row(s) (kept {} occurrence).",
+                report.dropped_duplicates, keep
+            ));
+        } else {
+            info("No duplicate FIDs found in source data.");
+        }
+    }
     println!("Building SQLite database...");
     println!(
         "FTS5 enabled: {}",
@@ -46,12 +79,47 @@ pub(crate) fn print_db_status_report(report: &DbStatusReport) {
     }
 }
 
+/// `db update --stats-json`: one JSON object per change event, then a summary object.
+pub(crate) fn print_db_update_jsonl(report: &DbUpdateReport) -> anyhow::Result<()> {
+    for ev in &report.events {
+        println!("{}", serde_json::to_string(ev)?);
+    }
+
+    let s = &report.summary;
+    let summary = serde_json::json!({
+        "kind": "summary",
+        "dry_run": report.dry_run,
+        "downloaded_features": report.downloaded_features,
+        "since": report.since,
+        "inserted": s.inserted,
+        "updated": s.updated,
+        "revived": s.revived,
+        "unchanged": s.unchanged,
+        "marked_deleted": s.marked_deleted,
+        "pruned": s.pruned,
+        "skipped": s.skipped,
+    });
+    println!("{}", serde_json::to_string(&summary)?);
+    Ok(())
+}
+
 pub(crate) fn print_db_update_report(report: &DbUpdateReport) {
     info("Fetching data from remote service...");
-    info(format!(
-        "Downloaded {} features. Comparing with local database...",
-        report.downloaded_features
-    ));
+    if let Some(reason) = &report.since_fallback {
+        warning(format!("--since ignored ({}): full download", reason));
+    }
+    match report.since {
+        Some(ms) => info(format!(
+            "Downloaded {} features edited since {} (incremental). Comparing with local database...",
+            report.downloaded_features,
+            chrono::DateTime::from_timestamp_millis(ms)
+                .map_or_else(|| ms.to_string(), |t| t.to_rfc3339())
+        )),
+        None => info(format!(
+            "Downloaded {} features. Comparing with local database...",
+            report.downloaded_features
+        )),
+    }
     if report.dry_run {
         warning("DRY-RUN mode enabled: no changes will be written");
         if report.prune {
@@ -59,6 +127,10 @@ pub(crate) fn print_db_update_report(report: &DbUpdateReport) {
         }
     }
 
+    if !report.mark_deleted {
+        warning("Mark-deleted pass skipped: planets missing from the remote feed are preserved");
+    }
+
     if report.dry_run {
         success("Dry-run completed (no changes written)");
     } else {
@@ -141,14 +213,60 @@ pub(crate) fn print_db_update_report(report: &DbUpdateReport) {
             }
         }
     }
+
+    if let Some(t) = &report.timings {
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        println!();
+        info("Bench (phase timings):");
+        info(format!("  download: {:>10.1} ms", ms(t.download)));
+        info(format!("  hash:     {:>10.1} ms", ms(t.hash)));
+        info(format!("  compare:  {:>10.1} ms", ms(t.compare)));
+        info(format!("  total:    {:>10.1} ms", ms(t.total)));
+    }
+}
+
+pub(crate) fn print_csv_import_report(report: &CsvImportReport) {
+    info(format!(
+        "Read {} rows from {}",
+        report.rows,
+        report.file.display()
+    ));
+    if report.dry_run {
+        warning("DRY-RUN mode enabled: no changes will be written");
+        success("Dry-run completed (no changes written)");
+    } else {
+        success("CSV import completed. Search indexes rebuilt.");
+    }
+
+    info(format!("inserted: {}", report.inserted));
+    info(format!("updated: {}", report.updated));
+    info(format!("revived: {}", report.revived));
+    info(format!("unchanged: {}", report.unchanged));
+    if report.mark_deleted {
+        info(format!("marked deleted: {}", report.marked_deleted));
+    }
+    if report.skipped > 0 {
+        warning(format!("skipped rows without Planet: {}", report.skipped));
+    }
 }
 
 pub(crate) fn print_migration_report(report: &MigrationReport) {
+    if let Some(msg) = report.newer_schema_warning() {
+        warning(msg);
+        return;
+    }
     if report.noop {
-        info(format!(
-            "Database schema already up-to-date (v{})",
-            report.current_version
-        ));
+        if report.target_version < LATEST_SCHEMA_VERSION {
+            info(format!(
+                "Database schema already at target v{}",
+                report.current_version
+            ));
+        } else {
+            info(format!(
+                "Database schema already up-to-date (v{})",
+                report.current_version
+            ));
+        }
         return;
     }
     info(format!(
@@ -469,3 +587,39 @@ pub(crate) fn build_galaxy_stats_tui(
         }
     }
 }
+
+fn distinct_values_lines(column: PlanetColumn, rows: &[(String, i64)]) -> Vec<String> {
+    let label = column.sql_name().to_lowercase();
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+
+    if rows.is_empty() {
+        lines.push(format!("No {} values found.", label));
+        return lines;
+    }
+
+    lines.push(format!("Distinct {} values: {}", label, rows.len()));
+    let name_w = rows
+        .iter()
+        .map(|(n, _)| n.chars().count())
+        .max()
+        .unwrap_or(10)
+        .max(10);
+    for (name, cnt) in rows {
+        lines.push(format!("  {:<name_w$} : {:>6}", name, cnt));
+    }
+    lines
+}
+
+pub(crate) fn print_distinct_values(column: PlanetColumn, rows: &[(String, i64)]) {
+    for line in distinct_values_lines(column, rows) {
+        println!("{}", line);
+    }
+}
+
+pub(crate) fn build_distinct_values_tui(
+    column: PlanetColumn,
+    rows: &[(String, i64)],
+    out: &mut TuiCommandOutput,
+) {
+    out.log_lines.extend(distinct_values_lines(column, rows));
+}
@@ -1,5 +1,6 @@
 use crate::cli::commands::route::types::RouteListTuiItem;
 use crate::cli::typewriter::{TypewriterConfig, TypewriterState};
+use crate::tui::history::HistoryFile;
 use crate::tui::{NavigationPanelKind, build_navigation_panel};
 use ratatui::text::Line;
 use std::time::{Duration, Instant};
@@ -46,10 +47,14 @@ pub(crate) struct App {
 
     pub history: Vec<String>,
     pub history_index: Option<usize>,
+    /// Where `history` is persisted across sessions (`None` = in memory only).
+    pub history_file: Option<HistoryFile>,
 
     pub route_list_results: Vec<RouteListTuiItem>,
 
     pub session_db: Option<String>,
+    /// Read-only connection used by Tab completion, opened on first use.
+    pub completion_db: Option<rusqlite::Connection>,
     pub typewriter: TypewriterState,
     pub typewriter_config: TypewriterConfig,
 }
@@ -84,8 +89,10 @@ impl Default for App {
             selection_mode: SelectionMode::None,
             history: Vec::new(),
             history_index: None,
+            history_file: None,
             route_list_results: Vec::new(),
             session_db: None,
+            completion_db: None,
             typewriter: TypewriterState::default(),
             typewriter_config: TypewriterConfig::default(),
         }
@@ -121,10 +128,11 @@ impl App {
         };
     }
 
-    pub(crate) fn push_history(&mut self, command: &str) {
+    /// Adds `command` unless it repeats the last entry; returns true when added.
+    pub(crate) fn push_history(&mut self, command: &str) -> bool {
         let trimmed = command.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
 
         let should_push = match self.history.last() {
@@ -137,6 +145,7 @@ impl App {
         }
 
         self.history_index = None;
+        should_push
     }
 
     pub(crate) fn history_up(&mut self) {
@@ -9,6 +9,7 @@ use crate::tui::{
 use ratatui::prelude::{Color, Line, Modifier, Span, Style};
 use sw_galaxy_map_core::model::RouteLoaded;
 use sw_galaxy_map_core::routing::eta::{RouteEtaEstimate, estimate_route_eta};
+use sw_galaxy_map_core::routing::hyperspace::CompressionTable;
 use sw_galaxy_map_core::validate;
 
 pub(crate) fn run_one_shot_for_tui(
@@ -22,10 +23,14 @@ pub(crate) fn run_one_shot_for_tui(
             sector,
             grid,
             status,
+            by_ref,
             canon,
             legends,
             fuzzy,
+            prefix,
             limit,
+            offset,
+            ..
         } => {
             let filter = sw_galaxy_map_core::model::SearchFilter {
                 query: query.clone(),
@@ -33,10 +38,13 @@ pub(crate) fn run_one_shot_for_tui(
                 sector: sector.clone(),
                 grid: grid.clone(),
                 status: status.clone(),
+                reference: by_ref.clone(),
                 canon: if *canon { Some(true) } else { None },
                 legends: if *legends { Some(true) } else { None },
                 fuzzy: *fuzzy,
+                prefix: *prefix,
                 limit: *limit,
+                offset: *offset,
             };
             validate::validate_search(&filter)?;
             let con = crate::cli::open_db_migrating(cli.db.clone())?;
@@ -189,15 +197,31 @@ pub(crate) fn run_one_shot_for_tui(
             Ok(out)
         }
 
-        args::Commands::Info { planet } => {
+        args::Commands::Info {
+            planet,
+            show_nearest,
+            alias_limit,
+            ..
+        } => {
             let con = crate::cli::open_db_migrating(cli.db.clone())?;
-            let (row, aliases) = commands::info::resolve(&con, planet)?;
+            let (row, aliases) = commands::info::resolve(&con, planet, *alias_limit)?;
 
             let mut out = tui_default_output();
             let (title, lines) = build_planet_panel(&row, Some(&aliases));
 
             out.log_lines
                 .push(format!("Info result for \"{}\": planet found", planet));
+            if let Some(n) = show_nearest {
+                let hits = commands::info::nearest(&con, row.fid, row.x, row.y, *n)?;
+                out.log_lines
+                    .push(format!("Nearest {} planet(s):", hits.len()));
+                for h in hits {
+                    out.log_lines.push(format!(
+                        "  {} (FID={}) dist={:.3}",
+                        h.planet, h.fid, h.distance
+                    ));
+                }
+            }
             out.planet1_title = title;
             out.planet1_lines = lines;
 
@@ -206,18 +230,29 @@ pub(crate) fn run_one_shot_for_tui(
 
         args::Commands::Near {
             range,
+            min_r,
             planet,
             unknown,
             fid,
             x,
             y,
             limit,
-            ..
+            exclude_fid,
+            sort,
+            canon,
+            legends,
         } => {
             validate::validate_near(*unknown, fid, planet, x, y)?;
+            validate::validate_near_radius(*range, *min_r)?;
+            let filter = sw_galaxy_map_core::model::NearFilter {
+                min_r: *min_r,
+                sort: *sort,
+                canon: *canon,
+                legends: *legends,
+            };
             let con = crate::cli::open_db_migrating(cli.db.clone())?;
 
-            let (reference, hits) = commands::near::resolve(
+            let (reference, hits, total) = commands::near::resolve(
                 &con,
                 *range,
                 *unknown,
@@ -226,6 +261,8 @@ pub(crate) fn run_one_shot_for_tui(
                 *x,
                 *y,
                 *limit,
+                exclude_fid,
+                &filter,
             )?;
 
             let mut out = tui_default_output();
@@ -264,10 +301,11 @@ pub(crate) fn run_one_shot_for_tui(
             }
 
             out.log_lines.push(format!(
-                "Near result within {:.2} parsecs: {} planet{} found",
+                "Near result within {:.2} parsecs: {} planet{} found (showing {})",
                 range,
-                hits.len(),
-                if hits.len() == 1 { "" } else { "s" }
+                total,
+                if total == 1 { "" } else { "s" },
+                hits.len()
             ));
             out.log_lines.push(String::new());
 
@@ -337,12 +375,21 @@ pub(crate) fn run_one_shot_for_tui(
                 from,
                 to,
                 wp,
+                near_obstacle,
                 sort,
             } => {
                 validate::validate_limit(*limit as i64, "list")?;
                 let con = crate::cli::open_db_migrating(cli.db.clone())?;
-                let items =
-                    resolve_list_for_tui(&con, *limit, status.as_deref(), *from, *to, *wp, *sort)?;
+                let items = resolve_list_for_tui(
+                    &con,
+                    *limit,
+                    status.as_deref(),
+                    *from,
+                    *to,
+                    *wp,
+                    near_obstacle.as_deref(),
+                    *sort,
+                )?;
 
                 let mut out = tui_default_output();
 
@@ -407,7 +454,7 @@ pub(crate) fn run_one_shot_for_tui(
                 Ok(out)
             }
 
-            args::RouteCmd::Show { route_id } => {
+            args::RouteCmd::Show { route_id, .. } => {
                 validate::validate_route_id(*route_id, "show")?;
                 let con = crate::cli::open_db_migrating(cli.db.clone())?;
                 let data = resolve_show_for_tui(&con, *route_id)?;
@@ -423,6 +470,45 @@ pub(crate) fn run_one_shot_for_tui(
             }
         },
 
+        args::Commands::Distance { from, to, .. } => {
+            let con = crate::cli::open_db_migrating(cli.db.clone())?;
+            let (a, b, d) = commands::distance::resolve(&con, from, to)?;
+            Ok(tui_log_only(format!(
+                "Distance {} → {}: {:.3} parsec ({:.3} ly)",
+                a.planet,
+                b.planet,
+                d,
+                d * sw_galaxy_map_core::model::PC_TO_LY
+            )))
+        }
+
+        args::Commands::Hyperspace {
+            from,
+            to,
+            class,
+            region_override,
+            compression_config,
+        } => {
+            let con = crate::cli::open_db_migrating(cli.db.clone())?;
+            let table = CompressionTable::load(compression_config.as_deref())?;
+            let e = commands::hyperspace::resolve(
+                &con,
+                from,
+                to,
+                *class,
+                region_override.as_deref(),
+                &table,
+            )?;
+            Ok(tui_log_only(format!(
+                "Hyperspace {} → {}: {:.1} h (~{:.1} d) at class {:.1}",
+                e.from.planet,
+                e.to.planet,
+                e.hours,
+                e.hours / 24.0,
+                class
+            )))
+        }
+
         args::Commands::Db { cmd } => match cmd {
             args::DbCommands::Stats { top } => {
                 let con = crate::cli::open_db_migrating(cli.db.clone())?;
@@ -431,6 +517,13 @@ pub(crate) fn run_one_shot_for_tui(
                 crate::cli::reports::build_galaxy_stats_tui(&s, *top, &mut out);
                 Ok(out)
             }
+            args::DbCommands::Values { column } => {
+                let con = crate::cli::open_db_migrating(cli.db.clone())?;
+                let rows = sw_galaxy_map_core::db::queries::distinct_values(&con, *column)?;
+                let mut out = tui_default_output();
+                crate::cli::reports::build_distinct_values_tui(*column, &rows, &mut out);
+                Ok(out)
+            }
             _ => {
                 if let Some(message) = tui_only_cli_message(cmd) {
                     Ok(tui_log_only(message))
@@ -464,5 +557,6 @@ pub fn route_eta(con: &rusqlite::Connection, loaded: &RouteLoaded) -> Option<Rou
         crate::tui::types::ETA_REGION_BLEND,
         crate::tui::types::ETA_DETOUR_COUNT_BASE,
         crate::tui::types::ETA_SEVERITY_K,
+        &CompressionTable::default(),
     )
 }
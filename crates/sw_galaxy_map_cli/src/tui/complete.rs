@@ -0,0 +1,209 @@
+use crate::cli::args::Cli;
+use clap::{Command, CommandFactory};
+
+/// Commands whose positional arguments are planet names.
+const PLANET_COMMANDS: &[&[&str]] = &[&["info"], &["near"], &["route", "compute"]];
+
+/// System commands handled by the input line itself (see `handle_key`).
+const SYSTEM_COMMANDS: &[&str] = &[
+    ":help",
+    ":history",
+    ":clear-history",
+    ":quit",
+    ":exit",
+    ":q",
+    ":x",
+];
+
+/// Outcome of completing the word under the cursor (the end of the input line).
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Completion {
+    /// Input line with the word replaced by the longest unambiguous completion.
+    pub input: String,
+    /// Every match, shown to the user when more than one is left.
+    pub candidates: Vec<String>,
+}
+
+/// Completes the last word of `input`: subcommands and long flags from the clap
+/// definition, planet names (via `planets`, given the typed prefix) where a command
+/// expects one. Returns `None` when nothing matches.
+pub(crate) fn complete(
+    input: &str,
+    planets: impl FnOnce(&str) -> Vec<String>,
+) -> Option<Completion> {
+    let start = word_start(input);
+    let word = &input[start..];
+
+    let candidates = if input.starts_with(':') {
+        starting_with(SYSTEM_COMMANDS.iter().copied(), input.trim_end())
+    } else {
+        let before = shell_words::split(&input[..start]).ok()?;
+        candidates_for(&before, word, planets)
+    };
+
+    let completed = match candidates.as_slice() {
+        [] => return None,
+        [only] => format!("{only} "),
+        many => {
+            let common = common_prefix(many);
+            if common.len() >= word.len() {
+                common
+            } else {
+                word.to_string()
+            }
+        }
+    };
+
+    let head = if input.starts_with(':') {
+        ""
+    } else {
+        &input[..start]
+    };
+    Some(Completion {
+        input: format!("{head}{completed}"),
+        candidates,
+    })
+}
+
+fn candidates_for(
+    before: &[String],
+    word: &str,
+    planets: impl FnOnce(&str) -> Vec<String>,
+) -> Vec<String> {
+    let root = Cli::command();
+    let mut cmd = &root;
+    let mut path: Vec<&str> = Vec::new();
+    let mut positional = false;
+
+    let mut tokens = before.iter();
+    while let Some(token) = tokens.next() {
+        if token.starts_with('-') {
+            if !token.contains('=') && takes_value(&root, cmd, token) {
+                tokens.next();
+            }
+            continue;
+        }
+        match cmd.find_subcommand(token) {
+            Some(sub) if !positional => {
+                path.push(sub.get_name());
+                cmd = sub;
+            }
+            _ => positional = true,
+        }
+    }
+
+    if word.starts_with('-') {
+        let longs = cmd
+            .get_arguments()
+            .chain(root.get_arguments().filter(|a| a.is_global_set()))
+            .filter(|a| !a.is_hide_set())
+            .filter_map(|a| a.get_long())
+            .map(|l| format!("--{l}"))
+            .collect::<Vec<_>>();
+        return starting_with(longs.iter().map(String::as_str), word);
+    }
+
+    if !positional && cmd.has_subcommands() {
+        let names = cmd
+            .get_subcommands()
+            .filter(|c| !c.is_hide_set())
+            .map(Command::get_name);
+        return starting_with(names, word);
+    }
+
+    if PLANET_COMMANDS.contains(&path.as_slice()) {
+        let prefix = word.trim_start_matches(['"', '\'']);
+        let mut names = planets(prefix)
+            .into_iter()
+            .map(|n| shell_words::quote(&n).into_owned())
+            .collect::<Vec<_>>();
+        names.dedup();
+        return names;
+    }
+
+    Vec::new()
+}
+
+/// True when `flag` (`--name`) is a known option that consumes the next token.
+fn takes_value(root: &Command, cmd: &Command, flag: &str) -> bool {
+    let Some(long) = flag.strip_prefix("--") else {
+        return false;
+    };
+    cmd.get_arguments()
+        .chain(root.get_arguments())
+        .find(|a| a.get_long() == Some(long))
+        .is_some_and(|a| a.get_action().takes_values())
+}
+
+/// Byte offset where the last word starts; spaces inside quotes do not split words.
+fn word_start(input: &str) -> usize {
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, _) if c.is_whitespace() => start = i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    start
+}
+
+fn starting_with<'a>(names: impl Iterator<Item = &'a str>, word: &str) -> Vec<String> {
+    names
+        .filter(|n| n.starts_with(word))
+        .map(str::to_string)
+        .collect()
+}
+
+fn common_prefix(names: &[String]) -> String {
+    let first = &names[0];
+    let len = names[1..].iter().fold(first.len(), |len, n| {
+        first[..len]
+            .char_indices()
+            .zip(n.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(n.len()), |((i, _), _)| i)
+    });
+    first[..len].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_planets(_: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[test]
+    fn completes_subcommands_flags_and_planets() {
+        let c = complete("ro", no_planets).expect("route");
+        assert_eq!(c.input, "route ");
+
+        let c = complete("route comp", no_planets).expect("compute");
+        assert_eq!(c.input, "route compute ");
+
+        let c = complete("route compute --max-w", no_planets).expect("flag");
+        assert_eq!(c.input, "route compute --max-waypoints ");
+
+        let c = complete(":q", no_planets).expect("system command");
+        assert_eq!(c.candidates, vec![":quit", ":q"]);
+        assert_eq!(c.input, ":q");
+
+        let planets = |prefix: &str| {
+            assert_eq!(prefix, "cor");
+            vec!["Coruscant".to_string(), "Corellia".to_string()]
+        };
+        let c = complete("route compute --safety 2 cor", planets).expect("planets");
+        assert_eq!(c.candidates, vec!["Coruscant", "Corellia"]);
+        assert_eq!(c.input, "route compute --safety 2 Cor");
+
+        let c = complete("info \"Nar S", |_| vec!["Nar Shaddaa".to_string()]).expect("quoted");
+        assert_eq!(c.input, "info 'Nar Shaddaa' ");
+
+        // Planet names are only offered where a command expects one.
+        assert_eq!(complete("search cor", |_| vec!["Corellia".into()]), None);
+    }
+}
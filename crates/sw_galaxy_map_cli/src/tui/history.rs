@@ -0,0 +1,167 @@
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use sw_galaxy_map_core::db::paths::{ensure_parent_dir, history_path};
+
+/// Environment variable capping the number of persisted commands.
+pub(crate) const HISTORY_SIZE_ENV: &str = "SW_GALAXY_HISTORY_SIZE";
+
+const DEFAULT_HISTORY_SIZE: usize = 500;
+
+/// Command history persisted across TUI sessions, one command per line.
+pub(crate) struct HistoryFile {
+    path: PathBuf,
+    max: usize,
+}
+
+impl HistoryFile {
+    pub(crate) fn new(path: PathBuf, max: usize) -> Self {
+        Self { path, max }
+    }
+
+    /// History file at the default location, capped by `SW_GALAXY_HISTORY_SIZE`.
+    ///
+    /// Returns the warnings to show (bad size value) alongside the file.
+    pub(crate) fn from_env() -> Result<(Self, Vec<String>)> {
+        let mut warnings = Vec::new();
+        let max = match std::env::var(HISTORY_SIZE_ENV) {
+            Ok(v) => v.trim().parse::<usize>().unwrap_or_else(|_| {
+                warnings.push(format!(
+                    "Ignoring {}={:?}: not a number; keeping {} commands.",
+                    HISTORY_SIZE_ENV, v, DEFAULT_HISTORY_SIZE
+                ));
+                DEFAULT_HISTORY_SIZE
+            }),
+            Err(_) => DEFAULT_HISTORY_SIZE,
+        };
+        Ok((Self::new(history_path()?, max), warnings))
+    }
+
+    /// Reads the last `max` commands, trimming the file when it grew past the cap.
+    ///
+    /// Lines that are not valid UTF-8 are dropped (and removed from the file), with a
+    /// warning returned alongside the entries. A missing file is an empty history; an
+    /// unreadable one is reported as an error and left untouched, so the caller can
+    /// warn and start empty.
+    pub(crate) fn load(&self) -> Result<(Vec<String>, Vec<String>)> {
+        let bytes = match fs::read(&self.path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok((Vec::new(), Vec::new()));
+            }
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Unable to read history file: {}", self.path.display())
+                });
+            }
+        };
+        let mut invalid = 0;
+        let mut entries = Vec::new();
+        for line in bytes.split(|&b| b == b'\n') {
+            match String::from_utf8_lossy(line) {
+                Cow::Borrowed(l) if !l.trim().is_empty() => entries.push(l.trim().to_string()),
+                Cow::Borrowed(_) => {}
+                Cow::Owned(_) => invalid += 1,
+            }
+        }
+
+        let mut warnings = Vec::new();
+        if invalid > 0 {
+            warnings.push(format!(
+                "Skipped {} history line(s) that are not valid UTF-8: {}",
+                invalid,
+                self.path.display()
+            ));
+        }
+        let capped = entries.len() > self.max;
+        if capped {
+            entries.drain(..entries.len() - self.max);
+        }
+        if capped || invalid > 0 {
+            self.rewrite(&entries)?;
+        }
+        Ok((entries, warnings))
+    }
+
+    /// Appends one command. A cap of 0 disables persistence.
+    pub(crate) fn append(&self, command: &str) -> Result<()> {
+        if self.max == 0 {
+            return Ok(());
+        }
+        ensure_parent_dir(&self.path)?;
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Unable to open history file: {}", self.path.display()))?;
+        writeln!(f, "{}", command.trim())
+            .with_context(|| format!("Unable to write history file: {}", self.path.display()))
+    }
+
+    /// Empties the history file.
+    pub(crate) fn clear(&self) -> Result<()> {
+        self.rewrite(&[])
+    }
+
+    fn rewrite(&self, entries: &[String]) -> Result<()> {
+        ensure_parent_dir(&self.path)?;
+        let mut text = entries.join("\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        fs::write(&self.path, text)
+            .with_context(|| format!("Unable to write history file: {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_is_capped_and_bad_files_are_reported() {
+        let dir = std::env::temp_dir().join(format!("swgm_history_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = HistoryFile::new(dir.join("tui_history"), 3);
+
+        assert!(history.load().expect("missing file").0.is_empty());
+        for cmd in [
+            "info tatooine",
+            "near hoth",
+            "",
+            "route list",
+            "search kuat",
+        ] {
+            history.append(cmd).expect("append");
+        }
+        assert_eq!(
+            history.load().expect("load"),
+            (
+                vec![
+                    "near hoth".into(),
+                    "route list".into(),
+                    "search kuat".into()
+                ],
+                vec![]
+            )
+        );
+        // Trimmed on load, so the next session starts from the capped file.
+        let on_disk = fs::read_to_string(dir.join("tui_history")).expect("read");
+        assert_eq!(on_disk.lines().count(), 3);
+
+        history.clear().expect("clear");
+        assert!(history.load().expect("cleared").0.is_empty());
+
+        fs::write(dir.join("tui_history"), b"info hoth\n\xff\xfe\nnear hoth\n").expect("garbage");
+        let (entries, warnings) = history.load().expect("invalid UTF-8 is skipped");
+        assert_eq!(entries, vec!["info hoth", "near hoth"]);
+        assert_eq!(warnings.len(), 1);
+        // The bad line is dropped from the file, so the warning is not repeated.
+        assert_eq!(history.load().expect("reload").1, Vec::<String>::new());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
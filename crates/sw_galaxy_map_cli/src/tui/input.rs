@@ -4,6 +4,7 @@ use crate::cli::db_runtime::open_db_migrating;
 use crate::cli::shell::split_args;
 use crate::tui::app::{App, SelectionMode};
 use crate::tui::bridge::run_one_shot_for_tui;
+use crate::tui::complete::complete;
 use crate::tui::log::{
     enqueue_log_line, enqueue_log_lines, extend_log_lines, flush_typewriter,
     force_scroll_to_bottom, push_log_line, scroll_down, scroll_page_down, scroll_page_up,
@@ -16,6 +17,15 @@ use crate::tui::{
 use clap::Parser;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::text::Line;
+use sw_galaxy_map_core::db::db_status::resolve_db_path;
+use sw_galaxy_map_core::db::{open_db_read_only, queries};
+use sw_galaxy_map_core::utils::normalize_text;
+
+/// Planet names offered per Tab press.
+const PLANET_COMPLETION_LIMIT: i64 = 20;
+
+/// Entries printed by `:history`.
+const HISTORY_SHOWN: usize = 20;
 
 /// Handle one keyboard event.
 /// Returns true when the TUI should exit.
@@ -23,8 +33,14 @@ pub(crate) fn handle_key(key: KeyEvent, app: &mut App) -> bool {
     match key.code {
         KeyCode::Esc => return true,
 
+        // On a non-empty command line, Tab completes (and stays there even without a
+        // match); otherwise it switches panels.
         KeyCode::Tab => {
-            app.next_panel();
+            if app.selected_panel == 4 && !app.input.trim().is_empty() {
+                complete_input(app);
+            } else {
+                app.next_panel();
+            }
             app.reset_cursor_blink();
         }
 
@@ -54,8 +70,12 @@ pub(crate) fn handle_key(key: KeyEvent, app: &mut App) -> bool {
                     "help" => {
                         push_log_line(app, "System commands:");
                         push_log_line(app, "  :q | :quit | :exit | :x   Exit application");
+                        push_log_line(app, "  :history                  Show recent commands");
+                        push_log_line(app, "  :clear-history            Forget all saved commands");
                         push_log_line(app, "  :help                     Show this help");
                     }
+                    "history" => show_history(app),
+                    "clear-history" => clear_history(app),
                     _ => {
                         push_log_line(app, format!("Unknown system command: :{}", cmd));
                     }
@@ -67,7 +87,11 @@ pub(crate) fn handle_key(key: KeyEvent, app: &mut App) -> bool {
             }
 
             if !command.is_empty() {
-                app.push_history(&command);
+                if app.push_history(&command)
+                    && let Some(Err(e)) = app.history_file.as_ref().map(|f| f.append(&command))
+                {
+                    push_log_line(app, format!("⚠️ {e:#}"));
+                }
                 push_log_line(app, format!("> {command}"));
 
                 if let Some(index) = parse_selection(&command) {
@@ -219,6 +243,66 @@ pub(crate) fn handle_key(key: KeyEvent, app: &mut App) -> bool {
     false
 }
 
+fn show_history(app: &mut App) {
+    if app.history.is_empty() {
+        push_log_line(app, "History is empty.");
+        return;
+    }
+    let skip = app.history.len().saturating_sub(HISTORY_SHOWN);
+    let lines = app
+        .history
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(i, cmd)| format!("{:>4}  {}", i + 1, cmd))
+        .collect::<Vec<_>>();
+    for line in lines {
+        push_log_line(app, line);
+    }
+}
+
+fn clear_history(app: &mut App) {
+    app.history.clear();
+    app.reset_history_navigation();
+    match app.history_file.as_ref().map(|f| f.clear()) {
+        Some(Err(e)) => push_log_line(app, format!("⚠️ {e:#}")),
+        _ => push_log_line(app, "History cleared."),
+    }
+}
+
+/// Completes the input line in place, listing the matches in the log when the
+/// completion is ambiguous. Leaves the line as is when nothing matched.
+fn complete_input(app: &mut App) {
+    // Completion must stay cheap and side-effect free: the existing database is
+    // opened read-only once, never created or migrated.
+    if app.completion_db.is_none()
+        && let Ok(path) = resolve_db_path(app.session_db.clone())
+        && path.exists()
+    {
+        app.completion_db = open_db_read_only(&path).ok();
+    }
+
+    let con = app.completion_db.as_ref();
+    let planets = |prefix: &str| {
+        let Some(con) = con else {
+            return Vec::new();
+        };
+        queries::search_planets_prefix(con, &normalize_text(prefix), PLANET_COMPLETION_LIMIT, 0)
+            .map(|rows| rows.into_iter().map(|r| r.name).collect())
+            .unwrap_or_default()
+    };
+
+    let Some(completion) = complete(&app.input, planets) else {
+        return;
+    };
+
+    if completion.candidates.len() > 1 {
+        push_log_line(app, completion.candidates.join("  "));
+    }
+    app.reset_history_navigation();
+    app.input = completion.input;
+}
+
 fn parse_selection(command: &str) -> Option<usize> {
     let trimmed = command.trim();
 
@@ -1,5 +1,7 @@
 pub(crate) mod app;
 pub(crate) mod bridge;
+pub(crate) mod complete;
+pub(crate) mod history;
 pub(crate) mod input;
 pub(crate) mod log;
 pub(crate) mod panels;
@@ -156,7 +156,7 @@ fn help_line_for_panel(selected_panel: usize, selection_mode: SelectionMode) ->
     let scroll_desc = match selected_panel {
         0 => "↑/↓ scroll log | PgUp/PgDn fast scroll",
         1..=3 => "↑/↓ scroll details | PgUp/PgDn fast scroll",
-        4 => "↑ previous command | ↓ next command",
+        4 => "↑ previous command | ↓ next command | Tab complete",
         _ => "↑/↓ scroll | PgUp/PgDn fast scroll",
     };
 
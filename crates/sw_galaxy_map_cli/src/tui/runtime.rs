@@ -1,7 +1,8 @@
 use crate::cli::args;
 use crate::tui::app::App;
+use crate::tui::history::HistoryFile;
 use crate::tui::input::handle_key;
-use crate::tui::log::update_typewriter;
+use crate::tui::log::{push_log_line, update_typewriter};
 use crate::tui::render::ui;
 use crate::tui::{TuiCommandOutput, tui_default_output};
 use crossterm::event::KeyEventKind;
@@ -43,6 +44,7 @@ fn run_app(
         session_db: db_arg,
         ..App::default()
     };
+    load_history(&mut app);
 
     loop {
         app.update_cursor_blink();
@@ -62,6 +64,34 @@ fn run_app(
     Ok(())
 }
 
+/// Restores the persisted command history. Problems are logged, never fatal.
+fn load_history(app: &mut App) {
+    let (file, warnings) = match HistoryFile::from_env() {
+        Ok(v) => v,
+        Err(e) => {
+            push_log_line(app, format!("⚠️ Command history not persisted: {e:#}"));
+            return;
+        }
+    };
+    for w in warnings {
+        push_log_line(app, format!("⚠️ {w}"));
+    }
+
+    match file.load() {
+        Ok((entries, warnings)) => {
+            app.history = entries;
+            for w in warnings {
+                push_log_line(app, format!("⚠️ {w}"));
+            }
+        }
+        Err(e) => push_log_line(
+            app,
+            format!("⚠️ {e:#}; starting with an empty history (:clear-history resets the file)."),
+        ),
+    }
+    app.history_file = Some(file);
+}
+
 pub(crate) fn tui_only_cli_message(cmd: &args::DbCommands) -> Option<String> {
     match cmd {
         args::DbCommands::Backup(args) => {
@@ -79,18 +109,23 @@ pub(crate) fn tui_only_cli_message(cmd: &args::DbCommands) -> Option<String> {
         args::DbCommands::Export(export_args) => {
             let mut cli_cmd = format!("sw_galaxy_map db export --table {}", export_args.table);
 
-            if export_args.csv {
-                cli_cmd.push_str(" --csv");
+            match export_args.export_format() {
+                args::ExportFormat::Csv => cli_cmd.push_str(" --format csv"),
+                args::ExportFormat::Json => cli_cmd.push_str(" --format json"),
             }
 
-            if export_args.json {
-                cli_cmd.push_str(" --json");
+            if export_args.include_deleted {
+                cli_cmd.push_str(" --include-deleted");
             }
 
             if let Some(output) = &export_args.output {
                 cli_cmd.push_str(&format!(" --output {}", output.display()));
             }
 
+            if let Some(out) = &export_args.out {
+                cli_cmd.push_str(&format!(" --out {}", out.display()));
+            }
+
             Some(format!(
                 "❌ This command is available only in CLI mode.\nRun it from a terminal:\n{}",
                 cli_cmd
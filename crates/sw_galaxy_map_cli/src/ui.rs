@@ -1,4 +1,7 @@
 use owo_colors::OwoColorize;
+use std::ffi::OsStr;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Level {
@@ -8,20 +11,59 @@ pub enum Level {
     Error,
 }
 
+/// When to emit ANSI colors (`--color`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Style {
     pub emoji: bool,
     pub color: bool,
 }
 
+impl Style {
+    /// Style for a `--color` choice; emojis are always on.
+    pub fn for_color(choice: ColorChoice) -> Self {
+        let no_color = std::env::var_os("NO_COLOR");
+        let color = resolve_color(choice, no_color.as_deref(), std::io::stdout().is_terminal());
+        Self { emoji: true, color }
+    }
+}
+
+/// Whether to color output: explicit `--color` > non-empty `NO_COLOR` > TTY detection.
+fn resolve_color(choice: ColorChoice, no_color: Option<&OsStr>, stdout_tty: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => no_color.is_none_or(OsStr::is_empty) && stdout_tty,
+    }
+}
+
 impl Default for Style {
+    /// The style set by [`init_style`], else colors only when stdout is a TTY.
     fn default() -> Self {
-        // Colors only when stdout is a TTY; emojis always on by default.
-        let color = atty::is(atty::Stream::Stdout);
-        Self { emoji: true, color }
+        STYLE
+            .get()
+            .copied()
+            .unwrap_or_else(|| Style::for_color(ColorChoice::Auto))
     }
 }
 
+static STYLE: OnceLock<Style> = OnceLock::new();
+
+/// Sets the process-wide style (from `--color`) used by `Style::default()`.
+///
+/// Only the first call has an effect.
+pub fn init_style(style: Style) {
+    let _ = STYLE.set(style);
+}
+
 pub fn info(msg: impl AsRef<str>) {
     print_line(Level::Info, msg.as_ref(), Style::default());
 }
@@ -38,6 +80,16 @@ pub fn error(msg: impl AsRef<str>) {
     print_line(Level::Error, msg.as_ref(), Style::default());
 }
 
+/// Warn that a result list was cut at `limit` rows and more matches may exist.
+pub fn limit_reached_hint(shown: usize, limit: i64) {
+    if limit > 0 && shown as i64 >= limit {
+        warning(format!(
+            "({} shown; more may exist — increase --limit)",
+            shown
+        ));
+    }
+}
+
 /// Lower-level API if you need custom style (e.g., disable emoji/colors).
 pub fn print_line(level: Level, msg: &str, style: Style) {
     let emoji = match level {
@@ -61,3 +113,22 @@ pub fn print_line(level: Level, msg: &str, style: Style) {
         println!("{}", line);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorChoice, resolve_color};
+    use std::ffi::OsStr;
+
+    #[test]
+    fn explicit_flag_beats_no_color_which_beats_tty() {
+        let set = Some(OsStr::new("1"));
+
+        assert!(resolve_color(ColorChoice::Always, set, false));
+        assert!(!resolve_color(ColorChoice::Never, None, true));
+
+        assert!(!resolve_color(ColorChoice::Auto, set, true));
+        assert!(resolve_color(ColorChoice::Auto, Some(OsStr::new("")), true));
+        assert!(resolve_color(ColorChoice::Auto, None, true));
+        assert!(!resolve_color(ColorChoice::Auto, None, false));
+    }
+}
@@ -1,11 +1,22 @@
 use anyhow::{Context, Result};
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
 
 pub fn open_db(path: &str) -> Result<Connection> {
     let con = Connection::open(path).with_context(|| format!("Unable to open database: {path}"))?;
     Ok(con)
 }
 
+/// Opens an existing database read-only: a missing file is an error, and nothing
+/// is created, initialized or migrated.
+pub fn open_db_read_only(path: &Path) -> Result<Connection> {
+    Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Unable to open database: {}", path.display()))
+}
+
 pub fn has_table(con: &Connection, table: &str) -> Result<bool> {
     let n: i64 = con.query_row(
         r#"
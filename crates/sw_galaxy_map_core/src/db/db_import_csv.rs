@@ -0,0 +1,348 @@
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, Transaction};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::db::db_update::{compute_arcgis_hash, db_get_hash_and_status, upsert_planet};
+use crate::db::provision::{
+    ARCGIS_HASH_FIELDS, meta_upsert_public, rebuild_planet_search_public,
+    rebuild_planets_fts_if_enabled,
+};
+use crate::utils::normalize::normalize_text;
+
+/// Columns parsed as integers; `X`, `Y`, `lat`, `long` are numbers, the rest text.
+const INT_FIELDS: [&str; 4] = ["FID", "Canon", "Legends", "zm"];
+const FLOAT_FIELDS: [&str; 4] = ["X", "Y", "lat", "long"];
+const REQUIRED_FIELDS: [&str; 4] = ["FID", "Planet", "X", "Y"];
+
+#[derive(Debug, Clone)]
+pub struct CsvImportReport {
+    pub file: PathBuf,
+    pub rows: usize,
+    pub dry_run: bool,
+    pub mark_deleted: bool,
+    pub inserted: i64,
+    pub updated: i64,
+    pub revived: i64,
+    pub unchanged: i64,
+    pub marked_deleted: i64,
+    /// Rows without a planet name (FID, X and Y are required by the parser).
+    pub skipped: i64,
+}
+
+/// Parses a CSV cell into the JSON value `upsert_planet` expects for `field`.
+///
+/// Integral numbers stay integers so the hash matches the ArcGIS feed.
+fn cell_value(field: &str, raw: &str) -> Result<Value> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(Value::Null);
+    }
+
+    if INT_FIELDS.contains(&field) {
+        let v: i64 = raw
+            .parse()
+            .with_context(|| format!("{}: '{}' is not an integer", field, raw))?;
+        return Ok(Value::from(v));
+    }
+    if FLOAT_FIELDS.contains(&field) {
+        if let Ok(v) = raw.parse::<i64>() {
+            return Ok(Value::from(v));
+        }
+        let v: f64 = raw
+            .parse()
+            .with_context(|| format!("{}: '{}' is not a number", field, raw))?;
+        return Ok(Value::from(v));
+    }
+    Ok(Value::from(raw))
+}
+
+/// Reads the overlay file into ArcGIS-like attribute objects.
+///
+/// Headers must be canonical field names (as written by `db export --table planets`);
+/// unknown columns are rejected so a typo does not silently drop a correction.
+fn read_rows(file: &Path) -> Result<Vec<Value>> {
+    let mut rdr = csv::Reader::from_path(file)
+        .with_context(|| format!("Unable to open CSV: {}", file.display()))?;
+
+    let headers: Vec<String> = rdr
+        .headers()
+        .context("Unable to read CSV header")?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    for h in &headers {
+        if !ARCGIS_HASH_FIELDS.contains(&h.as_str()) {
+            bail!(
+                "Unknown CSV column '{}'. Allowed columns: {}",
+                h,
+                ARCGIS_HASH_FIELDS.join(", ")
+            );
+        }
+    }
+    for f in REQUIRED_FIELDS {
+        if !headers.iter().any(|h| h == f) {
+            bail!("CSV is missing required column '{}'", f);
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (i, rec) in rdr.records().enumerate() {
+        // Line 1 is the header.
+        let line = i + 2;
+        let rec = rec.with_context(|| format!("Invalid CSV record at line {}", line))?;
+
+        let mut obj = Map::new();
+        for (field, raw) in headers.iter().zip(rec.iter()) {
+            let v = cell_value(field, raw).with_context(|| format!("CSV line {}", line))?;
+            obj.insert(field.clone(), v);
+        }
+        for f in ["FID", "X", "Y"] {
+            if obj.get(f).is_none_or(Value::is_null) {
+                bail!("CSV line {}: missing {}", line, f);
+            }
+        }
+        rows.push(Value::Object(obj));
+    }
+
+    Ok(rows)
+}
+
+/// Planets not matched by any CSV row, by FID or by normalized name.
+///
+/// Only names and FIDs count: planets sharing a sector/region/grid with an imported
+/// row are still marked when they are absent from the file.
+fn missing_planets_sql() -> &'static str {
+    r#"
+    FROM planets
+    WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+      AND (source IS NULL OR source <> 'local')
+      AND FID NOT IN (SELECT fid FROM __csv_fids)
+      AND planet_norm NOT IN (SELECT norm FROM __csv_names)
+    "#
+}
+
+fn mark_missing(
+    tx: &Transaction<'_>,
+    fids: &HashSet<i64>,
+    names: &HashSet<String>,
+    dry_run: bool,
+) -> Result<i64> {
+    tx.execute_batch(
+        r#"
+        DROP TABLE IF EXISTS __csv_fids;
+        DROP TABLE IF EXISTS __csv_names;
+        CREATE TEMP TABLE __csv_fids(fid INTEGER PRIMARY KEY);
+        CREATE TEMP TABLE __csv_names(norm TEXT PRIMARY KEY);
+        "#,
+    )?;
+    {
+        let mut ins = tx.prepare("INSERT OR IGNORE INTO __csv_fids(fid) VALUES (?1)")?;
+        for fid in fids {
+            ins.execute([fid])?;
+        }
+        let mut ins = tx.prepare("INSERT OR IGNORE INTO __csv_names(norm) VALUES (?1)")?;
+        for name in names {
+            ins.execute([name])?;
+        }
+    }
+
+    let n = if dry_run {
+        tx.query_row(
+            &format!("SELECT COUNT(*) {}", missing_planets_sql()),
+            [],
+            |r| r.get(0),
+        )?
+    } else {
+        tx.execute(
+            &format!(
+                "UPDATE planets SET status = 'deleted' WHERE FID IN (SELECT FID {})",
+                missing_planets_sql()
+            ),
+            [],
+        )? as i64
+    };
+
+    tx.execute_batch("DROP TABLE IF EXISTS __csv_fids; DROP TABLE IF EXISTS __csv_names;")?;
+    Ok(n)
+}
+
+/// Applies a local CSV overlay of planet corrections.
+///
+/// Rows are upserted like ArcGIS features (same hash, same alias handling). With
+/// `mark_deleted`, active non-local planets matched by neither FID nor name are marked
+/// deleted. `dry_run` computes the counters in a rolled-back transaction: no planet,
+/// search index or meta row is written.
+pub fn run(
+    con: &mut Connection,
+    file: &Path,
+    dry_run: bool,
+    mark_deleted: bool,
+) -> Result<CsvImportReport> {
+    let rows = read_rows(file)?;
+
+    let tx = con
+        .transaction()
+        .context("Failed to start CSV import transaction")?;
+
+    let mut report = CsvImportReport {
+        file: file.to_path_buf(),
+        rows: rows.len(),
+        dry_run,
+        mark_deleted,
+        inserted: 0,
+        updated: 0,
+        revived: 0,
+        unchanged: 0,
+        marked_deleted: 0,
+        skipped: 0,
+    };
+    let mut fids = HashSet::with_capacity(rows.len());
+    let mut names = HashSet::with_capacity(rows.len());
+
+    for a in &rows {
+        let fid = a["FID"].as_i64().context("Missing FID")?;
+        let planet = a
+            .get("Planet")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .unwrap_or_default();
+        if planet.is_empty() {
+            report.skipped += 1;
+            continue;
+        }
+        fids.insert(fid);
+        names.insert(normalize_text(planet));
+
+        let changed = match db_get_hash_and_status(&tx, fid)? {
+            None => {
+                report.inserted += 1;
+                true
+            }
+            Some((_, Some(status))) if status == "deleted" => {
+                report.revived += 1;
+                true
+            }
+            Some((old_hash, _)) if old_hash != compute_arcgis_hash(a) => {
+                report.updated += 1;
+                true
+            }
+            Some(_) => {
+                report.unchanged += 1;
+                false
+            }
+        };
+        if changed && !dry_run {
+            upsert_planet(&tx, a)?;
+        }
+    }
+
+    if mark_deleted {
+        report.marked_deleted = mark_missing(&tx, &fids, &names, dry_run)?;
+    }
+
+    if !dry_run {
+        rebuild_planet_search_public(&tx)?;
+        rebuild_planets_fts_if_enabled(&tx)?;
+
+        meta_upsert_public(
+            &tx,
+            "last_csv_import_utc",
+            &crate::utils::time::now_utc_iso(),
+        )?;
+        meta_upsert_public(&tx, "last_csv_import_file", &file.display().to_string())?;
+
+        tx.commit().context("Failed to commit CSV import")?;
+    }
+    // Dry run: the transaction rolls back on drop.
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn status_of(con: &Connection, fid: i64) -> Option<String> {
+        con.query_row("SELECT status FROM planets WHERE FID = ?1", [fid], |r| {
+            r.get(0)
+        })
+        .expect("planet row")
+    }
+
+    #[test]
+    fn csv_overlay_upserts_and_marks_missing_by_name_or_fid() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            -- planets.source arrives with the v14 migration.
+            ALTER TABLE planets ADD COLUMN source TEXT;
+            INSERT INTO planets (FID, Planet, planet_norm, Sector, X, Y, arcgis_hash, status, source) VALUES
+                (1, 'Tatooine', 'tatooine', 'Arkanis', 1.0, 1.0, 'h1', 'active', NULL),
+                (2, 'Geonosis', 'geonosis', 'Arkanis', 2.0, 2.0, 'h2', 'active', NULL),
+                (3, 'Ryloth', 'ryloth', 'Gaulus', 3.0, 3.0, 'h3', 'active', NULL),
+                (5, 'Outpost', 'outpost', 'Arkanis', 5.0, 5.0, 'h5', 'active', 'local');
+            INSERT INTO waypoints (id, name, name_norm, x, y, kind, fingerprint) VALUES
+                (1, 'Buoy', 'buoy', 1.0, 1.0, 'manual', 'fp1');
+            INSERT INTO waypoint_planets (waypoint_id, planet_fid, role) VALUES (1, 1, 'anchor');
+            "#,
+        )
+        .expect("planets");
+
+        // Tatooine corrected by FID; Ryloth listed under a new FID (kept by name);
+        // Geonosis shares Tatooine's sector but is absent, so it must be marked.
+        let dir = std::env::temp_dir().join(format!("swgm_csv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let file = dir.join("overlay.csv");
+        let mut f = std::fs::File::create(&file).expect("csv file");
+        writeln!(f, "FID,Planet,Sector,X,Y,status").unwrap();
+        writeln!(f, "1,Tatooine,Arkanis,1.5,1,active").unwrap();
+        writeln!(f, "30,Ryloth,Gaulus,3,3,active").unwrap();
+        writeln!(f, "4,,Nowhere,9,9,").unwrap();
+        drop(f);
+
+        let dry = run(&mut con, &file, true, true).expect("dry run");
+        assert_eq!((dry.updated, dry.inserted, dry.skipped), (1, 1, 1));
+        assert_eq!(dry.marked_deleted, 1);
+        assert_eq!(status_of(&con, 2).as_deref(), Some("active"));
+        let meta: i64 = con
+            .query_row(
+                "SELECT COUNT(*) FROM meta WHERE key LIKE 'last_csv_import%'",
+                [],
+                |r| r.get(0),
+            )
+            .expect("meta count");
+        assert_eq!(meta, 0, "dry run writes no metadata");
+
+        let real = run(&mut con, &file, false, true).expect("import");
+        assert_eq!(real.marked_deleted, 1);
+        assert_eq!(status_of(&con, 2).as_deref(), Some("deleted"));
+        assert_eq!(status_of(&con, 3).as_deref(), Some("active"));
+        assert_eq!(
+            status_of(&con, 5).as_deref(),
+            Some("active"),
+            "local planets kept"
+        );
+        let x: f64 = con
+            .query_row("SELECT X FROM planets WHERE FID = 1", [], |r| r.get(0))
+            .expect("x");
+        assert_eq!(x, 1.5);
+        let links: i64 = con
+            .query_row(
+                "SELECT COUNT(*) FROM waypoint_planets WHERE planet_fid = 1",
+                [],
+                |r| r.get(0),
+            )
+            .expect("links");
+        assert_eq!(links, 1, "updating a planet keeps its waypoint links");
+
+        let again = run(&mut con, &file, false, false).expect("re-import");
+        assert_eq!((again.unchanged, again.updated, again.inserted), (2, 0, 0));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
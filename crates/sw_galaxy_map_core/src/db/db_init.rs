@@ -1,82 +1,264 @@
 use crate::db::{paths, provision};
-use crate::provision::arcgis;
+use crate::domain::DedupePolicy;
+use crate::provision::{arcgis, cancel};
 use anyhow::{Context, Result};
 use reqwest::blocking::Client;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct DbInitReport {
     pub out_path: PathBuf,
     pub overwritten_existing: bool,
+    /// Where the replaced database was moved (`<path>.bak`).
+    pub backup_path: Option<PathBuf>,
+    pub recovered_incomplete: bool,
     pub downloaded_features: usize,
+    pub dedupe: Option<DedupePolicy>,
+    pub dropped_duplicates: usize,
     pub fts_enabled: bool,
 }
 
-pub fn run(out: Option<String>, force: bool) -> Result<DbInitReport> {
+/// What to do with a database already present at the target path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Existing {
+    None,
+    /// Move it to `<path>.bak` once the new database is ready.
+    Replace,
+    /// Leftover of an interrupted init: replaced (and kept as `<path>.bak`) too,
+    /// but reported as a recovery.
+    Discard,
+}
+
+/// `path` with `suffix` appended to the file name (`x.sqlite` → `x.sqlite<suffix>`).
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// The database file followed by its WAL-mode sidecars.
+fn db_files(path: &Path) -> [PathBuf; 3] {
+    [
+        path.to_path_buf(),
+        with_suffix(path, "-wal"),
+        with_suffix(path, "-shm"),
+    ]
+}
+
+fn remove_db_files(path: &Path) {
+    for f in db_files(path) {
+        let _ = std::fs::remove_file(f);
+    }
+}
+
+/// Moves a database and its sidecars; stale sidecars at `to` are removed so they
+/// can't be replayed against the moved file.
+fn move_db_files(from: &Path, to: &Path) -> Result<()> {
+    for (src, dst) in db_files(from).iter().zip(db_files(to).iter()) {
+        if src.exists() {
+            std::fs::rename(src, dst).with_context(|| {
+                format!("Unable to move {} to {}", src.display(), dst.display())
+            })?;
+        } else if dst.exists() {
+            std::fs::remove_file(dst)
+                .with_context(|| format!("Unable to remove stale {}", dst.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds the database into `<path>.init-tmp` and renames it over the target only
+/// once download, schema creation and inserts have all succeeded, so a failed or
+/// interrupted init leaves any previous database untouched. A replaced database is
+/// kept as `<path>.bak`.
+///
+/// `service_url` overrides the ArcGIS layer (see [`arcgis::layer_url`]); requests are
+/// retried per `retry`; `progress` is called while features are downloaded (see
+/// [`arcgis::FetchProgress`]).
+pub fn run(
+    out: Option<String>,
+    force: bool,
+    dedupe: Option<DedupePolicy>,
+    service_url: Option<&str>,
+    retry: &arcgis::RetryPolicy,
+    progress: arcgis::FetchProgress<'_>,
+) -> Result<DbInitReport> {
+    let layer_url = arcgis::layer_url(service_url)?;
     let out_path: PathBuf = match out {
         Some(p) => PathBuf::from(p),
-        None => paths::default_db_path()?,
+        None => paths::env_or_default_db_path()?,
     };
 
+    cancel::reset();
+
     paths::ensure_parent_dir(&out_path)?;
-    let mut overwritten_existing = false;
 
-    if out_path.exists() {
-        if force {
-            std::fs::remove_file(&out_path).with_context(|| {
-                format!("Unable to remove existing database: {}", out_path.display())
-            })?;
-            overwritten_existing = true;
-        } else if confirm_overwrite(&out_path)? {
-            std::fs::remove_file(&out_path).with_context(|| {
-                format!("Unable to remove existing database: {}", out_path.display())
-            })?;
-            overwritten_existing = true;
-        } else {
+    let existing = if !out_path.exists() {
+        Existing::None
+    } else {
+        // A file that only looks incomplete is still confirmed and kept as `.bak`.
+        let incomplete = is_incomplete_db(&out_path);
+        if !force && !confirm_overwrite(&out_path, incomplete)? {
             anyhow::bail!("Aborted. Existing database was not modified.");
         }
-    }
+        if incomplete {
+            Existing::Discard
+        } else {
+            Existing::Replace
+        }
+    };
+
+    // A leftover from an interrupted run is never worth resuming: start over.
+    let tmp_path = with_suffix(&out_path, ".init-tmp");
+    remove_db_files(&tmp_path);
+
+    let built = build(&tmp_path, &layer_url, dedupe, retry, progress);
+    let (downloaded_features, dropped_duplicates, fts_enabled) = match built {
+        Ok(v) => v,
+        Err(e) => {
+            remove_db_files(&tmp_path);
+            if cancel::is_cancelled() {
+                // The stage is irrelevant: the user stopped it and nothing was touched.
+                return Err(anyhow::anyhow!("Cancelled, no changes written"));
+            }
+            let kept = if existing == Existing::None {
+                "nothing was written"
+            } else {
+                "the existing database was left untouched"
+            };
+            return Err(e.context(format!("db init failed ({})", kept)));
+        }
+    };
 
+    let backup_path = install(&tmp_path, &out_path, existing)?;
+
+    Ok(DbInitReport {
+        out_path,
+        overwritten_existing: existing == Existing::Replace,
+        backup_path,
+        recovered_incomplete: existing == Existing::Discard,
+        downloaded_features,
+        dedupe,
+        dropped_duplicates,
+        fts_enabled,
+    })
+}
+
+/// Moves the database built at `tmp_path` over `out_path`, first moving any existing
+/// database to `<out_path>.bak`. Returns the backup path, if one was made.
+fn install(tmp_path: &Path, out_path: &Path, existing: Existing) -> Result<Option<PathBuf>> {
+    let backup_path = match existing {
+        Existing::None => None,
+        Existing::Replace | Existing::Discard => {
+            let bak = with_suffix(out_path, ".bak");
+            move_db_files(out_path, &bak).inspect_err(|_| remove_db_files(tmp_path))?;
+            Some(bak)
+        }
+    };
+
+    move_db_files(tmp_path, out_path).with_context(|| {
+        format!(
+            "db init failed while installing the new database (built at {})",
+            tmp_path.display()
+        )
+    })?;
+    Ok(backup_path)
+}
+
+/// Downloads the dataset and builds a complete database at `path`.
+///
+/// Returns (downloaded features, dropped duplicates, FTS enabled). Errors name the
+/// stage that failed; the connection is closed before returning either way.
+fn build(
+    path: &Path,
+    layer_url: &str,
+    dedupe: Option<DedupePolicy>,
+    retry: &arcgis::RetryPolicy,
+    progress: arcgis::FetchProgress<'_>,
+) -> Result<(usize, usize, bool)> {
     let client = Client::builder()
         .timeout(Duration::from_secs(60))
         .build()
         .context("Unable to create HTTP client")?;
 
-    let layer = arcgis::fetch_layer_info(&client)?;
+    let layer = arcgis::fetch_layer_info(&client, layer_url, retry)
+        .context("Stage: fetching layer info")?;
     let page_size = if layer.max_record_count > 0 {
         layer.max_record_count
     } else {
         2000
     };
 
-    let features = arcgis::fetch_all_features(&client, page_size)?;
+    let features = arcgis::fetch_all_features(&client, layer_url, page_size, retry, progress)
+        .context("Stage: downloading features")?;
 
-    let mut con = rusqlite::Connection::open(&out_path)
-        .with_context(|| format!("Unable to create SQLite database: {}", out_path.display()))?;
+    let mut con = rusqlite::Connection::open(path)
+        .with_context(|| format!("Unable to create SQLite database: {}", path.display()))?;
 
     let enable_fts = provision::has_fts5(&con);
-    provision::create_schema(&con, enable_fts)?;
 
     let meta = provision::BuildMeta {
         imported_at_utc: chrono::Utc::now().to_rfc3339(),
         source_service_item_id: layer.service_item_id,
+        source_service_url: layer_url.to_string(),
         dataset_version: "C2".to_string(),
         importer_version: "sw_galaxy_map-0.2.0-dev".to_string(),
     };
 
-    provision::insert_all(&mut con, meta, &features, enable_fts)?;
+    provision::create_schema(&con, enable_fts).context("Stage: creating schema")?;
+    let dropped = provision::insert_all(&mut con, meta, &features, enable_fts, dedupe)
+        .context("Stage: inserting planets")?;
 
-    Ok(DbInitReport {
-        out_path,
-        overwritten_existing,
-        downloaded_features: features.len(),
-        fts_enabled: enable_fts,
-    })
+    // Closing the last connection checkpoints the WAL into the main file.
+    con.close()
+        .map_err(|(_, e)| e)
+        .context("Stage: closing the new database")?;
+
+    Ok((features.len(), dropped, enable_fts))
+}
+
+/// True when `path` looks like the leftover of an interrupted `db init`:
+/// an empty file, or a schema whose final meta keys were never committed.
+fn is_incomplete_db(path: &Path) -> bool {
+    if std::fs::metadata(path).is_ok_and(|m| m.len() == 0) {
+        return true;
+    }
+
+    let Ok(con) =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+    else {
+        return false;
+    };
+
+    let Ok(tables) = con.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+        [],
+        |r| r.get::<_, i64>(0),
+    ) else {
+        // Not a SQLite database: let the user decide.
+        return false;
+    };
+
+    if tables == 0 {
+        return true;
+    }
+
+    if !crate::db::has_table(&con, "meta").unwrap_or(false) {
+        return false;
+    }
+
+    con.query_row(
+        "SELECT COUNT(*) FROM meta WHERE key = 'imported_at_utc'",
+        [],
+        |r| r.get::<_, i64>(0),
+    )
+    .map(|n| n == 0)
+    .unwrap_or(false)
 }
 
-fn confirm_overwrite(path: &std::path::Path) -> Result<bool> {
+fn confirm_overwrite(path: &std::path::Path, incomplete: bool) -> Result<bool> {
     if !atty::is(atty::Stream::Stdin) {
         return Ok(false);
     }
@@ -87,6 +269,9 @@ fn confirm_overwrite(path: &std::path::Path) -> Result<bool> {
 ",
         path.display()
     );
+    if incomplete {
+        eprintln!("It looks incomplete (left by an interrupted db init).");
+    }
     eprint!("Overwrite existing database? [y/N]: ");
     io::stdout().flush()?;
 
@@ -96,3 +281,56 @@ fn confirm_overwrite(path: &std::path::Path) -> Result<bool> {
     let answer = input.trim().to_lowercase();
     Ok(matches!(answer.as_str(), "y" | "yes"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_db_files_carries_sidecars_and_drops_stale_ones() {
+        let dir = std::env::temp_dir().join(format!("swgm_init_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let from = dir.join("new.sqlite");
+        let to = dir.join("db.sqlite");
+
+        std::fs::write(&from, "new").unwrap();
+        std::fs::write(with_suffix(&from, "-shm"), "new-shm").unwrap();
+        std::fs::write(&to, "old").unwrap();
+        std::fs::write(with_suffix(&to, "-wal"), "old-wal").unwrap();
+
+        move_db_files(&from, &to).expect("move");
+
+        assert_eq!(std::fs::read_to_string(&to).unwrap(), "new");
+        assert_eq!(
+            std::fs::read_to_string(with_suffix(&to, "-shm")).unwrap(),
+            "new-shm"
+        );
+        assert!(
+            !with_suffix(&to, "-wal").exists(),
+            "old WAL must not be replayed"
+        );
+        assert!(!from.exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn install_keeps_an_incomplete_database_as_backup() {
+        let dir = std::env::temp_dir().join(format!("swgm_install_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let tmp = dir.join("db.sqlite.init-tmp");
+        let out = dir.join("db.sqlite");
+
+        std::fs::write(&tmp, "new").unwrap();
+        std::fs::write(&out, "").unwrap();
+
+        let bak = install(&tmp, &out, Existing::Discard).expect("install");
+
+        assert_eq!(bak.as_deref(), Some(with_suffix(&out, ".bak").as_path()));
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "new");
+        assert!(with_suffix(&out, ".bak").exists());
+        assert!(!tmp.exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,28 +1,84 @@
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DbHealth {
     Ok,
     Missing,
     Invalid,
 }
 
-#[derive(Debug, Clone)]
+/// Row counts; `None` when the table does not exist.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DbStatusCounts {
+    pub planets: i64,
+    pub active_planets: i64,
+    pub deleted_planets: i64,
+    pub planets_unknown: Option<i64>,
+    pub planet_aliases: Option<i64>,
+    pub planet_search: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableRows {
+    pub table: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DbFtsStatus {
+    /// `meta.fts_enabled` as stored (`None` when unset).
+    pub meta_enabled: Option<String>,
+    pub table_present: bool,
+    pub rows: Option<i64>,
+}
+
+/// Status report: `lines` is the human text, the other fields are the same data
+/// in structured form (`db status --json`).
+#[derive(Debug, Clone, Serialize)]
 pub struct DbStatusReport {
     pub db_path: PathBuf,
     pub health: DbHealth,
     pub file_size_bytes: Option<u64>,
+    /// Known meta keys that are set; edit dates also get an `<key>_iso` entry.
+    pub meta: BTreeMap<String, String>,
+    pub counts: Option<DbStatusCounts>,
+    /// Every table with its row count, largest first (only with `sizes`).
+    pub table_sizes: Option<Vec<TableRows>>,
+    /// Presence of the `v_planets_clean` view.
+    pub v_planets_clean: Option<bool>,
+    pub fts: Option<DbFtsStatus>,
+    #[serde(skip)]
     pub lines: Vec<String>,
     pub warnings: Vec<String>,
 }
 
+impl DbStatusReport {
+    fn new(db_path: PathBuf, health: DbHealth, file_size_bytes: Option<u64>) -> Self {
+        Self {
+            db_path,
+            health,
+            file_size_bytes,
+            meta: BTreeMap::new(),
+            counts: None,
+            table_sizes: None,
+            v_planets_clean: None,
+            fts: None,
+            lines: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
 pub fn resolve_db_path(db_arg: Option<String>) -> Result<PathBuf> {
     Ok(match db_arg {
         Some(p) => PathBuf::from(p),
-        None => crate::db::paths::default_db_path()?,
+        None => crate::db::paths::env_or_default_db_path()?,
     })
 }
 
@@ -68,11 +124,35 @@ fn has_table(con: &Connection, name: &str) -> Result<bool> {
     Ok(n > 0)
 }
 
+/// Row count of every table (SQLite internals excluded), largest first.
+fn table_sizes(con: &Connection) -> Result<Vec<(String, i64)>> {
+    let mut stmt = con.prepare(
+        r#"
+        SELECT name
+        FROM sqlite_master
+        WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+        ORDER BY name
+        "#,
+    )?;
+    let names = stmt
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut sizes = Vec::with_capacity(names.len());
+    for name in names {
+        let quoted = format!("\"{}\"", name.replace('"', "\"\""));
+        sizes.push((name, count(con, &quoted)?));
+    }
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(sizes)
+}
+
 fn push_kv(lines: &mut Vec<String>, label: &str, value: impl std::fmt::Display) {
     lines.push(format!("  {}: {}", label, value));
 }
 
-pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
+/// `sizes` adds a per-table row count section (largest first).
+pub fn run(db_arg: Option<String>, sizes: bool) -> Result<DbStatusReport> {
     let db_path = resolve_db_path(db_arg)?;
     let mut lines = vec![format!("Database path: {}", db_path.display())];
     let mut warnings = Vec::new();
@@ -80,11 +160,9 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
     if !db_path.exists() {
         warnings.push("Hint: run `sw_galaxy_map db init` to create it.".to_string());
         return Ok(DbStatusReport {
-            db_path,
-            health: DbHealth::Missing,
-            file_size_bytes: None,
             lines,
             warnings,
+            ..DbStatusReport::new(db_path, DbHealth::Missing, None)
         });
     }
 
@@ -102,14 +180,14 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
                 .to_string(),
         );
         return Ok(DbStatusReport {
-            db_path,
-            health: DbHealth::Invalid,
-            file_size_bytes: Some(file_size_bytes),
             lines,
             warnings,
+            ..DbStatusReport::new(db_path, DbHealth::Invalid, Some(file_size_bytes))
         });
     }
 
+    let mut report = DbStatusReport::new(db_path, DbHealth::Ok, Some(file_size_bytes));
+
     lines.push(String::new());
     lines.push("Meta:".to_string());
     for k in [
@@ -117,6 +195,7 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
         "imported_at_utc",
         "last_update_utc",
         "source_serviceItemId",
+        "source_serviceUrl",
         "source_currentVersion",
         "source_maxRecordCount",
         "source_lastEditDate",
@@ -132,11 +211,13 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
             match k {
                 "source_lastEditDate" | "source_schemaLastEditDate" | "source_dataLastEditDate" => {
                     if let Some((label, value)) = epoch_millis_iso(&con, k)? {
-                        push_kv(&mut lines, &label, value);
+                        push_kv(&mut lines, &label, &value);
+                        report.meta.insert(label, value);
                     }
                 }
-                _ => push_kv(&mut lines, k, v),
+                _ => push_kv(&mut lines, k, &v),
             }
+            report.meta.insert(k.to_string(), v);
         }
     }
 
@@ -145,52 +226,66 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
     let planets_total = count(&con, "planets")?;
     push_kv(&mut lines, "planets", planets_total);
 
-    let active = con
+    let active_n: i64 = con
         .query_row(
-            "SELECT COUNT(*) FROM planets WHERE p.status NOT IN ('deleted', 'skipped', 'invalid')",
+            "SELECT COUNT(*) FROM planets WHERE status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid')",
             [],
-            |r| r.get::<_, i64>(0),
+            |r| r.get(0),
         )
-        .optional();
-    if let Ok(Some(active_n)) = active {
-        let deleted_n = planets_total - active_n;
-        push_kv(&mut lines, "active_planets (deleted=0)", active_n);
-        push_kv(&mut lines, "deleted_planets (deleted=1)", deleted_n);
-    }
+        .context("Failed to count active planets")?;
+    let deleted_n = planets_total - active_n;
+    push_kv(&mut lines, "active_planets", active_n);
+    push_kv(&mut lines, "deleted_planets", deleted_n);
 
-    if has_table(&con, "planets_unknown")? {
-        push_kv(
-            &mut lines,
-            "planets_unknown",
-            count(&con, "planets_unknown")?,
-        );
-    } else {
-        push_kv(&mut lines, "planets_unknown", "-");
-    }
+    let mut optional_count = |table: &str| -> Result<Option<i64>> {
+        let n = if has_table(&con, table)? {
+            Some(count(&con, table)?)
+        } else {
+            None
+        };
+        match n {
+            Some(n) => push_kv(&mut lines, table, n),
+            None => push_kv(&mut lines, table, "-"),
+        }
+        Ok(n)
+    };
+    report.counts = Some(DbStatusCounts {
+        planets: planets_total,
+        active_planets: active_n,
+        deleted_planets: deleted_n,
+        planets_unknown: optional_count("planets_unknown")?,
+        planet_aliases: optional_count("planet_aliases")?,
+        planet_search: optional_count("planet_search")?,
+    });
 
-    if has_table(&con, "planet_aliases")? {
-        push_kv(&mut lines, "planet_aliases", count(&con, "planet_aliases")?);
-    } else {
-        push_kv(&mut lines, "planet_aliases", "-");
-    }
+    if sizes {
+        let tables = table_sizes(&con)?;
+        let total: i64 = tables.iter().map(|(_, n)| n).sum();
+        let name_w = tables.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
 
-    if has_table(&con, "planet_search")? {
-        push_kv(&mut lines, "planet_search", count(&con, "planet_search")?);
-    } else {
-        push_kv(&mut lines, "planet_search", "-");
+        lines.push(String::new());
+        lines.push("Table sizes (rows):".to_string());
+        for (name, n) in &tables {
+            lines.push(format!("  {:<name_w$} : {:>10}", name, n));
+        }
+        lines.push(format!("  {:<name_w$} : {:>10}", "total", total));
+        report.table_sizes = Some(
+            tables
+                .into_iter()
+                .map(|(table, rows)| TableRows { table, rows })
+                .collect(),
+        );
     }
 
     lines.push(String::new());
     lines.push("Schema:".to_string());
+    let clean_view = has_view(&con, "v_planets_clean")?;
     push_kv(
         &mut lines,
         "v_planets_clean",
-        if has_view(&con, "v_planets_clean")? {
-            "present"
-        } else {
-            "missing"
-        },
+        if clean_view { "present" } else { "missing" },
     );
+    report.v_planets_clean = Some(clean_view);
 
     lines.push(String::new());
     lines.push("FTS:".to_string());
@@ -209,9 +304,13 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
         if fts_table { "present" } else { "missing" },
     );
 
-    if fts_table {
-        push_kv(&mut lines, "planets_fts rows", count(&con, "planets_fts")?);
-    }
+    let fts_rows = if fts_table {
+        let n = count(&con, "planets_fts")?;
+        push_kv(&mut lines, "planets_fts rows", n);
+        Some(n)
+    } else {
+        None
+    };
 
     if meta_flag && !fts_table {
         warnings
@@ -220,13 +319,14 @@ pub fn run(db_arg: Option<String>) -> Result<DbStatusReport> {
         warnings.push("warning: planets_fts exists but meta says FTS is disabled".to_string());
     }
 
-    Ok(DbStatusReport {
-        db_path,
-        health: DbHealth::Ok,
-        file_size_bytes: Some(file_size_bytes),
-        lines,
-        warnings,
-    })
+    report.fts = Some(DbFtsStatus {
+        meta_enabled: fts_enabled,
+        table_present: fts_table,
+        rows: fts_rows,
+    });
+    report.lines = lines;
+    report.warnings = warnings;
+    Ok(report)
 }
 
 fn epoch_millis_iso(con: &Connection, key: &str) -> Result<Option<(String, String)>> {
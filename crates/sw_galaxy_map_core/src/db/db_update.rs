@@ -1,18 +1,21 @@
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, Transaction, params};
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 use crate::db::provision::{
     meta_upsert_public, rebuild_planet_search_public, rebuild_planets_fts_if_enabled,
 };
-use crate::provision::arcgis;
+use crate::provision::{arcgis, cancel};
 use crate::utils::normalize::normalize_text;
 
 // ----------------------------
 // Stats collection (optional)
 // ----------------------------
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ChangeKind {
     Inserted,
     Updated,
@@ -20,7 +23,7 @@ pub enum ChangeKind {
     MarkedDeleted,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ChangeEvent {
     pub fid: i64,
     pub kind: ChangeKind,
@@ -50,13 +53,33 @@ pub struct UpdateStatsReport {
     pub first_changed: Vec<ChangeEvent>,
 }
 
+/// Phase timings collected with `--bench` (dry-run only).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UpdateTimings {
+    pub download: Duration,
+    /// Time spent hashing all features (wall clock, so parallel hashing shows up).
+    pub hash: Duration,
+    /// Per-feature comparison against the local DB (excluding hashing).
+    pub compare: Duration,
+    pub total: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct DbUpdateReport {
     pub downloaded_features: usize,
     pub dry_run: bool,
     pub prune: bool,
+    /// False when the mark-deleted pass was skipped (local-only planets preserved).
+    pub mark_deleted: bool,
     pub summary: UpdateSummary,
     pub stats: Option<UpdateStatsReport>,
+    /// Every change event, uncapped (only filled with `all_events`).
+    pub events: Vec<ChangeEvent>,
+    /// Edit-date cutoff (epoch ms) when only recently edited features were fetched.
+    pub since: Option<i64>,
+    /// Why a requested `--since` fell back to a full download.
+    pub since_fallback: Option<String>,
+    pub timings: Option<UpdateTimings>,
 }
 
 pub struct SkippedPlanetRow {
@@ -67,33 +90,10 @@ pub struct SkippedPlanetRow {
     pub reason: String,
 }
 
-fn compute_arcgis_hash(a: &Value) -> String {
-    // Must match the one used in provision (keep in sync)
-    let keys = [
-        "FID",
-        "Planet",
-        "Region",
-        "Sector",
-        "System",
-        "Grid",
-        "X",
-        "Y",
-        "Canon",
-        "Legends",
-        "zm",
-        "name0",
-        "name1",
-        "name2",
-        "lat",
-        "long",
-        "ref",
-        "status",
-        "CRegion",
-        "CRegion_li",
-    ];
-
+pub(crate) fn compute_arcgis_hash(a: &Value) -> String {
+    // Must match the one used in provision
     let mut s = String::new();
-    for k in keys {
+    for k in crate::db::provision::ARCGIS_HASH_FIELDS {
         s.push_str(k);
         s.push('=');
 
@@ -126,7 +126,32 @@ fn get_s(a: &Value, k: &str) -> Option<String> {
         .map(|s| s.trim().to_string())
 }
 
-fn upsert_planet(tx: &Transaction<'_>, a: &Value) -> Result<()> {
+/// `compute_arcgis_hash` of every feature, in feature order.
+///
+/// With `parallel`, the features are split into one contiguous chunk per
+/// available core and the chunks are hashed on scoped threads.
+fn hash_features(features: &[Value], parallel: bool) -> Vec<String> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if !parallel || threads < 2 || features.len() < 2 {
+        return features.iter().map(compute_arcgis_hash).collect();
+    }
+
+    let chunk = features.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = features
+            .chunks(chunk)
+            .map(|part| {
+                scope.spawn(move || part.iter().map(compute_arcgis_hash).collect::<Vec<_>>())
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("hash worker panicked"))
+            .collect()
+    })
+}
+
+pub(crate) fn upsert_planet(tx: &Transaction<'_>, a: &Value) -> Result<()> {
     let fid = get_i(a, "FID").context("Missing FID")?;
     let planet = get_s(a, "Planet").unwrap_or_default();
     let x = get_f(a, "X").context("Missing X")?;
@@ -140,14 +165,13 @@ fn upsert_planet(tx: &Transaction<'_>, a: &Value) -> Result<()> {
     let planet_norm = normalize_text(&planet);
     let arcgis_hash = compute_arcgis_hash(a);
 
-    // DELETE + INSERT strategy (ensures aliases cascade cleanly)
-    tx.execute("DELETE FROM planets WHERE FID = ?1", params![fid])?;
-
+    // Update in place: deleting the row would trip the routes FK and cascade away
+    // waypoint links. Feed-derived aliases are replaced below.
     tx.execute(
         r#"
         INSERT INTO planets(
             FID, Planet, planet_norm, Region, Sector, System, Grid,
-            X, Y, arcgis_hash,
+            X, Y, arcgis_hash, deleted,
             Canon, Legends, zm,
             name0, name1, name2,
             lat, long, ref, status, CRegion, CRegion_li
@@ -159,6 +183,29 @@ fn upsert_planet(tx: &Transaction<'_>, a: &Value) -> Result<()> {
             ?14, ?15, ?16,
             ?17, ?18, ?19, ?20, ?21, ?22
         )
+        ON CONFLICT(FID) DO UPDATE SET
+            Planet = excluded.Planet,
+            planet_norm = excluded.planet_norm,
+            Region = excluded.Region,
+            Sector = excluded.Sector,
+            System = excluded.System,
+            Grid = excluded.Grid,
+            X = excluded.X,
+            Y = excluded.Y,
+            arcgis_hash = excluded.arcgis_hash,
+            deleted = 0,
+            Canon = excluded.Canon,
+            Legends = excluded.Legends,
+            zm = excluded.zm,
+            name0 = excluded.name0,
+            name1 = excluded.name1,
+            name2 = excluded.name2,
+            lat = excluded.lat,
+            long = excluded.long,
+            ref = excluded.ref,
+            status = excluded.status,
+            CRegion = excluded.CRegion,
+            CRegion_li = excluded.CRegion_li
         "#,
         params![
             fid,
@@ -187,6 +234,10 @@ fn upsert_planet(tx: &Transaction<'_>, a: &Value) -> Result<()> {
     )?;
 
     // Insert aliases from name0/name1/name2
+    tx.execute(
+        "DELETE FROM planet_aliases WHERE planet_fid = ?1 AND source IN ('name0', 'name1', 'name2')",
+        params![fid],
+    )?;
     let mut stmt_alias = tx.prepare_cached(
         r#"
         INSERT OR IGNORE INTO planet_aliases(planet_fid, alias, alias_norm, source)
@@ -206,7 +257,7 @@ fn upsert_planet(tx: &Transaction<'_>, a: &Value) -> Result<()> {
     Ok(())
 }
 
-fn db_get_hash_and_status(
+pub(crate) fn db_get_hash_and_status(
     tx: &Transaction<'_>,
     fid: i64,
 ) -> Result<Option<(String, Option<String>)>> {
@@ -220,7 +271,7 @@ fn db_get_hash_and_status(
 }
 
 fn mark_deleted_missing(tx: &Transaction<'_>, keep_fids: &HashSet<i64>) -> Result<i64> {
-    // Mark planets not in remote feed via status = 'deleted'
+    // Mark planets not in remote feed via status = 'deleted' (local planets are never in it)
     tx.execute_batch(
         "DROP TABLE IF EXISTS __keep_fids; CREATE TEMP TABLE __keep_fids(fid INTEGER PRIMARY KEY);",
     )?;
@@ -237,6 +288,7 @@ fn mark_deleted_missing(tx: &Transaction<'_>, keep_fids: &HashSet<i64>) -> Resul
         UPDATE planets
         SET status = 'deleted'
         WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+          AND (source IS NULL OR source <> 'local')
           AND FID NOT IN (SELECT fid FROM __keep_fids)
         "#,
         [],
@@ -246,29 +298,153 @@ fn mark_deleted_missing(tx: &Transaction<'_>, keep_fids: &HashSet<i64>) -> Resul
     Ok(changed)
 }
 
+fn meta_get(con: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(con
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |r| r.get(0))
+        .optional()?)
+}
+
 fn prune_deleted(tx: &Transaction<'_>) -> Result<i64> {
     // FK cascades will remove aliases/search automatically (where linked).
     let n = tx.execute("DELETE FROM planets WHERE status = 'deleted'", [])? as i64;
     Ok(n)
 }
 
+/// Cutoff for `db update --since`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSince {
+    /// The `source_dataLastEditDate` stored by the previous update.
+    Stored,
+    /// Explicit timestamp (epoch milliseconds).
+    At(i64),
+}
+
+/// Parses a `--since` value: RFC 3339, `YYYY-MM-DD[THH:MM:SS]` (UTC) or a Unix epoch.
+///
+/// Epoch values below 10^11 are read as seconds, larger ones as milliseconds
+/// (the unit stored in `meta`).
+pub fn parse_since(s: &str) -> Result<i64> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<i64>() {
+        return Ok(if n.abs() < 100_000_000_000 {
+            n * 1000
+        } else {
+            n
+        });
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.timestamp_millis());
+    }
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+        return Ok(dt.and_utc().timestamp_millis());
+    }
+    if let Ok(d) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(d
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .timestamp_millis());
+    }
+    anyhow::bail!(
+        "Invalid --since value '{}': expected an ISO date/time or a Unix epoch",
+        s
+    )
+}
+
+/// Downloads the ArcGIS feed and applies it to the local planets.
+///
+/// `stats` keeps a capped sample of change events for the `--stats` preview;
+/// `all_events` records every event in [`DbUpdateReport::events`] (`--stats-json`).
+///
+/// With `since`, only features edited after the cutoff are fetched (when the layer
+/// tracks edit dates). Such a partial feed says nothing about missing planets, so
+/// the mark-deleted pass is skipped and stale unknown-planet rows are kept.
+///
+/// `parallel_hash` spreads feature hashing over the available cores; the
+/// report is identical either way.
+///
+/// `service_url` overrides the ArcGIS layer and requests are retried per `retry`;
+/// `progress` is called while features are downloaded.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     con: &mut Connection,
     prune: bool,
     dry_run: bool,
+    mark_deleted: bool,
     stats: bool,
     stats_limit: usize,
+    all_events: bool,
+    since: Option<UpdateSince>,
+    bench: bool,
+    parallel_hash: bool,
+    service_url: Option<&str>,
+    retry: &arcgis::RetryPolicy,
+    progress: arcgis::FetchProgress<'_>,
 ) -> Result<DbUpdateReport> {
+    if bench && !dry_run {
+        anyhow::bail!("--bench requires --dry-run");
+    }
+    let layer_url = arcgis::layer_url(service_url)?;
+
+    cancel::reset();
+    let started = Instant::now();
+
     let client = reqwest::blocking::Client::builder()
         .timeout(std::time::Duration::from_secs(60))
         .build()
         .context("Failed to build HTTP client")?;
 
-    let layer = arcgis::fetch_layer_info(&client).context("Failed to fetch ArcGIS layer info")?;
+    let layer = arcgis::fetch_layer_info(&client, &layer_url, retry)
+        .context("Failed to fetch ArcGIS layer info")?;
+
+    let mut since_fallback = None;
+    let since_ms = match since {
+        None => None,
+        Some(UpdateSince::At(ms)) => Some(ms),
+        Some(UpdateSince::Stored) => {
+            let stored = meta_get(con, "source_dataLastEditDate")?.and_then(|v| v.parse().ok());
+            if stored.is_none() {
+                since_fallback = Some("no stored source_dataLastEditDate".to_string());
+            }
+            stored
+        }
+    };
+    let edit_date_field = layer
+        .edit_fields_info
+        .as_ref()
+        .and_then(|e| e.edit_date_field.as_deref());
+    let where_clause = match (since_ms, edit_date_field) {
+        (Some(ms), Some(field)) => arcgis::edited_since_clause(field, ms),
+        (Some(_), None) => {
+            since_fallback = Some("the layer does not track edit dates".to_string());
+            None
+        }
+        (None, _) => None,
+    };
+    let since_ms = where_clause.as_ref().and(since_ms);
+    let mark_deleted = mark_deleted && since_ms.is_none();
 
     let page_size = layer.max_record_count.min(2000);
-    let features = arcgis::fetch_all_features(&client, page_size)
-        .context("Failed to download features from ArcGIS")?;
+    let mut features = arcgis::fetch_features(
+        &client,
+        &layer_url,
+        page_size,
+        where_clause.as_deref().unwrap_or("1=1"),
+        retry,
+        progress,
+    )
+    .map_err(|e| {
+        if cancel::is_cancelled() {
+            e
+        } else {
+            e.context("Failed to download features from ArcGIS")
+        }
+    })?;
+    let download_elapsed = started.elapsed();
+
+    // Compare in FID order (rows without FID last) so the capped `events` buffer,
+    // and therefore --stats, doesn't depend on the order the feed was served in.
+    features.sort_by_key(|a| get_i(a, "FID").map_or((1, 0), |fid| (0, fid)));
 
     // Start transaction: gives consistent view and allows temp tables.
     // In dry-run we will NOT commit -> changes (if any) won't persist.
@@ -279,6 +455,7 @@ pub fn run(
     // Write meta only in real mode
     if !dry_run {
         meta_upsert_public(&tx, "source_serviceItemId", &layer.service_item_id)?;
+        meta_upsert_public(&tx, "source_serviceUrl", &layer_url)?;
 
         meta_upsert_public(
             &tx,
@@ -312,6 +489,7 @@ pub fn run(
     }
 
     let mut events: Vec<ChangeEvent> = Vec::new();
+    let record_event = |n: usize| all_events || (stats && n < stats_limit * 50);
 
     // Keep set of FIDs present in remote feed
     let mut keep = HashSet::<i64>::with_capacity(features.len());
@@ -336,8 +514,13 @@ pub fn run(
             .filter(|s| !s.is_empty())
     };
 
+    let hash_started = Instant::now();
+    let hashes = hash_features(&features, parallel_hash);
+    let hash_elapsed = hash_started.elapsed();
+
     // 1) Per-feature compare (and apply only if !dry_run)
-    for a in &features {
+    let compare_started = Instant::now();
+    for (a, new_hash) in features.iter().zip(&hashes) {
         let fid = match get_i(a, "FID") {
             Some(v) => v,
             None => {
@@ -398,12 +581,10 @@ pub fn run(
             continue;
         }
 
-        let new_hash = compute_arcgis_hash(a);
-
         match db_get_hash_and_status(&tx, fid)? {
             None => {
                 inserted += 1;
-                if stats && (events.len() < stats_limit * 50) {
+                if record_event(events.len()) {
                     events.push(ChangeEvent {
                         fid,
                         kind: ChangeKind::Inserted,
@@ -419,7 +600,7 @@ pub fn run(
 
                 if is_deleted {
                     revived += 1;
-                    if stats && (events.len() < stats_limit * 50) {
+                    if record_event(events.len()) {
                         events.push(ChangeEvent {
                             fid,
                             kind: ChangeKind::Revived,
@@ -430,9 +611,9 @@ pub fn run(
                         // revive by forcing rewrite
                         upsert_planet(&tx, a)?;
                     }
-                } else if old_hash != new_hash {
+                } else if old_hash != *new_hash {
                     updated += 1;
-                    if stats && (events.len() < stats_limit * 50) {
+                    if record_event(events.len()) {
                         events.push(ChangeEvent {
                             fid,
                             kind: ChangeKind::Updated,
@@ -449,21 +630,31 @@ pub fn run(
         }
     }
 
+    let compare_elapsed = compare_started.elapsed();
+
     // 2) Soft-delete missing (real) OR compute count (dry-run)
+    // Skipped entirely with --no-mark-deleted: planets missing from the feed are kept as-is.
     // If stats enabled, capture a preview of top missing (FID, Planet) before actually updating.
     let mut deleted_preview: Vec<(i64, String)> = Vec::new();
-    if stats {
-        deleted_preview = select_missing_active_planets(&tx, &keep, stats_limit)
+    if (stats || all_events) && mark_deleted {
+        let limit = if all_events {
+            i64::MAX as usize
+        } else {
+            stats_limit
+        };
+        deleted_preview = select_missing_active_planets(&tx, &keep, limit)
             .context("Failed to compute missing planets preview for --stats")?;
     }
 
-    let marked_deleted: i64 = if dry_run {
+    let marked_deleted: i64 = if !mark_deleted {
+        0
+    } else if dry_run {
         count_missing_active_planets(&tx, &keep)?
     } else {
         mark_deleted_missing(&tx, &keep)?
     };
 
-    if stats {
+    if stats || all_events {
         for (fid, planet) in deleted_preview {
             events.push(ChangeEvent {
                 fid,
@@ -498,12 +689,40 @@ pub fn run(
         rebuild_planets_fts_if_enabled(&tx)?;
 
         // Update meta
-        meta_upsert_public(&tx, "last_update_utc", &crate::utils::time::now_utc_iso())?;
+        let ran_at = crate::utils::time::now_utc_iso();
+        meta_upsert_public(&tx, "last_update_utc", &ran_at)?;
         meta_upsert_public(&tx, "update_mode", "incremental")?;
         meta_upsert_public(&tx, "prune_used", if prune { "1" } else { "0" })?;
 
-        sync_unknown_planets(&tx, &skipped_rows)?;
+        sync_unknown_planets(&tx, &skipped_rows, since_ms.is_none())?;
+
+        tx.execute(
+            r#"
+            INSERT INTO update_log(
+                ran_at, downloaded_features,
+                inserted, updated, revived, unchanged,
+                marked_deleted, pruned, skipped,
+                prune_used, mark_deleted_used
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+            rusqlite::params![
+                ran_at,
+                features.len() as i64,
+                inserted,
+                updated,
+                revived,
+                unchanged,
+                marked_deleted,
+                pruned,
+                skipped,
+                prune,
+                mark_deleted,
+            ],
+        )
+        .context("Failed to record update_log entry")?;
 
+        // Dropping the transaction on bail rolls everything back.
+        cancel::check()?;
         tx.commit().context("Failed to commit db update")?;
     } else {
         // No commit: transaction rolls back automatically on drop
@@ -554,8 +773,18 @@ pub fn run(
         downloaded_features: features.len(),
         dry_run,
         prune,
+        mark_deleted,
         summary,
         stats: stats_report,
+        events: if all_events { events } else { Vec::new() },
+        since: since_ms,
+        since_fallback,
+        timings: bench.then(|| UpdateTimings {
+            download: download_elapsed,
+            hash: hash_elapsed,
+            compare: compare_elapsed,
+            total: started.elapsed(),
+        }),
     })
 }
 
@@ -582,6 +811,7 @@ fn select_missing_active_planets(
         SELECT FID, Planet
         FROM planets
         WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+          AND (source IS NULL OR source <> 'local')
           AND FID NOT IN (SELECT fid FROM __keep_fids)
         ORDER BY FID
         LIMIT ?1
@@ -620,6 +850,7 @@ fn count_missing_active_planets(tx: &Transaction<'_>, keep_fids: &HashSet<i64>)
         SELECT COUNT(*)
         FROM planets
         WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+          AND (source IS NULL OR source <> 'local')
           AND FID NOT IN (SELECT fid FROM __keep_fids)
         "#,
         [],
@@ -665,7 +896,13 @@ fn coord_key(v: Option<f64>) -> String {
     }
 }
 
-fn sync_unknown_planets(tx: &Transaction<'_>, skipped_rows: &[SkippedPlanetRow]) -> Result<()> {
+/// Upserts the skipped rows into `planets_unknown`; with `drop_unseen`, rows not in
+/// this feed are removed (only valid for a full download).
+fn sync_unknown_planets(
+    tx: &Transaction<'_>,
+    skipped_rows: &[SkippedPlanetRow],
+    drop_unseen: bool,
+) -> Result<()> {
     tx.execute_batch(
         "DROP TABLE IF EXISTS __unknown_seen_keys;
          CREATE TEMP TABLE __unknown_seen_keys(seen_key TEXT PRIMARY KEY);",
@@ -758,6 +995,11 @@ fn sync_unknown_planets(tx: &Transaction<'_>, skipped_rows: &[SkippedPlanetRow])
     drop(update_existing);
     drop(insert_new);
 
+    if !drop_unseen {
+        tx.execute_batch("DROP TABLE IF EXISTS __unknown_seen_keys;")?;
+        return Ok(());
+    }
+
     tx.execute(
         r#"
         DELETE FROM planets_unknown
@@ -775,3 +1017,96 @@ fn sync_unknown_planets(tx: &Transaction<'_>, skipped_rows: &[SkippedPlanetRow])
     tx.execute_batch("DROP TABLE IF EXISTS __unknown_seen_keys;")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_arcgis_hash, hash_features, parse_since, upsert_planet};
+    use crate::provision::arcgis::edited_since_clause;
+    use rusqlite::Connection;
+    use serde_json::json;
+
+    #[test]
+    fn parallel_hashing_matches_sequential_order() {
+        let features: Vec<_> = (0..257)
+            .map(|fid| json!({"FID": fid, "Planet": format!("P{fid}"), "X": fid, "Y": 1.5}))
+            .collect();
+
+        let sequential = hash_features(&features, false);
+        assert_eq!(sequential.len(), features.len());
+        assert_eq!(sequential[42], compute_arcgis_hash(&features[42]));
+        assert_eq!(hash_features(&features, true), sequential);
+        assert!(hash_features(&[], true).is_empty());
+    }
+
+    #[test]
+    fn parse_since_accepts_iso_and_epoch() {
+        let ms = 1_704_067_200_000; // 2024-01-01T00:00:00Z
+        assert_eq!(parse_since("2024-01-01").unwrap(), ms);
+        assert_eq!(parse_since("2024-01-01T00:00:00").unwrap(), ms);
+        assert_eq!(parse_since("2024-01-01T01:00:00+01:00").unwrap(), ms);
+        assert_eq!(parse_since("1704067200").unwrap(), ms);
+        assert_eq!(parse_since("1704067200000").unwrap(), ms);
+        assert!(parse_since("yesterday").is_err());
+
+        assert_eq!(
+            edited_since_clause("EditDate", ms).as_deref(),
+            Some("EditDate > TIMESTAMP '2024-01-01 00:00:00'")
+        );
+    }
+
+    #[test]
+    fn upsert_planet_updates_in_place_and_keeps_references() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+
+        let attrs = |fid: i64, name: &str, x: f64, alias: &str| {
+            json!({
+                "FID": fid, "Planet": name, "X": x, "Y": 0.0,
+                "Region": "Core", "name0": alias, "status": "active",
+            })
+        };
+
+        let tx = con.transaction().expect("tx");
+        upsert_planet(&tx, &attrs(1, "Alderaan", 1.0, "Alderaa")).expect("insert");
+        upsert_planet(&tx, &attrs(2, "Tatooine", 9.0, "Tatoo")).expect("insert");
+        tx.execute_batch(
+            r#"
+            INSERT INTO routes (from_planet_fid, to_planet_fid, algo_version, options_json)
+                VALUES (1, 2, 'v1', '{}');
+            INSERT INTO waypoints (name, name_norm, x, y) VALUES ('Gate', 'gate', 1.0, 0.0);
+            INSERT INTO waypoint_planets (waypoint_id, planet_fid) VALUES (1, 1);
+            "#,
+        )
+        .expect("references");
+
+        // Re-upserting a planet referenced by a route must not trip the FK, and must
+        // not cascade its waypoint links away.
+        upsert_planet(&tx, &attrs(1, "Alderaan Prime", 2.0, "Old Alderaan")).expect("update");
+        tx.commit().expect("commit");
+
+        let (name, x): (String, f64) = con
+            .query_row("SELECT Planet, X FROM planets WHERE FID = 1", [], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .expect("planet");
+        assert_eq!((name.as_str(), x), ("Alderaan Prime", 2.0));
+
+        let links: i64 = con
+            .query_row(
+                "SELECT COUNT(*) FROM waypoint_planets WHERE planet_fid = 1",
+                [],
+                |r| r.get(0),
+            )
+            .expect("links");
+        assert_eq!(links, 1);
+
+        let aliases: Vec<String> = con
+            .prepare("SELECT alias FROM planet_aliases WHERE planet_fid = 1 ORDER BY alias")
+            .expect("prepare")
+            .query_map([], |r| r.get(0))
+            .expect("aliases")
+            .collect::<rusqlite::Result<_>>()
+            .expect("collect");
+        assert_eq!(aliases, vec!["Old Alderaan".to_string()]);
+    }
+}
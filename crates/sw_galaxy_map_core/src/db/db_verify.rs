@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::db::migrate::LATEST_SCHEMA_VERSION;
+
+/// Outcome of a single `db verify` check.
+#[derive(Debug, Clone)]
+pub struct VerifyCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DbVerifyReport {
+    pub checks: Vec<VerifyCheck>,
+}
+
+impl DbVerifyReport {
+    pub fn failed(&self) -> usize {
+        self.checks.iter().filter(|c| !c.ok).count()
+    }
+
+    fn push(&mut self, name: &'static str, ok: bool, detail: impl Into<String>) {
+        self.checks.push(VerifyCheck {
+            name,
+            ok,
+            detail: detail.into(),
+        });
+    }
+}
+
+fn count(con: &Connection, sql: &str) -> Result<i64> {
+    con.query_row(sql, [], |r| r.get(0))
+        .with_context(|| format!("Verify query failed: {}", sql.trim()))
+}
+
+fn meta_get(con: &Connection, key: &str) -> Result<Option<String>> {
+    con.query_row("SELECT value FROM meta WHERE key = ?1", [key], |r| {
+        r.get::<_, String>(0)
+    })
+    .optional()
+    .with_context(|| format!("Failed to read meta key: {}", key))
+}
+
+fn has_table(con: &Connection, name: &str) -> Result<bool> {
+    let n: i64 = con.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [name],
+        |r| r.get(0),
+    )?;
+    Ok(n > 0)
+}
+
+fn integrity_check(con: &Connection, report: &mut DbVerifyReport) -> Result<()> {
+    let mut stmt = con.prepare("PRAGMA integrity_check")?;
+    let rows = stmt
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("PRAGMA integrity_check failed")?;
+
+    let ok = rows.len() == 1 && rows[0] == "ok";
+    let detail = if ok {
+        "ok".to_string()
+    } else {
+        rows.join("; ")
+    };
+    report.push("integrity_check", ok, detail);
+    Ok(())
+}
+
+fn schema_version(con: &Connection, report: &mut DbVerifyReport) -> Result<()> {
+    let current = meta_get(con, "schema_version")?;
+    let parsed = current.as_deref().and_then(|v| v.parse::<i64>().ok());
+    let ok = parsed == Some(LATEST_SCHEMA_VERSION);
+    report.push(
+        "schema_version",
+        ok,
+        format!(
+            "v{} (expected v{})",
+            current.as_deref().unwrap_or("-"),
+            LATEST_SCHEMA_VERSION
+        ),
+    );
+    Ok(())
+}
+
+fn fts_rows(con: &Connection, report: &mut DbVerifyReport) -> Result<()> {
+    let enabled = matches!(meta_get(con, "fts_enabled")?.as_deref(), Some("1"));
+    if !enabled {
+        report.push("planets_fts", true, "FTS disabled; skipped");
+        return Ok(());
+    }
+    if !has_table(con, "planets_fts")? {
+        report.push(
+            "planets_fts",
+            false,
+            "meta.fts_enabled=1 but table is missing",
+        );
+        return Ok(());
+    }
+
+    let fts = count(con, "SELECT COUNT(*) FROM planets_fts")?;
+    let active = count(
+        con,
+        "SELECT COUNT(*) FROM planets WHERE status NOT IN ('deleted', 'skipped', 'invalid')",
+    )?;
+    report.push(
+        "planets_fts",
+        fts == active,
+        format!("{} rows, {} active planets", fts, active),
+    );
+    Ok(())
+}
+
+fn orphans(
+    con: &Connection,
+    report: &mut DbVerifyReport,
+    name: &'static str,
+    sql: &str,
+) -> Result<()> {
+    let n = count(con, sql)?;
+    let detail = if n == 0 {
+        "no orphan rows".to_string()
+    } else {
+        format!("{} orphan rows", n)
+    };
+    report.push(name, n == 0, detail);
+    Ok(())
+}
+
+/// Runs every health check; a failed check is reported, not returned as an error.
+///
+/// Errors are reserved for queries that cannot run at all (e.g. missing tables).
+pub fn run(con: &Connection) -> Result<DbVerifyReport> {
+    let mut report = DbVerifyReport::default();
+
+    integrity_check(con, &mut report)?;
+    schema_version(con, &mut report)?;
+    fts_rows(con, &mut report)?;
+
+    orphans(
+        con,
+        &mut report,
+        "waypoint_planets",
+        r#"
+        SELECT COUNT(*)
+        FROM waypoint_planets wp
+        WHERE NOT EXISTS (SELECT 1 FROM waypoints w WHERE w.id = wp.waypoint_id)
+           OR NOT EXISTS (SELECT 1 FROM planets p WHERE p.FID = wp.planet_fid)
+        "#,
+    )?;
+    orphans(
+        con,
+        &mut report,
+        "route_waypoints",
+        r#"
+        SELECT COUNT(*)
+        FROM route_waypoints rw
+        WHERE NOT EXISTS (SELECT 1 FROM routes r WHERE r.id = rw.route_id)
+           OR (rw.waypoint_id IS NOT NULL
+               AND NOT EXISTS (SELECT 1 FROM waypoints w WHERE w.id = rw.waypoint_id))
+        "#,
+    )?;
+    orphans(
+        con,
+        &mut report,
+        "route_detours",
+        r#"
+        SELECT COUNT(*)
+        FROM route_detours rd
+        WHERE NOT EXISTS (SELECT 1 FROM routes r WHERE r.id = rd.route_id)
+           OR (rd.waypoint_id IS NOT NULL
+               AND NOT EXISTS (SELECT 1 FROM waypoints w WHERE w.id = rd.waypoint_id))
+        "#,
+    )?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<'a>(report: &'a DbVerifyReport, name: &str) -> &'a VerifyCheck {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .expect("check present")
+    }
+
+    #[test]
+    fn verify_flags_orphans_and_stale_schema() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(&format!(
+            r#"
+            INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '{LATEST_SCHEMA_VERSION}');
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Tatooine', 'tatooine', 1.0, 1.0, 'h1');
+            INSERT INTO waypoints (id, name, name_norm, x, y, kind, fingerprint) VALUES
+                (1, 'Buoy', 'buoy', 0.0, 0.0, 'manual', 'fp1');
+            INSERT INTO waypoint_planets (waypoint_id, planet_fid, role, distance) VALUES
+                (1, 1, 'near', 1.0);
+            "#
+        ))
+        .expect("seed");
+
+        let clean = run(&con).expect("verify");
+        assert_eq!(clean.failed(), 0, "{:?}", clean.checks);
+
+        con.execute_batch(
+            r#"
+            PRAGMA foreign_keys = OFF;
+            INSERT INTO waypoint_planets (waypoint_id, planet_fid, role, distance) VALUES
+                (1, 999, 'near', 2.0);
+            INSERT INTO route_waypoints (route_id, seq, x, y) VALUES (42, 0, 0.0, 0.0);
+            UPDATE meta SET value = '3' WHERE key = 'schema_version';
+            "#,
+        )
+        .expect("break invariants");
+
+        let dirty = run(&con).expect("verify");
+        assert!(!check(&dirty, "schema_version").ok);
+        assert!(!check(&dirty, "waypoint_planets").ok);
+        assert!(!check(&dirty, "route_waypoints").ok);
+        assert!(check(&dirty, "route_detours").ok);
+        assert!(check(&dirty, "integrity_check").ok);
+        assert_eq!(dirty.failed(), 3);
+    }
+}
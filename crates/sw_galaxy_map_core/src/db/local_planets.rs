@@ -0,0 +1,301 @@
+use crate::db::provision::{rebuild_planet_search_public, rebuild_planets_fts_if_enabled};
+use crate::db::queries::get_planet_by_fid;
+use crate::model::Planet;
+use crate::utils::normalize::normalize_text;
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+
+/// First FID of the range reserved for user-authored planets.
+///
+/// Remote FIDs stay well below this value, so local rows never collide with
+/// the canonical dataset.
+pub const LOCAL_FID_MIN: i64 = 1_000_000;
+
+/// Value of `planets.source` for user-authored rows.
+pub const LOCAL_SOURCE: &str = "local";
+
+/// New local planet. `fid = None` picks the next free FID in the reserved range.
+#[derive(Debug, Clone, Default)]
+pub struct LocalPlanetInput {
+    pub fid: Option<i64>,
+    pub planet: String,
+    pub x: f64,
+    pub y: f64,
+    pub region: Option<String>,
+    pub sector: Option<String>,
+    pub system: Option<String>,
+    pub grid: Option<String>,
+    pub canon: Option<i64>,
+    pub legends: Option<i64>,
+}
+
+/// Partial update payload for a local planet.
+#[derive(Debug, Clone, Default)]
+pub struct LocalPlanetUpdate {
+    pub planet: Option<String>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub region: Option<Option<String>>,
+    pub sector: Option<Option<String>>,
+    pub system: Option<Option<String>>,
+    pub grid: Option<Option<String>>,
+    pub canon: Option<Option<i64>>,
+    pub legends: Option<Option<i64>>,
+}
+
+impl LocalPlanetUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.planet.is_none()
+            && self.x.is_none()
+            && self.y.is_none()
+            && self.region.is_none()
+            && self.sector.is_none()
+            && self.system.is_none()
+            && self.grid.is_none()
+            && self.canon.is_none()
+            && self.legends.is_none()
+    }
+}
+
+fn next_local_fid(tx: &Transaction<'_>) -> Result<i64> {
+    let max: Option<i64> = tx.query_row(
+        "SELECT MAX(FID) FROM planets WHERE FID >= ?1",
+        params![LOCAL_FID_MIN],
+        |r| r.get(0),
+    )?;
+    Ok(max.map_or(LOCAL_FID_MIN, |m| m + 1))
+}
+
+/// Rejects a name already used by another visible planet.
+fn ensure_name_free(
+    tx: &Transaction<'_>,
+    planet_norm: &str,
+    except_fid: Option<i64>,
+) -> Result<()> {
+    let clash: Option<(i64, String)> = tx
+        .query_row(
+            r#"
+            SELECT FID, Planet
+            FROM planets
+            WHERE planet_norm = ?1
+              AND (?2 IS NULL OR FID <> ?2)
+              AND (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+            LIMIT 1
+            "#,
+            params![planet_norm, except_fid],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+
+    if let Some((fid, name)) = clash {
+        anyhow::bail!("A planet named '{}' already exists (FID={})", name, fid);
+    }
+    Ok(())
+}
+
+fn refresh_search(tx: &Transaction<'_>) -> Result<()> {
+    rebuild_planet_search_public(tx)?;
+    rebuild_planets_fts_if_enabled(tx)?;
+    Ok(())
+}
+
+/// Inserts a user-authored planet marked with `source = 'local'`.
+///
+/// `arcgis_hash` stays empty: the row has no remote attributes to hash, and
+/// `source` is what identifies it as local.
+pub fn add(con: &mut Connection, input: &LocalPlanetInput) -> Result<Planet> {
+    let planet = input.planet.trim();
+    if planet.is_empty() {
+        anyhow::bail!("Planet name cannot be empty");
+    }
+    if !input.x.is_finite() || !input.y.is_finite() {
+        anyhow::bail!("Coordinates must be finite numbers");
+    }
+    let planet_norm = normalize_text(planet);
+
+    let tx = con
+        .transaction()
+        .context("Failed to start planets add transaction")?;
+
+    ensure_name_free(&tx, &planet_norm, None)?;
+
+    let fid = match input.fid {
+        Some(fid) if fid < LOCAL_FID_MIN => {
+            anyhow::bail!(
+                "Local planet FID must be >= {} (got {})",
+                LOCAL_FID_MIN,
+                fid
+            );
+        }
+        Some(fid) => {
+            if get_planet_by_fid(&tx, fid)?.is_some() {
+                anyhow::bail!("A planet with FID={} already exists", fid);
+            }
+            fid
+        }
+        None => next_local_fid(&tx)?,
+    };
+
+    tx.execute(
+        r#"
+        INSERT INTO planets(
+            FID, Planet, planet_norm, Region, Sector, System, Grid,
+            X, Y, arcgis_hash, deleted,
+            Canon, Legends, status, source, grid_unit
+        ) VALUES (
+            ?1, ?2, ?3, ?4, ?5, ?6, ?7,
+            ?8, ?9, '', 0,
+            ?10, ?11, 'active', ?12, 'ly'
+        )
+        "#,
+        params![
+            fid,
+            planet,
+            planet_norm,
+            input.region,
+            input.sector,
+            input.system,
+            input.grid,
+            input.x,
+            input.y,
+            input.canon,
+            input.legends,
+            LOCAL_SOURCE,
+        ],
+    )
+    .with_context(|| format!("Failed to insert local planet FID={}", fid))?;
+
+    refresh_search(&tx)?;
+    tx.commit()
+        .context("Failed to commit planets add transaction")?;
+
+    get_planet_by_fid(con, fid)?
+        .ok_or_else(|| anyhow::anyhow!("Local planet disappeared after insert: FID={}", fid))
+}
+
+/// Updates a planet previously created with [`add`]. Imported planets are rejected.
+pub fn edit(con: &mut Connection, fid: i64, update: &LocalPlanetUpdate) -> Result<Planet> {
+    let tx = con
+        .transaction()
+        .context("Failed to start planets edit transaction")?;
+
+    let source: Option<Option<String>> = tx
+        .query_row(
+            "SELECT source FROM planets WHERE FID = ?1",
+            params![fid],
+            |r| r.get(0),
+        )
+        .optional()?;
+
+    match source {
+        None => anyhow::bail!("No planet found for FID {}", fid),
+        Some(s) if s.as_deref() != Some(LOCAL_SOURCE) => anyhow::bail!(
+            "Planet FID={} comes from the remote dataset and cannot be edited; only local planets can",
+            fid
+        ),
+        Some(_) => {}
+    }
+
+    let Some(current) = get_planet_by_fid(&tx, fid)? else {
+        anyhow::bail!("No planet found for FID {}", fid);
+    };
+
+    let planet = match update.planet.as_deref().map(str::trim) {
+        Some("") => anyhow::bail!("Planet name cannot be empty"),
+        Some(p) => p.to_string(),
+        None => current.planet.clone(),
+    };
+    let planet_norm = normalize_text(&planet);
+    if planet_norm != current.planet_norm {
+        ensure_name_free(&tx, &planet_norm, Some(fid))?;
+    }
+
+    let x = update.x.unwrap_or(current.x);
+    let y = update.y.unwrap_or(current.y);
+    if !x.is_finite() || !y.is_finite() {
+        anyhow::bail!("Coordinates must be finite numbers");
+    }
+
+    let region = update.region.clone().unwrap_or(current.region);
+    let sector = update.sector.clone().unwrap_or(current.sector);
+    let system = update.system.clone().unwrap_or(current.system);
+    let grid = update.grid.clone().unwrap_or(current.grid);
+    let canon = update.canon.unwrap_or(current.canon);
+    let legends = update.legends.unwrap_or(current.legends);
+
+    tx.execute(
+        r#"
+        UPDATE planets
+        SET Planet = ?2,
+            planet_norm = ?3,
+            Region = ?4,
+            Sector = ?5,
+            System = ?6,
+            Grid = ?7,
+            X = ?8,
+            Y = ?9,
+            Canon = ?10,
+            Legends = ?11
+        WHERE FID = ?1
+        "#,
+        params![
+            fid,
+            planet,
+            planet_norm,
+            region,
+            sector,
+            system,
+            grid,
+            x,
+            y,
+            canon,
+            legends,
+        ],
+    )
+    .with_context(|| format!("Failed to update local planet FID={}", fid))?;
+
+    refresh_search(&tx)?;
+    tx.commit()
+        .context("Failed to commit planets edit transaction")?;
+
+    get_planet_by_fid(con, fid)?
+        .ok_or_else(|| anyhow::anyhow!("Local planet disappeared after update: FID={}", fid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LOCAL_FID_MIN, LOCAL_SOURCE, LocalPlanetInput, add};
+    use rusqlite::Connection;
+
+    #[test]
+    fn add_marks_the_row_local_without_a_fake_hash() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            ALTER TABLE planets ADD COLUMN source TEXT;
+            ALTER TABLE planets ADD COLUMN grid_unit TEXT NOT NULL DEFAULT 'pc';
+            "#,
+        )
+        .expect("v14 columns");
+
+        let input = LocalPlanetInput {
+            planet: "Haven".to_string(),
+            x: 1.0,
+            y: 2.0,
+            ..Default::default()
+        };
+        let planet = add(&mut con, &input).expect("add");
+        assert_eq!(planet.fid, LOCAL_FID_MIN);
+
+        let (hash, source): (String, Option<String>) = con
+            .query_row(
+                "SELECT arcgis_hash, source FROM planets WHERE FID = ?1",
+                [planet.fid],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .expect("row");
+        assert_eq!(hash, "");
+        assert_eq!(source.as_deref(), Some(LOCAL_SOURCE));
+    }
+}
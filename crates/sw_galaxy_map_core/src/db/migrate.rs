@@ -18,8 +18,26 @@ pub struct MigrationReport {
     pub applied: Vec<AppliedMigrationStep>,
 }
 
+impl MigrationReport {
+    /// True when the database schema is newer than this binary knows about.
+    pub fn newer_than_binary(&self) -> bool {
+        self.current_version > self.target_version
+    }
+
+    /// Warning for a database created by a newer version, `None` otherwise.
+    pub fn newer_schema_warning(&self) -> Option<String> {
+        self.newer_than_binary().then(|| {
+            format!(
+                "Database schema v{} is newer than this binary supports (v{}); \
+                 it was created by a newer version and some features may not work",
+                self.current_version, self.target_version
+            )
+        })
+    }
+}
+
 const START_SCHEMA_VERSION: i64 = 3;
-const LATEST_SCHEMA_VERSION: i64 = 13;
+pub const LATEST_SCHEMA_VERSION: i64 = 19;
 
 struct MigrationStep {
     from: i64,
@@ -90,6 +108,42 @@ fn migration_steps() -> &'static [MigrationStep] {
             label: "coordinates normalization + grid_unit",
             apply: m_to_v13,
         },
+        MigrationStep {
+            from: 13,
+            to: 14,
+            label: "planets source (local entries)",
+            apply: m_to_v14,
+        },
+        MigrationStep {
+            from: 14,
+            to: 15,
+            label: "update log",
+            apply: m_to_v15,
+        },
+        MigrationStep {
+            from: 15,
+            to: 16,
+            label: "waypoint fingerprint index ignores empty values",
+            apply: m_to_v16,
+        },
+        MigrationStep {
+            from: 16,
+            to: 17,
+            label: "waypoint planet links keyed by role",
+            apply: m_to_v17,
+        },
+        MigrationStep {
+            from: 17,
+            to: 18,
+            label: "planets canon/legends indexes",
+            apply: m_to_v18,
+        },
+        MigrationStep {
+            from: 18,
+            to: 19,
+            label: "routes compute timing",
+            apply: m_to_v19,
+        },
     ]
 }
 
@@ -522,6 +576,131 @@ fn m_to_v13(tx: &Transaction<'_>) -> Result<()> {
     Ok(())
 }
 
+fn m_to_v14(tx: &Transaction<'_>) -> Result<()> {
+    // NULL = imported from the remote feed; 'local' = user-authored via `planets add`.
+    if !column_exists(tx, "planets", "source")? {
+        tx.execute_batch(
+            r#"
+            ALTER TABLE planets ADD COLUMN source TEXT;
+            "#,
+        )
+        .context("Failed to add planets.source")?;
+    }
+
+    tx.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_planets_source ON planets(source);
+        "#,
+    )
+    .context("Failed to migrate schema to v14 (creation idx_planets_source index)")?;
+
+    Ok(())
+}
+
+fn m_to_v15(tx: &Transaction<'_>) -> Result<()> {
+    // One row per committed `db update`; dry runs are not recorded.
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS update_log (
+            id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+            ran_at              TEXT NOT NULL,
+            downloaded_features INTEGER NOT NULL,
+            inserted            INTEGER NOT NULL,
+            updated             INTEGER NOT NULL,
+            revived             INTEGER NOT NULL,
+            unchanged           INTEGER NOT NULL,
+            marked_deleted      INTEGER NOT NULL,
+            pruned              INTEGER NOT NULL,
+            skipped             INTEGER NOT NULL,
+            prune_used          INTEGER NOT NULL,
+            mark_deleted_used   INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_update_log_ran_at ON update_log(ran_at);
+        "#,
+    )
+    .context("Failed to migrate schema to v15 (update_log table)")?;
+
+    Ok(())
+}
+
+fn m_to_v16(tx: &Transaction<'_>) -> Result<()> {
+    // Manual waypoints all carry an empty fingerprint: only computed ones must be unique.
+    tx.execute_batch(
+        r#"
+        DROP INDEX IF EXISTS idx_waypoints_fingerprint;
+        CREATE UNIQUE INDEX idx_waypoints_fingerprint
+          ON waypoints(fingerprint)
+          WHERE fingerprint IS NOT NULL AND fingerprint <> '';
+        "#,
+    )
+    .context("Failed to migrate schema to v16 (waypoint fingerprint index)")?;
+
+    Ok(())
+}
+
+fn m_to_v17(tx: &Transaction<'_>) -> Result<()> {
+    // Rebuild with `role` in the primary key so a planet can hold several roles.
+    tx.execute_batch(
+        r#"
+        CREATE TABLE waypoint_planets_v17 (
+          waypoint_id INTEGER NOT NULL,
+          planet_fid  INTEGER NOT NULL,
+          role        TEXT NOT NULL DEFAULT 'anchor', -- anchor/avoid/near/cluster_member
+          distance    REAL,
+          PRIMARY KEY (waypoint_id, planet_fid, role),
+          FOREIGN KEY (waypoint_id) REFERENCES waypoints(id) ON DELETE CASCADE,
+          FOREIGN KEY (planet_fid)  REFERENCES planets(FID)  ON DELETE CASCADE
+        );
+
+        INSERT OR IGNORE INTO waypoint_planets_v17 (waypoint_id, planet_fid, role, distance)
+        SELECT waypoint_id, planet_fid, role, distance FROM waypoint_planets;
+
+        DROP TABLE waypoint_planets;
+        ALTER TABLE waypoint_planets_v17 RENAME TO waypoint_planets;
+
+        CREATE INDEX IF NOT EXISTS idx_wp_planets_planet
+          ON waypoint_planets(planet_fid);
+
+        CREATE INDEX IF NOT EXISTS idx_wp_planets_waypoint
+          ON waypoint_planets(waypoint_id);
+
+        CREATE INDEX IF NOT EXISTS idx_wp_planets_role
+          ON waypoint_planets(role);
+        "#,
+    )
+    .context("Failed to migrate schema to v17 (waypoint_planets role key)")?;
+
+    Ok(())
+}
+
+fn m_to_v18(tx: &Transaction<'_>) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_planets_canon   ON planets(Canon);
+        CREATE INDEX IF NOT EXISTS idx_planets_legends ON planets(Legends);
+        "#,
+    )
+    .context("Failed to migrate schema to v18 (canon/legends indexes)")?;
+
+    Ok(())
+}
+
+fn m_to_v19(tx: &Transaction<'_>) -> Result<()> {
+    // Wall-clock time of the last computation, in milliseconds.
+    if !column_exists(tx, "routes", "compute_ms")? {
+        tx.execute_batch(
+            r#"
+            ALTER TABLE routes
+            ADD COLUMN compute_ms INTEGER;
+            "#,
+        )
+        .context("Failed to add routes.compute_ms")?;
+    }
+
+    Ok(())
+}
+
 fn convert_table_coordinates_to_ly(tx: &Transaction<'_>, table_name: &str) -> rusqlite::Result<()> {
     let key_column = match table_name {
         "planets" => "FID",
@@ -601,7 +780,20 @@ fn convert_table_coordinates_to_ly(tx: &Transaction<'_>, table_name: &str) -> ru
 
 /// Run schema migrations up to SCHEMA_VERSION.
 /// Idempotent and safe to call on every startup/open.
-pub fn run(con: &mut Connection, dry_run: bool, _emit_noop: bool) -> Result<MigrationReport> {
+pub fn run(con: &mut Connection, dry_run: bool, emit_noop: bool) -> Result<MigrationReport> {
+    run_to(con, dry_run, emit_noop, None)
+}
+
+/// Like [`run`], but stops once the schema reaches `target` (default: latest).
+///
+/// Targets above the latest version or below the current one are rejected.
+/// Only the steps up to `target` are applied and committed.
+pub fn run_to(
+    con: &mut Connection,
+    dry_run: bool,
+    _emit_noop: bool,
+    target: Option<i64>,
+) -> Result<MigrationReport> {
     con.query_row("SELECT 1 FROM meta LIMIT 1", [], |r| r.get::<_, i32>(0))
         .context("Database schema is missing required table: meta")?;
 
@@ -613,10 +805,28 @@ pub fn run(con: &mut Connection, dry_run: bool, _emit_noop: bool) -> Result<Migr
         .max()
         .unwrap_or(START_SCHEMA_VERSION);
 
-    if current >= LATEST_SCHEMA_VERSION {
+    if let Some(t) = target {
+        if t > latest {
+            anyhow::bail!(
+                "Target schema v{} is above the latest supported version v{}",
+                t,
+                latest
+            );
+        }
+        if t < current.max(START_SCHEMA_VERSION) {
+            anyhow::bail!(
+                "Target schema v{} is below the current version v{} (downgrades are not supported)",
+                t,
+                current.max(START_SCHEMA_VERSION)
+            );
+        }
+    }
+    let target = target.unwrap_or(latest);
+
+    if current >= target {
         return Ok(MigrationReport {
             current_version: current,
-            target_version: latest,
+            target_version: target,
             dry_run,
             noop: true,
             applied: Vec::new(),
@@ -630,7 +840,7 @@ pub fn run(con: &mut Connection, dry_run: bool, _emit_noop: bool) -> Result<Migr
     let mut cur = current.max(START_SCHEMA_VERSION);
     let mut applied = Vec::new();
 
-    while cur < latest {
+    while cur < target {
         let next = cur + 1;
 
         let Some(step) = steps.iter().find(|s| s.from == cur && s.to == next) else {
@@ -665,9 +875,105 @@ pub fn run(con: &mut Connection, dry_run: bool, _emit_noop: bool) -> Result<Migr
 
     Ok(MigrationReport {
         current_version: current,
-        target_version: latest,
+        target_version: target,
         dry_run,
         noop: false,
         applied,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta_only(version: &str) -> Connection {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        con.execute_batch("CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);")
+            .expect("meta table");
+        con.execute(
+            "INSERT INTO meta (key, value) VALUES ('schema_version', ?1)",
+            [version],
+        )
+        .expect("schema_version");
+        con
+    }
+
+    #[test]
+    fn newer_schema_is_left_alone_but_warned_about() {
+        let mut con = meta_only("999");
+
+        let report = run(&mut con, false, false).expect("migrate");
+        assert!(report.noop);
+        assert!(report.applied.is_empty());
+        assert!(report.newer_than_binary());
+        let msg = report.newer_schema_warning().expect("warning");
+        assert!(msg.contains("v999"), "{msg}");
+        assert!(msg.contains(&format!("v{LATEST_SCHEMA_VERSION}")), "{msg}");
+
+        assert_eq!(meta_get_i64(&con, "schema_version").unwrap(), Some(999));
+    }
+
+    #[test]
+    fn run_to_stops_at_target_and_commits_only_that_subset() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', '12')",
+            [],
+        )
+        .expect("schema_version");
+
+        let err = run_to(&mut con, false, false, Some(LATEST_SCHEMA_VERSION + 1)).unwrap_err();
+        assert!(err.to_string().contains("above the latest"), "{err}");
+        let err = run_to(&mut con, false, false, Some(11)).unwrap_err();
+        assert!(err.to_string().contains("below the current"), "{err}");
+
+        let dry = run_to(&mut con, true, false, Some(14)).expect("dry run");
+        let steps: Vec<_> = dry.applied.iter().map(|s| (s.from, s.to)).collect();
+        assert_eq!(steps, vec![(12, 13), (13, 14)]);
+        assert_eq!(meta_get_i64(&con, "schema_version").unwrap(), Some(12));
+
+        let report = run_to(&mut con, false, false, Some(14)).expect("partial migration");
+        assert_eq!(report.target_version, 14);
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(meta_get_i64(&con, "schema_version").unwrap(), Some(14));
+        assert!(!crate::db::has_table(&con, "update_log").unwrap());
+
+        let rest = run(&mut con, false, false).expect("finish migration");
+        assert_eq!(rest.applied.first().map(|s| s.from), Some(14));
+        assert_eq!(
+            meta_get_i64(&con, "schema_version").unwrap(),
+            Some(LATEST_SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn canon_legends_indexes_step_is_idempotent() {
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+
+        let tx = con.transaction().expect("tx");
+        m_to_v18(&tx).expect("first run");
+        m_to_v18(&tx).expect("second run");
+        tx.commit().expect("commit");
+
+        let n: i64 = con
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master
+                 WHERE type = 'index' AND name IN ('idx_planets_canon', 'idx_planets_legends')",
+                [],
+                |r| r.get(0),
+            )
+            .expect("index count");
+        assert_eq!(n, 2);
+    }
+
+    #[test]
+    fn current_schema_has_no_warning() {
+        let mut con = meta_only(&LATEST_SCHEMA_VERSION.to_string());
+
+        let report = run(&mut con, false, false).expect("migrate");
+        assert!(report.noop);
+        assert!(report.newer_schema_warning().is_none());
+    }
+}
@@ -1,8 +1,11 @@
 pub mod core;
+pub mod db_import_csv;
 pub mod db_init;
 pub mod db_skipped_planets;
 pub mod db_status;
 pub mod db_update;
+pub mod db_verify;
+pub mod local_planets;
 pub mod migrate;
 pub mod paths;
 pub mod provision;
@@ -2,6 +2,17 @@ use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use std::path::{Path, PathBuf};
 
+/// Environment variable overriding the default database path when `--db` is not given.
+pub const DB_PATH_ENV: &str = "SW_GALAXY_DB";
+
+/// Database path from `SW_GALAXY_DB` (if set and non-empty), otherwise the OS default.
+pub fn env_or_default_db_path() -> Result<PathBuf> {
+    match std::env::var_os(DB_PATH_ENV) {
+        Some(p) if !p.is_empty() => Ok(PathBuf::from(p)),
+        _ => default_db_path(),
+    }
+}
+
 pub fn default_db_path() -> Result<PathBuf> {
     let proj = ProjectDirs::from("", "", "sw_galaxy_map")
         .context("Unable to determine OS app data directory")?;
@@ -12,6 +23,22 @@ pub fn default_db_path() -> Result<PathBuf> {
     Ok(dir.join("sw_planets.sqlite"))
 }
 
+/// Environment variable overriding the TUI command history file.
+pub const HISTORY_PATH_ENV: &str = "SW_GALAXY_HISTORY";
+
+/// TUI command history file from `SW_GALAXY_HISTORY` (if set and non-empty),
+/// otherwise `tui_history` in the OS config directory.
+pub fn history_path() -> Result<PathBuf> {
+    match std::env::var_os(HISTORY_PATH_ENV) {
+        Some(p) if !p.is_empty() => Ok(PathBuf::from(p)),
+        _ => {
+            let proj = ProjectDirs::from("", "", "sw_galaxy_map")
+                .context("Unable to determine OS config directory")?;
+            Ok(proj.config_dir().join("tui_history"))
+        }
+    }
+}
+
 pub fn ensure_parent_dir(path: &Path) -> Result<()> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)
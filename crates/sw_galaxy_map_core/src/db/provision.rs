@@ -1,12 +1,15 @@
+use crate::domain::DedupePolicy;
 use crate::utils::normalize::normalize_text;
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, Transaction, params};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 
 pub struct BuildMeta {
     pub imported_at_utc: String,
     pub source_service_item_id: String,
+    pub source_service_url: String,
     pub dataset_version: String,
     pub importer_version: String,
 }
@@ -167,7 +170,8 @@ pub fn create_schema(con: &Connection, enable_fts: bool) -> Result<()> {
         CREATE UNIQUE INDEX IF NOT EXISTS idx_waypoints_name_norm ON waypoints(name_norm);
         CREATE INDEX IF NOT EXISTS idx_waypoints_xy ON waypoints(x, y);
         CREATE UNIQUE INDEX IF NOT EXISTS idx_waypoints_fingerprint
-            ON waypoints(fingerprint);
+            ON waypoints(fingerprint)
+            WHERE fingerprint IS NOT NULL AND fingerprint <> '';
 
         CREATE TRIGGER IF NOT EXISTS trg_waypoints_updated_at
         AFTER UPDATE ON waypoints
@@ -184,7 +188,7 @@ pub fn create_schema(con: &Connection, enable_fts: bool) -> Result<()> {
         CREATE TABLE waypoint_planets (
             waypoint_id  INTEGER NOT NULL,
             planet_fid   INTEGER NOT NULL,
-            role         TEXT NOT NULL DEFAULT 'via',
+            role         TEXT NOT NULL DEFAULT 'anchor', -- anchor/avoid/near/cluster_member
             distance     REAL,
             PRIMARY KEY (waypoint_id, planet_fid, role),
             FOREIGN KEY (waypoint_id) REFERENCES waypoints(id) ON DELETE CASCADE,
@@ -210,6 +214,7 @@ pub fn create_schema(con: &Connection, enable_fts: bool) -> Result<()> {
           options_json    TEXT NOT NULL,
           length          REAL,
           iterations      INTEGER,
+          compute_ms      INTEGER,
           status          TEXT NOT NULL DEFAULT 'ok' CHECK(status IN ('ok','failed')),
           error           TEXT,
           created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
@@ -417,32 +422,35 @@ fn build_search_row(tx: &Transaction<'_>, fid: i64) -> Result<Option<SearchRow>>
     }))
 }
 
-fn compute_arcgis_hash(a: &Value) -> String {
-    let keys = [
-        "FID",
-        "Planet",
-        "Region",
-        "Sector",
-        "System",
-        "Grid",
-        "X",
-        "Y",
-        "Canon",
-        "Legends",
-        "zm",
-        "name0",
-        "name1",
-        "name2",
-        "lat",
-        "long",
-        "ref",
-        "status",
-        "CRegion",
-        "CRegion_li",
-    ];
+/// ArcGIS attributes hashed into `planets.arcgis_hash`, in canonical order.
+///
+/// Also the column order of `db export --table planets`.
+pub const ARCGIS_HASH_FIELDS: [&str; 20] = [
+    "FID",
+    "Planet",
+    "Region",
+    "Sector",
+    "System",
+    "Grid",
+    "X",
+    "Y",
+    "Canon",
+    "Legends",
+    "zm",
+    "name0",
+    "name1",
+    "name2",
+    "lat",
+    "long",
+    "ref",
+    "status",
+    "CRegion",
+    "CRegion_li",
+];
 
+fn compute_arcgis_hash(a: &Value) -> String {
     let mut s = String::new();
-    for k in keys {
+    for k in ARCGIS_HASH_FIELDS {
         s.push_str(k);
         s.push('=');
 
@@ -513,22 +521,39 @@ fn meta_upsert(con: &Connection, key: &str, value: &str) -> Result<()> {
     Ok(())
 }
 
+/// Inserts the downloaded features and returns how many duplicate-FID rows were dropped.
+///
+/// Without a `dedupe` policy a repeated FID aborts the import.
 pub fn insert_all(
     con: &mut Connection,
     meta: BuildMeta,
     rows: &[Value],
     enable_fts: bool,
-) -> Result<()> {
+    dedupe: Option<DedupePolicy>,
+) -> Result<usize> {
     let tx = con.transaction()?;
 
     meta_upsert(&tx, "imported_at_utc", &meta.imported_at_utc)?;
     meta_upsert(&tx, "source_serviceItemId", &meta.source_service_item_id)?;
+    meta_upsert(&tx, "source_serviceUrl", &meta.source_service_url)?;
     meta_upsert(&tx, "dataset_version", &meta.dataset_version)?;
     meta_upsert(&tx, "importer_version", &meta.importer_version)?;
     meta_upsert(&tx, "fts_enabled", if enable_fts { "1" } else { "0" })?;
     meta_upsert(&tx, "schema_version", "12")?;
 
     let mut skipped_rows: Vec<SkippedUnknownRow> = Vec::new();
+    let mut seen_fids: HashSet<i64> = HashSet::new();
+    let mut dropped_duplicates = 0usize;
+
+    // For "last", the winning row is the final occurrence of each FID.
+    let last_index: HashMap<i64, usize> = match dedupe {
+        Some(DedupePolicy::Last) => rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| a.get("FID").and_then(|v| v.as_i64()).map(|fid| (fid, i)))
+            .collect(),
+        _ => HashMap::new(),
+    };
 
     {
         let mut stmt = tx.prepare(
@@ -556,7 +581,7 @@ pub fn insert_all(
             "#,
         )?;
 
-        for a in rows {
+        for (i, a) in rows.iter().enumerate() {
             let fid = a.get("FID").and_then(|v| v.as_i64());
             let planet = a
                 .get("Planet")
@@ -602,6 +627,30 @@ pub fn insert_all(
             }
 
             let fid = fid.expect("validated fid");
+
+            match dedupe {
+                None => {
+                    if !seen_fids.insert(fid) {
+                        anyhow::bail!(
+                            "Duplicate FID {} in source data; rerun with --dedupe first|last",
+                            fid
+                        );
+                    }
+                }
+                Some(DedupePolicy::First) => {
+                    if !seen_fids.insert(fid) {
+                        dropped_duplicates += 1;
+                        continue;
+                    }
+                }
+                Some(DedupePolicy::Last) => {
+                    if last_index.get(&fid) != Some(&i) {
+                        dropped_duplicates += 1;
+                        continue;
+                    }
+                }
+            }
+
             let x = x.expect("validated x");
             let y = y.expect("validated y");
             let planet_norm = normalize_text(&planet);
@@ -672,8 +721,9 @@ pub fn insert_all(
         rebuild_planets_fts(&tx)?;
     }
 
+    crate::provision::cancel::check()?;
     tx.commit()?;
-    Ok(())
+    Ok(dropped_duplicates)
 }
 
 pub fn has_fts5(con: &Connection) -> bool {
@@ -713,6 +763,16 @@ pub(crate) fn rebuild_planets_fts_if_enabled(tx: &Transaction<'_>) -> Result<()>
         )
         .optional()?;
     if matches!(enabled.as_deref(), Some("1")) {
+        // Recreate a dropped index, so `db rebuild-search` also repairs that state.
+        tx.execute_batch(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS planets_fts USING fts5(
+                planet_fid UNINDEXED,
+                search_norm,
+                tokenize = 'unicode61'
+            );
+            "#,
+        )?;
         rebuild_planets_fts(tx)?;
     }
     Ok(())
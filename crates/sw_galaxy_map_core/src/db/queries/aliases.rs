@@ -2,19 +2,25 @@ use crate::model::AliasRow;
 use anyhow::Result;
 use rusqlite::{Connection, params};
 
-/// Returns all aliases for the given planet FID ordered by source and alias.
-pub fn get_aliases(con: &Connection, fid: i64) -> Result<Vec<AliasRow>> {
+/// Returns the aliases for the given planet FID ordered by source and alias.
+///
+/// `limit = None` returns every alias; pair with [`count_aliases`] to report truncation.
+pub fn get_aliases(con: &Connection, fid: i64, limit: Option<usize>) -> Result<Vec<AliasRow>> {
     let mut stmt = con.prepare(
         r#"
         SELECT alias, source
         FROM planet_aliases
         WHERE planet_fid = ?1
         ORDER BY source, alias
+        LIMIT ?2
         "#,
     )?;
 
+    // SQLite treats a negative LIMIT as "no limit".
+    let limit = limit.map_or(-1, |n| n as i64);
+
     let rows = stmt
-        .query_map(params![fid], |r| {
+        .query_map(params![fid, limit], |r| {
             Ok(AliasRow {
                 alias: r.get(0)?,
                 source: r.get(1)?,
@@ -24,3 +30,13 @@ pub fn get_aliases(con: &Connection, fid: i64) -> Result<Vec<AliasRow>> {
 
     Ok(rows)
 }
+
+/// Returns how many aliases the given planet FID has.
+pub fn count_aliases(con: &Connection, fid: i64) -> Result<usize> {
+    let n: i64 = con.query_row(
+        "SELECT COUNT(*) FROM planet_aliases WHERE planet_fid = ?1",
+        params![fid],
+        |r| r.get(0),
+    )?;
+    Ok(n.max(0) as usize)
+}
@@ -1,10 +1,12 @@
 pub mod aliases;
 pub mod near;
 pub mod planets;
+pub mod route_transfer;
 pub mod routes;
 mod search;
 pub mod stats;
 pub mod unknown;
+pub mod update_log;
 pub mod waypoints;
 
 mod row_mappers;
@@ -12,18 +14,29 @@ mod row_mappers;
 pub use aliases::*;
 pub use near::*;
 pub use planets::*;
+pub use route_transfer::*;
 pub use routes::*;
 pub use search::*;
 pub use stats::*;
 pub use unknown::*;
+pub use update_log::*;
 pub use waypoints::*;
 
 #[cfg(test)]
 mod tests {
     use super::{
-        UnknownPlanetUpdate, near_planets, near_planets_excluding_fid, search_planets,
-        update_unknown_planet,
+        RouteImportOutcome, UnknownPlanetUpdate, WaypointUpdate, count_near_planets,
+        count_near_planets_excluding, export_route, find_planet_by_fid, find_planet_for_info,
+        fts_expected_but_missing, fts_prefix_query, get_route_by_from_to, import_route,
+        like_escape, link_waypoint_to_planet, list_all_waypoint_links, list_all_waypoints,
+        list_planets_in_system, list_routing_obstacles_in_bbox, load_route, near_planets,
+        near_planets_excluding, near_planets_excluding_fid, near_waypoints,
+        nearest_planets_excluding_fid, persist_route, persist_route_failure, rename_waypoint,
+        search_planets, search_planets_filtered, search_planets_prefix,
+        unlink_waypoint_from_planet, update_unknown_planet, update_waypoint, validate_route_export,
     };
+    use crate::domain::NearSort;
+    use crate::model::{NearFilter, RouteExport, RouteRow, RouteWaypointRow, SearchFilter};
     use rusqlite::Connection;
 
     fn setup_search_db() -> Connection {
@@ -39,16 +52,17 @@ mod tests {
                 Grid TEXT,
                 X REAL NOT NULL,
                 Y REAL NOT NULL,
-                deleted INTEGER NOT NULL DEFAULT 0
+                deleted INTEGER NOT NULL DEFAULT 0,
+                status TEXT
             );
             CREATE TABLE planet_search (
                 planet_fid INTEGER NOT NULL,
                 search_norm TEXT NOT NULL
             );
-            INSERT INTO planets (FID, Planet, Region, Sector, System, Grid, X, Y, deleted) VALUES
-                (1, 'Alderaan', 'Core Worlds', 'Alderaan', 'Alderaan', 'L-4', 10.0, 10.0, 0),
-                (2, 'Tatooine', 'Outer Rim', 'Arkanis', 'Tatoo', 'R-16', 20.0, 25.0, 0),
-                (3, 'Deleted', 'Unknown', NULL, NULL, NULL, 50.0, 50.0, 1);
+            INSERT INTO planets (FID, Planet, Region, Sector, System, Grid, X, Y, status) VALUES
+                (1, 'Alderaan', 'Core Worlds', 'Alderaan', 'Alderaan', 'L-4', 10.0, 10.0, 'active'),
+                (2, 'Tatooine', 'Outer Rim', 'Arkanis', 'Tatoo', 'R-16', 20.0, 25.0, 'active'),
+                (3, 'Deleted', 'Unknown', NULL, NULL, NULL, 50.0, 50.0, 'deleted');
             INSERT INTO planet_search (planet_fid, search_norm) VALUES
                 (1, 'alderaan house organa'),
                 (2, 'tatooine luke skywalker'),
@@ -111,34 +125,651 @@ mod tests {
         let con = setup_search_db();
 
         assert!(
-            search_planets(&con, "", 10)
+            search_planets(&con, "", 10, 0)
                 .expect("empty query")
                 .is_empty()
         );
         assert!(
-            search_planets(&con, "   ", 10)
+            search_planets(&con, "   ", 10, 0)
                 .expect("blank query")
                 .is_empty()
         );
         assert!(
-            search_planets(&con, "alderaan", 0)
+            search_planets(&con, "alderaan", 0, 0)
                 .expect("zero limit")
                 .is_empty()
         );
     }
 
+    fn setup_fts_db() -> Connection {
+        let con = setup_search_db();
+        con.execute_batch(
+            r#"
+            ALTER TABLE planets ADD COLUMN planet_norm TEXT;
+            ALTER TABLE planets ADD COLUMN Canon INTEGER;
+            ALTER TABLE planets ADD COLUMN Legends INTEGER;
+            UPDATE planets SET planet_norm = lower(Planet);
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, status) VALUES
+                (4, 'Corellia', 'corellia', 30.0, 30.0, 'active');
+            CREATE TABLE planet_aliases (
+                planet_fid INTEGER NOT NULL,
+                alias_norm TEXT NOT NULL
+            );
+            CREATE VIRTUAL TABLE planets_fts USING fts5(
+                planet_fid UNINDEXED,
+                search_norm,
+                tokenize = 'unicode61'
+            );
+            INSERT INTO planets_fts (planet_fid, search_norm) VALUES
+                (1, 'alderaan house organa'),
+                (2, 'tatooine luke skywalker'),
+                (4, 'corellia corellian system');
+            "#,
+        )
+        .expect("fts setup");
+        con
+    }
+
+    #[test]
+    fn prefix_search_matches_partial_tokens_with_fts() {
+        let con = setup_fts_db();
+
+        assert!(
+            search_planets(&con, "corell", 10, 0)
+                .expect("plain fts")
+                .is_empty()
+        );
+
+        let rows = search_planets_prefix(&con, "corell", 10, 0).expect("prefix fts");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, "Corellia");
+        assert!(rows[0].score.is_some(), "FTS hits carry a bm25 score");
+
+        let filter = SearchFilter {
+            query: Some("corell".to_string()),
+            prefix: true,
+            limit: 10,
+            ..Default::default()
+        };
+        let rows = search_planets_filtered(&con, &filter).expect("filtered prefix");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, "Corellia");
+        assert!(rows[0].score.is_some());
+
+        let whole_word = SearchFilter {
+            prefix: false,
+            ..filter.clone()
+        };
+        assert!(
+            search_planets_filtered(&con, &whole_word)
+                .expect("filtered fts")
+                .is_empty(),
+            "non-prefix text queries also go through FTS"
+        );
+        let rows = search_planets_filtered(
+            &con,
+            &SearchFilter {
+                query: Some("corellia".to_string()),
+                ..whole_word.clone()
+            },
+        )
+        .expect("filtered fts word");
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].score.is_some());
+
+        con.execute_batch("DROP TABLE planets_fts")
+            .expect("drop fts");
+        let rows = search_planets_filtered(&con, &whole_word).expect("filtered like");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].score, None);
+        let hits = search_planets(&con, "corell", 10, 0).expect("plain like");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].score, None);
+    }
+
+    #[test]
+    fn like_filters_match_wildcards_literally() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, Region, X, Y, arcgis_hash, ref) VALUES
+                (1, 'Alpha', 'alpha', 'Rim_Core', 0.0, 0.0, 'h1', 'TCW_3'),
+                (2, 'Beta', 'beta', 'RimxCore', 1.0, 1.0, 'h2', 'TCWx3');
+            "#,
+        )
+        .expect("planets");
+
+        assert_eq!(like_escape(r"a\%_b"), r"a\\\%\_b");
+
+        let fids = |filter: SearchFilter| -> Vec<i64> {
+            search_planets_filtered(
+                &con,
+                &SearchFilter {
+                    limit: 10,
+                    ..filter
+                },
+            )
+            .expect("search")
+            .into_iter()
+            .map(|r| r.fid)
+            .collect()
+        };
+        let by_ref = SearchFilter {
+            reference: Some("tcw_3".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(fids(by_ref), vec![1]);
+        let by_region = SearchFilter {
+            region: Some("_".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(fids(by_region), vec![1]);
+        assert!(search_planets(&con, "%", 10, 0).expect("like").is_empty());
+        assert!(
+            search_planets_prefix(&con, "_", 10, 0)
+                .expect("like")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn prefix_search_escapes_fts_syntax() {
+        let con = setup_fts_db();
+
+        assert_eq!(fts_prefix_query("corell sys"), r#""corell"* "sys"*"#);
+        assert_eq!(fts_prefix_query(r#"co"rell"#), r#""co""rell"*"#);
+
+        for q in [r#"co"rell"#, "\"", "corell AND", "a:b (c"] {
+            assert!(
+                search_planets_prefix(&con, q, 10, 0).is_ok(),
+                "query {:?} should not break FTS",
+                q
+            );
+        }
+    }
+
+    #[test]
+    fn search_pages_are_stable_and_disjoint() {
+        let con = setup_fts_db();
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, status) VALUES
+                (11, 'Kessel', 'kessel', 1.0, 1.0, 'active'),
+                (12, 'Kessel Moon', 'kessel moon', 2.0, 2.0, 'active'),
+                (13, 'Kessel Station', 'kessel station', 3.0, 3.0, 'active'),
+                (14, 'Kessel Run', 'kessel run', 4.0, 4.0, 'active');
+            INSERT INTO planets_fts (planet_fid, search_norm) VALUES
+                (11, 'kessel spice'),
+                (12, 'kessel spice'),
+                (13, 'kessel spice'),
+                (14, 'kessel spice');
+            "#,
+        )
+        .expect("extra planets");
+
+        let fids = |rows: Vec<crate::model::SearchHit>| -> Vec<i64> {
+            rows.into_iter().map(|r| r.fid).collect()
+        };
+        let row_fids = |rows: Vec<crate::model::PlanetSearchRow>| -> Vec<i64> {
+            rows.into_iter().map(|r| r.fid).collect()
+        };
+
+        // Identical bm25 scores: the FID tie-breaker keeps pages consistent.
+        let all = fids(search_planets(&con, "kessel", 10, 0).expect("all"));
+        assert_eq!(all.len(), 4);
+        let mut paged = fids(search_planets(&con, "kessel", 2, 0).expect("page 1"));
+        paged.extend(fids(search_planets(&con, "kessel", 2, 2).expect("page 2")));
+        assert_eq!(paged, all);
+        assert_eq!(
+            fids(search_planets(&con, "kessel", 10, 0).expect("repeat")),
+            all
+        );
+
+        let filter = SearchFilter {
+            query: Some("kess".to_string()),
+            prefix: true,
+            limit: 3,
+            ..Default::default()
+        };
+        let first = row_fids(search_planets_filtered(&con, &filter).expect("filtered page 1"));
+        let second = row_fids(
+            search_planets_filtered(
+                &con,
+                &SearchFilter {
+                    offset: 3,
+                    ..filter.clone()
+                },
+            )
+            .expect("filtered page 2"),
+        );
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 1);
+        assert!(second.iter().all(|f| !first.contains(f)));
+    }
+
+    #[test]
+    fn fid_lookup_is_separate_from_the_name_lookup() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (7, 'Dagobah', 'dagobah', 1.0, 1.0, 'h7'),
+                (42, '7', '7', 2.0, 2.0, 'h42');
+            "#,
+        )
+        .expect("planets");
+
+        assert!(
+            find_planet_for_info(&con, "42")
+                .expect("name lookup")
+                .is_none()
+        );
+        let by_fid = find_planet_by_fid(&con, " 42 ").expect("fid lookup");
+        assert_eq!(by_fid.map(|p| p.planet), Some("7".to_string()));
+
+        let by_name = find_planet_for_info(&con, "7").expect("numeric name");
+        assert_eq!(by_name.map(|p| p.fid), Some(42));
+
+        assert!(
+            find_planet_by_fid(&con, "9999")
+                .expect("unknown fid")
+                .is_none()
+        );
+        assert!(
+            find_planet_by_fid(&con, "dagobah")
+                .expect("not a fid")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn update_waypoint_writes_only_given_fields_and_guards_names() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO waypoints (id, name, name_norm, x, y, kind, fingerprint, note) VALUES
+                (1, 'Junction', 'junction', 1.0, 2.0, 'manual', 'fp1', 'keep me'),
+                (2, 'Buoy', 'buoy', 5.0, 5.0, 'nav_buoy', 'fp2', NULL);
+            "#,
+        )
+        .expect("waypoints");
+
+        let moved = update_waypoint(
+            &con,
+            1,
+            &WaypointUpdate {
+                x: Some(-3.5),
+                kind: Some("junction".into()),
+                ..Default::default()
+            },
+        )
+        .expect("update");
+        assert_eq!((moved.x, moved.y), (-3.5, 2.0));
+        assert_eq!(moved.kind, "junction");
+        assert_eq!(moved.name, "Junction");
+        assert_eq!(moved.note.as_deref(), Some("keep me"));
+        assert!(moved.updated_at.is_some(), "trigger sets updated_at");
+
+        let renamed = update_waypoint(
+            &con,
+            1,
+            &WaypointUpdate {
+                name: Some("Kessel Junction".into()),
+                ..Default::default()
+            },
+        )
+        .expect("rename");
+        assert_eq!(renamed.name_norm, "kessel junction");
+
+        let clash = WaypointUpdate {
+            name: Some("BUOY".into()),
+            ..Default::default()
+        };
+        let err = update_waypoint(&con, 1, &clash).expect_err("name collision");
+        assert!(err.to_string().contains("already in use"));
+        assert!(
+            update_waypoint(&con, 2, &clash).is_ok(),
+            "renaming to itself"
+        );
+        assert!(update_waypoint(&con, 99, &WaypointUpdate::default()).is_err());
+    }
+
+    #[test]
+    fn list_all_waypoints_returns_every_row_with_links() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Coruscant', 'coruscant', 0.0, 0.0, 'h1');
+            INSERT INTO waypoints (id, name, name_norm, x, y, kind, fingerprint) VALUES
+                (2, 'Detour', 'detour', 3.0, 4.0, 'computed', 'abc'),
+                (1, 'Junction', 'junction', 1.0, 2.0, 'manual', '');
+            INSERT INTO waypoint_planets (waypoint_id, planet_fid, role, distance) VALUES
+                (1, 1, 'anchor', 2.5);
+            "#,
+        )
+        .expect("fixture");
+
+        let all = list_all_waypoints(&con).expect("list all");
+        assert_eq!(all.iter().map(|w| w.id).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(all[1].kind, "computed");
+        assert_eq!(all[1].fingerprint.as_deref(), Some("abc"));
+
+        let links = list_all_waypoint_links(&con).expect("links");
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].waypoint_id, links[0].planet_fid), (1, 1));
+        assert_eq!(links[0].role, "anchor");
+    }
+
+    #[test]
+    fn rename_waypoint_keeps_links_and_rejects_taken_names() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Coruscant', 'coruscant', 0.0, 0.0, 'h1');
+            INSERT INTO waypoints (id, name, name_norm, x, y, fingerprint) VALUES
+                (1, 'Junction', 'junction', 1.0, 2.0, 'fp1'),
+                (2, 'Buoy', 'buoy', 5.0, 5.0, 'fp2');
+            INSERT INTO waypoint_planets (waypoint_id, planet_fid, role, distance) VALUES
+                (1, 1, 'anchor', 2.5);
+            "#,
+        )
+        .expect("fixture");
+
+        let w = rename_waypoint(&con, 1, "Kessel Junction").expect("rename");
+        assert_eq!(w.name, "Kessel Junction");
+        assert_eq!(w.name_norm, "kessel junction");
+        assert_eq!((w.x, w.y), (1.0, 2.0));
+
+        let links: Vec<(i64, i64, String, Option<f64>)> = con
+            .prepare("SELECT waypoint_id, planet_fid, role, distance FROM waypoint_planets")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(links, [(1, 1, "anchor".to_string(), Some(2.5))]);
+
+        let err = rename_waypoint(&con, 1, "buoy").unwrap_err().to_string();
+        assert_eq!(err, "Waypoint name already in use: 'Buoy' (id=2)");
+        assert!(rename_waypoint(&con, 1, "   ").is_err());
+    }
+
+    #[test]
+    fn waypoint_links_keep_one_row_per_role() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Coruscant', 'coruscant', 0.0, 0.0, 'h1');
+            INSERT INTO waypoints (id, name, name_norm, x, y, fingerprint) VALUES
+                (1, 'Junction', 'junction', 1.0, 2.0, 'fp1');
+            "#,
+        )
+        .expect("fixture");
+
+        link_waypoint_to_planet(&con, 1, 1, "anchor", None).expect("anchor");
+        link_waypoint_to_planet(&con, 1, 1, "near", Some(2.0)).expect("near");
+        link_waypoint_to_planet(&con, 1, 1, "near", Some(3.0)).expect("relink near");
+
+        let roles = |con: &Connection| -> Vec<(String, Option<f64>)> {
+            con.prepare("SELECT role, distance FROM waypoint_planets ORDER BY role")
+                .unwrap()
+                .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        assert_eq!(
+            roles(&con),
+            [
+                ("anchor".to_string(), None),
+                ("near".to_string(), Some(3.0))
+            ]
+        );
+
+        assert_eq!(
+            unlink_waypoint_from_planet(&con, 1, 1, Some("near")).unwrap(),
+            1
+        );
+        assert_eq!(roles(&con), [("anchor".to_string(), None)]);
+
+        link_waypoint_to_planet(&con, 1, 1, "near", None).expect("near again");
+        assert_eq!(unlink_waypoint_from_planet(&con, 1, 1, None).unwrap(), 2);
+        assert!(roles(&con).is_empty());
+
+        // Same column default as the v17 migration.
+        con.execute(
+            "INSERT INTO waypoint_planets (waypoint_id, planet_fid) VALUES (1, 1)",
+            [],
+        )
+        .expect("link without role");
+        assert_eq!(roles(&con), [("anchor".to_string(), None)]);
+    }
+
+    #[test]
+    fn route_exceeding_max_iters_is_recorded_as_failed() {
+        use crate::routing::collision::Obstacle;
+        use crate::routing::geometry::Point;
+        use crate::routing::router::{RouteOptions, compute_route};
+
+        let mut con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (1, 'Start', 'start', 0.0, 0.0, 'h1'),
+                (2, 'End', 'end', 100.0, 0.0, 'h2'),
+                (3, 'Block', 'block', 50.0, 0.0, 'h3');
+            "#,
+        )
+        .expect("planets");
+
+        let (start, end) = (Point::new(0.0, 0.0), Point::new(100.0, 0.0));
+        let obstacles = [Obstacle {
+            id: 3,
+            name: "Block".into(),
+            center: Point::new(50.0, 0.0),
+            radius: 5.0,
+            radius_y: None,
+        }];
+        let opts = RouteOptions {
+            max_iters: 0,
+            ..Default::default()
+        };
+
+        let err = compute_route(start, end, &obstacles, opts).expect_err("max_iters exceeded");
+        let id = persist_route_failure(&mut con, 1, 2, opts, &Default::default(), &err.to_string())
+            .expect("failure");
+
+        let row = get_route_by_from_to(&con, 1, 2)
+            .unwrap()
+            .expect("route row");
+        assert_eq!(row.id, id);
+        assert_eq!(row.status, "failed");
+        assert!(row.error.as_deref().unwrap_or("").contains("max_iters=0"));
+        assert_eq!((row.length, row.iterations), (None, None));
+
+        // A later successful computation replaces the failure in place.
+        let opts = RouteOptions::default();
+        let route = compute_route(start, end, &obstacles, opts).expect("route");
+        assert_eq!(
+            persist_route(
+                &mut con,
+                1,
+                2,
+                opts,
+                &Default::default(),
+                &route,
+                None,
+                Some(42)
+            )
+            .unwrap(),
+            id
+        );
+        let row = get_route_by_from_to(&con, 1, 2)
+            .unwrap()
+            .expect("route row");
+        assert_eq!(row.status, "ok");
+        assert_eq!(row.error, None);
+        assert_eq!(row.compute_ms, Some(42));
+
+        // A failure after that keeps the good polyline, options and stats.
+        let stored = load_route(&con, id).unwrap().expect("loaded");
+        for _ in 0..2 {
+            let failed_opts = RouteOptions {
+                max_iters: 0,
+                ..Default::default()
+            };
+            assert_eq!(
+                persist_route_failure(&mut con, 1, 2, failed_opts, &Default::default(), "boom")
+                    .expect("failure"),
+                id
+            );
+        }
+        let after = load_route(&con, id).unwrap().expect("loaded");
+        assert_eq!(after.route.status, "failed");
+        assert_eq!(after.route.error.as_deref(), Some("boom"));
+        assert_eq!(after.route.options_json, stored.route.options_json);
+        assert_eq!(after.route.length, stored.route.length);
+        assert_eq!(after.route.compute_ms, Some(42));
+        assert_eq!(after.waypoints.len(), stored.waypoints.len());
+        assert_eq!(after.detours.len(), stored.detours.len());
+    }
+
+    #[test]
+    fn routing_obstacles_break_name_ties_by_fid() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                (9, 'Twin', 'twin', 1.0, 1.0, 'h9'),
+                (4, 'Twin', 'twin', 2.0, 2.0, 'h4'),
+                (7, 'Twin', 'twin', 3.0, 3.0, 'h7');
+            "#,
+        )
+        .expect("planets");
+
+        let fids = |limit| -> Vec<i64> {
+            list_routing_obstacles_in_bbox(&con, 0.0, 10.0, 0.0, 10.0, limit, 1.0)
+                .expect("obstacles")
+                .into_iter()
+                .map(|o| o.fid)
+                .collect()
+        };
+        assert_eq!(fids(10), vec![4, 7, 9]);
+        assert_eq!(fids(2), vec![4, 7]);
+    }
+
+    #[test]
+    fn near_waypoints_filters_by_radius_and_sorts_by_distance() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO waypoints (id, name, name_norm, x, y, fingerprint) VALUES
+                (1, 'Far', 'far', 30.0, 0.0, 'fp1'),
+                (2, 'Close', 'close', 1.0, 1.0, 'fp2'),
+                (3, 'Mid', 'mid', -4.0, 3.0, 'fp3');
+            "#,
+        )
+        .expect("waypoints");
+
+        let hits = near_waypoints(&con, 0.0, 0.0, 10.0, 10).expect("near waypoints");
+        let got: Vec<(i64, f64)> = hits.iter().map(|(w, d)| (w.id, *d)).collect();
+        assert_eq!(got.iter().map(|h| h.0).collect::<Vec<_>>(), [2, 3]);
+        assert!((got[1].1 - 5.0).abs() < 1e-9);
+
+        assert_eq!(
+            near_waypoints(&con, 0.0, 0.0, 10.0, 1)
+                .expect("limit")
+                .len(),
+            1
+        );
+        assert!(near_waypoints(&con, 0.0, 0.0, -1.0, 10).is_err());
+    }
+
+    #[test]
+    fn siblings_use_system_then_fall_back_to_sector() {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        crate::db::provision::create_schema(&con, false).expect("schema");
+        con.execute_batch(
+            r#"
+            INSERT INTO planets (FID, Planet, planet_norm, System, Sector, X, Y, arcgis_hash, status) VALUES
+                (1, 'Hoth', 'hoth', 'Hoth system', 'Anoat sector', 0.0, 0.0, 'h1', 'active'),
+                (2, 'Hoth II', 'hoth ii', 'Hoth system', 'Anoat sector', 0.0, 0.0, 'h2', 'active'),
+                (3, 'Anoat', 'anoat', NULL, 'Anoat sector', 0.0, 0.0, 'h3', 'active'),
+                (4, 'Bespin', 'bespin', 'Bespin system', 'Anoat sector', 0.0, 0.0, 'h4', 'active'),
+                (5, 'Gone', 'gone', 'Hoth system', 'Anoat sector', 0.0, 0.0, 'h5', 'deleted');
+            "#,
+        )
+        .expect("planets");
+
+        let names = |rows: Vec<(i64, String)>| -> Vec<String> {
+            rows.into_iter().map(|(_, n)| n).collect()
+        };
+
+        let in_system =
+            list_planets_in_system(&con, Some("Hoth system"), Some("Anoat sector"), 1, 10)
+                .expect("system siblings");
+        assert_eq!(names(in_system), ["Hoth II"]);
+
+        let in_sector = list_planets_in_system(&con, None, Some("Anoat sector"), 3, 10)
+            .expect("sector siblings");
+        assert_eq!(names(in_sector), ["Bespin", "Hoth", "Hoth II"]);
+
+        assert!(
+            list_planets_in_system(&con, Some("  "), None, 3, 10)
+                .expect("no system or sector")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fts_fallback_is_detected_from_meta() {
+        let con = setup_search_db();
+        assert!(!fts_expected_but_missing(&con).expect("no meta table"));
+
+        con.execute_batch(
+            r#"
+            CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            INSERT INTO meta (key, value) VALUES ('fts_enabled', '1');
+            CREATE VIRTUAL TABLE planets_fts USING fts5(planet_fid UNINDEXED, search_norm);
+            "#,
+        )
+        .expect("meta");
+        assert!(!fts_expected_but_missing(&con).expect("fts present"));
+
+        con.execute_batch("DROP TABLE planets_fts;")
+            .expect("drop fts");
+        assert!(fts_expected_but_missing(&con).expect("fts missing"));
+
+        con.execute("UPDATE meta SET value = '0' WHERE key = 'fts_enabled'", [])
+            .expect("disable");
+        assert!(!fts_expected_but_missing(&con).expect("fts disabled"));
+    }
+
     #[test]
     fn near_planets_validates_inputs_and_filters_results() {
         let con = setup_search_db();
 
-        let rows = near_planets(&con, 9.0, 9.0, 2.0, 10).expect("near query");
+        let rows =
+            near_planets(&con, 9.0, 9.0, 2.0, &NearFilter::default(), 10).expect("near query");
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].planet, "Alderaan");
 
-        assert!(near_planets(&con, 0.0, 0.0, -1.0, 10).is_err());
-        assert!(near_planets(&con, f64::NAN, 0.0, 1.0, 10).is_err());
+        assert!(near_planets(&con, 0.0, 0.0, -1.0, &NearFilter::default(), 10).is_err());
+        assert!(near_planets(&con, f64::NAN, 0.0, 1.0, &NearFilter::default(), 10).is_err());
         assert!(
-            near_planets(&con, 0.0, 0.0, 1.0, 0)
+            near_planets(&con, 0.0, 0.0, 1.0, &NearFilter::default(), 0)
                 .expect("zero limit")
                 .is_empty()
         );
@@ -149,11 +780,437 @@ mod tests {
         let con = setup_search_db();
 
         let rows =
-            near_planets_excluding_fid(&con, 1, 10.0, 10.0, 30.0, 10).expect("excluding fid");
+            near_planets_excluding_fid(&con, 1, 10.0, 10.0, 30.0, &NearFilter::default(), 10)
+                .expect("excluding fid");
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].planet, "Tatooine");
     }
 
+    #[test]
+    fn nearest_planets_have_no_radius() {
+        let con = setup_search_db();
+        con.execute(
+            "INSERT INTO planets (FID, Planet, X, Y, status) VALUES (4, 'Far', 1.0e7, 0.0, NULL)",
+            [],
+        )
+        .expect("far planet");
+
+        let rows = nearest_planets_excluding_fid(&con, 1, 10.0, 10.0, 10).expect("nearest");
+        let names = rows.iter().map(|h| h.planet.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["Tatooine", "Far"]);
+
+        let rows = nearest_planets_excluding_fid(&con, 1, 10.0, 10.0, 1).expect("limited");
+        assert_eq!(rows.len(), 1);
+        assert!(
+            nearest_planets_excluding_fid(&con, 1, 10.0, 10.0, 0)
+                .expect("zero limit")
+                .is_empty()
+        );
+        assert!(nearest_planets_excluding_fid(&con, 1, f64::NAN, 0.0, 1).is_err());
+    }
+
+    #[test]
+    fn near_planets_excluding_skips_every_listed_fid() {
+        let con = setup_search_db();
+
+        let rows =
+            near_planets_excluding(&con, &[1, 3], 10.0, 10.0, 100.0, &NearFilter::default(), 10)
+                .expect("excluding");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].planet, "Tatooine");
+
+        assert_eq!(
+            count_near_planets_excluding(
+                &con,
+                &[1, 2, 3],
+                10.0,
+                10.0,
+                100.0,
+                &NearFilter::default()
+            )
+            .expect("count"),
+            0
+        );
+        assert_eq!(
+            near_planets_excluding(&con, &[], 10.0, 10.0, 100.0, &NearFilter::default(), 10)
+                .expect("empty exclusion")
+                .len(),
+            2,
+            "soft-deleted FID 3 is never a hit"
+        );
+    }
+
+    #[test]
+    fn near_planets_skip_soft_deleted_rows() {
+        let con = setup_search_db();
+
+        let fids = |con: &Connection| -> Vec<i64> {
+            near_planets(con, 10.0, 10.0, 100.0, &NearFilter::default(), 10)
+                .expect("near query")
+                .into_iter()
+                .map(|h| h.fid)
+                .collect()
+        };
+        assert_eq!(fids(&con), [1, 2]);
+
+        // Same marking `db update` applies to planets dropped from the feed.
+        con.execute("UPDATE planets SET status = 'deleted' WHERE FID = 2", [])
+            .expect("soft delete");
+        assert_eq!(fids(&con), [1]);
+        assert_eq!(
+            count_near_planets(&con, None, 10.0, 10.0, 100.0, &NearFilter::default())
+                .expect("count"),
+            1
+        );
+    }
+
+    #[test]
+    fn near_planets_min_r_skips_the_inner_disc() {
+        let con = setup_search_db();
+
+        let annulus = NearFilter {
+            min_r: Some(2.0),
+            ..Default::default()
+        };
+
+        // Alderaan is ~1.41 pc from (9, 9), Tatooine ~19.4 pc.
+        let rows = near_planets(&con, 9.0, 9.0, 30.0, &annulus, 10).expect("annulus");
+        let names: Vec<&str> = rows.iter().map(|r| r.planet.as_str()).collect();
+        assert_eq!(names, ["Tatooine"]);
+        assert_eq!(
+            count_near_planets(&con, None, 9.0, 9.0, 30.0, &annulus).expect("count"),
+            1
+        );
+
+        assert_eq!(
+            near_planets(&con, 9.0, 9.0, 30.0, &NearFilter::default(), 10)
+                .expect("no lower bound")
+                .len(),
+            2
+        );
+        assert!(
+            near_planets(
+                &con,
+                9.0,
+                9.0,
+                30.0,
+                &NearFilter {
+                    min_r: Some(30.0),
+                    ..Default::default()
+                },
+                10
+            )
+            .is_err()
+        );
+        assert!(
+            near_planets(
+                &con,
+                9.0,
+                9.0,
+                30.0,
+                &NearFilter {
+                    min_r: Some(-1.0),
+                    ..Default::default()
+                },
+                10
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn near_planets_sort_changes_order_not_distances() {
+        let con = setup_search_db();
+        con.execute("UPDATE planets SET status = 'active' WHERE FID = 3", [])
+            .expect("restore");
+
+        let by = |sort| -> Vec<(i64, f64)> {
+            near_planets(
+                &con,
+                20.0,
+                25.0,
+                100.0,
+                &NearFilter {
+                    sort,
+                    ..Default::default()
+                },
+                10,
+            )
+            .expect("near query")
+            .into_iter()
+            .map(|h| (h.fid, h.distance))
+            .collect()
+        };
+
+        let by_distance = by(NearSort::Distance);
+        assert_eq!(
+            by_distance.iter().map(|h| h.0).collect::<Vec<_>>(),
+            [2, 1, 3]
+        );
+        let by_name = by(NearSort::Name);
+        assert_eq!(by_name.iter().map(|h| h.0).collect::<Vec<_>>(), [1, 3, 2]);
+        let by_fid = by(NearSort::Fid);
+        assert_eq!(by_fid.iter().map(|h| h.0).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let mut sorted = by_name.clone();
+        sorted.sort_by_key(|h| h.0);
+        assert_eq!(sorted, by_fid);
+    }
+
+    #[test]
+    fn near_planets_continuity_flags_filter_hits() {
+        let con = setup_fts_db();
+        con.execute_batch(
+            r#"
+            UPDATE planets SET Canon = 1, Legends = 1 WHERE FID = 1;
+            UPDATE planets SET Canon = 0, Legends = 1 WHERE FID = 2;
+            UPDATE planets SET Canon = 1, Legends = 0 WHERE FID = 4;
+            "#,
+        )
+        .expect("continuity flags");
+
+        let fids = |canon, legends| -> Vec<i64> {
+            let filter = NearFilter {
+                canon,
+                legends,
+                sort: NearSort::Fid,
+                ..Default::default()
+            };
+            near_planets(&con, 20.0, 20.0, 100.0, &filter, 10)
+                .expect("near query")
+                .into_iter()
+                .map(|h| h.fid)
+                .collect()
+        };
+
+        assert_eq!(
+            fids(true, false),
+            [1, 4],
+            "legends-only Tatooine is excluded"
+        );
+        assert_eq!(fids(false, true), [1, 2]);
+        assert_eq!(fids(true, true), [1]);
+    }
+
+    #[test]
+    fn count_near_planets_ignores_limit_and_honors_exclusion() {
+        let con = setup_search_db();
+
+        assert_eq!(
+            count_near_planets(&con, None, 10.0, 10.0, 30.0, &NearFilter::default())
+                .expect("count"),
+            2
+        );
+        assert_eq!(
+            count_near_planets(&con, Some(1), 10.0, 10.0, 30.0, &NearFilter::default())
+                .expect("count excluding"),
+            1
+        );
+        assert!(count_near_planets(&con, None, 0.0, 0.0, -1.0, &NearFilter::default()).is_err());
+    }
+
+    fn sample_route_export() -> RouteExport {
+        let wp = |seq: i64, x: f64| RouteWaypointRow {
+            seq,
+            x,
+            y: 0.0,
+            waypoint_id: None,
+            waypoint_name: None,
+            waypoint_kind: None,
+        };
+        RouteExport {
+            format: super::ROUTE_EXPORT_FORMAT.to_string(),
+            format_version: super::ROUTE_EXPORT_VERSION,
+            exported_at: "2024-01-01T00:00:00Z".to_string(),
+            route: RouteRow {
+                id: 1,
+                from_planet_fid: 1,
+                to_planet_fid: 2,
+                from_planet_name: "Alderaan".to_string(),
+                to_planet_name: "Tatooine".to_string(),
+                algo_version: "router_v1".to_string(),
+                options_json: "{}".to_string(),
+                length: Some(10.0),
+                iterations: Some(1),
+                compute_ms: None,
+                status: "ok".to_string(),
+                error: None,
+                created_at: "2024-01-01T00:00:00Z".to_string(),
+                updated_at: None,
+            },
+            options: None,
+            waypoints: vec![wp(0, 0.0), wp(1, 10.0)],
+            detours: Vec::new(),
+            obstacles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn exported_route_imports_with_detours_and_waypoint_links() {
+        use crate::routing::collision::Obstacle;
+        use crate::routing::geometry::Point;
+        use crate::routing::router::{RouteOptions, compute_route};
+
+        let planets = |con: &Connection, with_block: bool| {
+            crate::db::provision::create_schema(con, false).expect("schema");
+            con.execute_batch(
+                r#"
+                INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash) VALUES
+                    (1, 'Start', 'start', 0.0, 0.0, 'h1'),
+                    (2, 'End', 'end', 100.0, 0.0, 'h2');
+                "#,
+            )
+            .expect("planets");
+            if with_block {
+                con.execute(
+                    "INSERT INTO planets (FID, Planet, planet_norm, X, Y, arcgis_hash)
+                     VALUES (3, 'Block', 'block', 50.0, 0.0, 'h3')",
+                    [],
+                )
+                .expect("obstacle planet");
+            }
+        };
+
+        let mut src = Connection::open_in_memory().expect("in-memory sqlite");
+        planets(&src, true);
+        let obstacles = [Obstacle {
+            id: 3,
+            name: "Block".into(),
+            center: Point::new(50.0, 0.0),
+            radius: 5.0,
+            radius_y: None,
+        }];
+        let opts = RouteOptions::default();
+        let route = compute_route(
+            Point::new(0.0, 0.0),
+            Point::new(100.0, 0.0),
+            &obstacles,
+            opts,
+        )
+        .expect("route");
+        assert!(!route.detours.is_empty(), "fixture should force a detour");
+        let src_id = persist_route(
+            &mut src,
+            1,
+            2,
+            opts,
+            &Default::default(),
+            &route,
+            None,
+            None,
+        )
+        .expect("persist");
+
+        let export = export_route(&src, src_id).unwrap().expect("export");
+        let json = serde_json::to_string(&export).expect("serialize");
+        let export: RouteExport = serde_json::from_str(&json).expect("deserialize");
+
+        let mut dst = Connection::open_in_memory().expect("in-memory sqlite");
+        planets(&dst, true);
+        // Occupy the first waypoint id so imported ids cannot match the source by accident.
+        dst.execute(
+            "INSERT INTO waypoints (name, name_norm, x, y) VALUES ('Gate', 'gate', 9.0, 9.0)",
+            [],
+        )
+        .expect("waypoint");
+
+        let imported_id = |outcome: RouteImportOutcome, want_replaced: bool| match outcome {
+            RouteImportOutcome::Imported {
+                route_id,
+                replaced,
+                missing_obstacles,
+            } => {
+                assert_eq!(replaced, want_replaced);
+                assert!(missing_obstacles.is_empty());
+                route_id
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        };
+        let id = imported_id(import_route(&mut dst, &export).expect("import"), false);
+        // Importing again replaces the route instead of duplicating its rows.
+        assert_eq!(
+            imported_id(import_route(&mut dst, &export).expect("reimport"), true),
+            id
+        );
+
+        let original = load_route(&src, src_id).unwrap().expect("source route");
+        let copy = load_route(&dst, id).unwrap().expect("imported route");
+
+        let seqs: Vec<i64> = copy.waypoints.iter().map(|w| w.seq).collect();
+        assert_eq!(
+            seqs,
+            (0..original.waypoints.len() as i64).collect::<Vec<_>>()
+        );
+        let xy = |l: &crate::model::RouteLoaded| -> Vec<(f64, f64)> {
+            l.waypoints.iter().map(|w| (w.x, w.y)).collect()
+        };
+        assert_eq!(xy(&copy), xy(&original));
+
+        assert_eq!(copy.detours.len(), original.detours.len());
+        for (a, b) in copy.detours.iter().zip(&original.detours) {
+            assert_eq!((a.idx, a.obstacle_id), (b.idx, b.obstacle_id));
+            assert_eq!((a.wp_x, a.wp_y), (b.wp_x, b.wp_y));
+        }
+
+        // Route waypoints point at the detour waypoints recreated in this database.
+        let detour_wps: Vec<Option<i64>> = copy.detours.iter().map(|d| d.waypoint_id).collect();
+        assert!(detour_wps.iter().all(|w| w.is_some_and(|id| id > 1)));
+        let linked: Vec<i64> = copy
+            .waypoints
+            .iter()
+            .filter_map(|w| w.waypoint_id)
+            .collect();
+        assert_eq!(
+            linked.len(),
+            original
+                .waypoints
+                .iter()
+                .filter(|w| w.waypoint_id.is_some())
+                .count()
+        );
+        assert!(linked.iter().all(|id| detour_wps.contains(&Some(*id))));
+        let avoid_links: i64 = dst
+            .query_row(
+                "SELECT COUNT(*) FROM waypoint_planets WHERE planet_fid = 3 AND role = 'avoid'",
+                [],
+                |r| r.get(0),
+            )
+            .expect("links");
+        assert!(avoid_links >= 1);
+
+        // Without the obstacle planet the route still imports, minus the planet links.
+        let mut bare = Connection::open_in_memory().expect("in-memory sqlite");
+        planets(&bare, false);
+        match import_route(&mut bare, &export).expect("import without obstacle") {
+            RouteImportOutcome::Imported {
+                missing_obstacles, ..
+            } => assert_eq!(missing_obstacles, vec![3]),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_route_export_checks_format_and_version() {
+        let ok = sample_route_export();
+        assert!(validate_route_export(&ok).is_ok());
+
+        let json = serde_json::to_string(&ok).expect("serialize");
+        let back: RouteExport = serde_json::from_str(&json).expect("deserialize");
+        assert!(validate_route_export(&back).is_ok());
+
+        let mut future = sample_route_export();
+        future.format_version += 1;
+        assert!(validate_route_export(&future).is_err());
+
+        let mut foreign = sample_route_export();
+        foreign.format = "something-else".to_string();
+        assert!(validate_route_export(&foreign).is_err());
+
+        let mut short = sample_route_export();
+        short.waypoints.truncate(1);
+        assert!(validate_route_export(&short).is_err());
+    }
+
     #[test]
     fn update_unknown_planet_updates_requested_fields_and_planet_norm() {
         let con = setup_unknown_db();
@@ -1,101 +1,253 @@
-use crate::model::NearHit;
+use crate::domain::NearSort;
+use crate::model::{NearFilter, NearHit};
 use anyhow::Result;
-use rusqlite::{Connection, params};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params_from_iter};
 
-/// Returns planets near the given coordinates within the specified radius.
-pub fn near_planets(con: &Connection, x: f64, y: f64, r: f64, limit: i64) -> Result<Vec<NearHit>> {
+fn validate_near_inputs(x: f64, y: f64, r: f64, min_r: Option<f64>) -> Result<()> {
     if !x.is_finite() || !y.is_finite() {
         anyhow::bail!("Center coordinates must be finite numbers");
     }
     if !r.is_finite() || r < 0.0 {
         anyhow::bail!("Radius must be a finite number >= 0");
     }
+    if let Some(m) = min_r
+        && (!m.is_finite() || m < 0.0 || m >= r)
+    {
+        anyhow::bail!("Inner radius must be a finite number >= 0 and below the radius");
+    }
+    Ok(())
+}
+
+/// Squared inner radius; `-1` disables the lower bound (every `d2` is >= 0).
+fn min_r2(min_r: Option<f64>) -> f64 {
+    min_r.map_or(-1.0, |m| m * m)
+}
+
+fn order_clause(sort: NearSort) -> &'static str {
+    match sort {
+        NearSort::Distance => "ORDER BY d2 ASC, FID ASC",
+        NearSort::Name => "ORDER BY Planet COLLATE NOCASE ASC, FID ASC",
+        NearSort::Fid => "ORDER BY FID ASC",
+    }
+}
+
+/// Canon/Legends predicates; both flags set means both must hold.
+fn continuity_clause(filter: &NearFilter) -> String {
+    let mut sql = String::new();
+    if filter.canon {
+        sql.push_str("AND Canon = 1 ");
+    }
+    if filter.legends {
+        sql.push_str("AND Legends = 1 ");
+    }
+    sql
+}
+
+/// `AND FID NOT IN (?n, ...)` with placeholders numbered from `first`.
+fn exclusion_clause(exclude: &[i64], first: usize) -> String {
+    if exclude.is_empty() {
+        return String::new();
+    }
+    let placeholders = (first..first + exclude.len())
+        .map(|i| format!("?{}", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("AND FID NOT IN ({})", placeholders)
+}
+
+/// Returns planets near the given coordinates within the specified radius.
+///
+/// Planets `db update` marked as deleted (or skipped/invalid) are never returned.
+///
+/// `filter` adds an inner radius, continuity flags and the sort order (which
+/// also decides which hits survive `limit`).
+pub fn near_planets(
+    con: &Connection,
+    x: f64,
+    y: f64,
+    r: f64,
+    filter: &NearFilter,
+    limit: i64,
+) -> Result<Vec<NearHit>> {
+    near_planets_excluding(con, &[], x, y, r, filter, limit)
+}
+
+/// Counts all planets within the radius (ignoring any limit), optionally excluding one FID.
+///
+/// Uses the same distance predicate as [`near_planets`] / [`near_planets_excluding_fid`].
+pub fn count_near_planets(
+    con: &Connection,
+    exclude_fid: Option<i64>,
+    x: f64,
+    y: f64,
+    r: f64,
+    filter: &NearFilter,
+) -> Result<i64> {
+    count_near_planets_excluding(con, exclude_fid.as_slice(), x, y, r, filter)
+}
+
+/// Returns planets near the given coordinates, excluding the center planet FID.
+pub fn near_planets_excluding_fid(
+    con: &Connection,
+    center_fid: i64,
+    x: f64,
+    y: f64,
+    r: f64,
+    filter: &NearFilter,
+    limit: i64,
+) -> Result<Vec<NearHit>> {
+    near_planets_excluding(con, &[center_fid], x, y, r, filter, limit)
+}
+
+/// Returns planets near the given coordinates, skipping every FID in `exclude`.
+///
+/// The exclusion list is bound as parameters, never interpolated.
+pub fn near_planets_excluding(
+    con: &Connection,
+    exclude: &[i64],
+    x: f64,
+    y: f64,
+    r: f64,
+    filter: &NearFilter,
+    limit: i64,
+) -> Result<Vec<NearHit>> {
+    validate_near_inputs(x, y, r, filter.min_r)?;
     if limit <= 0 {
         return Ok(Vec::new());
     }
 
     let r2 = r * r;
 
-    let mut stmt = con.prepare(
+    let sql = format!(
         r#"
         SELECT FID, Planet, X, Y,
-               ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) AS d2
+               ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) AS d2,
+               Region, Sector
         FROM planets
-        WHERE ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) <= ?3
-        ORDER BY d2 ASC
+        WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+          AND ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) <= ?3
+          AND ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) >= ?5
+          {continuity}
+          {exclusion}
+        {order}
         LIMIT ?4
         "#,
-    )?;
+        continuity = continuity_clause(filter),
+        exclusion = exclusion_clause(exclude, 6),
+        order = order_clause(filter.sort)
+    );
+
+    let mut params: Vec<Value> = vec![
+        Value::Real(x),
+        Value::Real(y),
+        Value::Real(r2),
+        Value::Integer(limit),
+        Value::Real(min_r2(filter.min_r)),
+    ];
+    params.extend(exclude.iter().map(|fid| Value::Integer(*fid)));
 
+    let mut stmt = con.prepare(&sql)?;
     let rows = stmt
-        .query_map(params![x, y, r2, limit], |r| {
-            let fid: i64 = r.get(0)?;
-            let planet: String = r.get(1)?;
-            let px: f64 = r.get(2)?;
-            let py: f64 = r.get(3)?;
-            let d2: f64 = r.get(4)?;
-            Ok(NearHit {
-                fid,
-                planet,
-                x: px,
-                y: py,
-                distance: d2.sqrt(),
-            })
-        })?
+        .query_map(params_from_iter(params.iter()), near_hit_from_row)?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(rows)
 }
 
-/// Returns planets near the given coordinates, excluding the center planet FID.
-pub fn near_planets_excluding_fid(
+/// Maps a `FID, Planet, X, Y, d2, Region, Sector` row.
+fn near_hit_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<NearHit> {
+    let d2: f64 = r.get(4)?;
+    Ok(NearHit {
+        fid: r.get(0)?,
+        planet: r.get(1)?,
+        region: r.get(5)?,
+        sector: r.get(6)?,
+        x: r.get(2)?,
+        y: r.get(3)?,
+        distance: d2.sqrt(),
+    })
+}
+
+/// Returns the `limit` planets closest to the given coordinates, however far away,
+/// excluding the center planet FID.
+///
+/// Skips the same deleted/skipped/invalid planets as [`near_planets`]; ties are
+/// broken by FID.
+pub fn nearest_planets_excluding_fid(
     con: &Connection,
     center_fid: i64,
     x: f64,
     y: f64,
-    r: f64,
     limit: i64,
 ) -> Result<Vec<NearHit>> {
     if !x.is_finite() || !y.is_finite() {
         anyhow::bail!("Center coordinates must be finite numbers");
     }
-    if !r.is_finite() || r < 0.0 {
-        anyhow::bail!("Radius must be a finite number >= 0");
-    }
     if limit <= 0 {
         return Ok(Vec::new());
     }
 
-    let r2 = r * r;
-
     let mut stmt = con.prepare(
         r#"
         SELECT FID, Planet, X, Y,
-               ((X - ?2)*(X - ?2) + (Y - ?3)*(Y - ?3)) AS d2
+               ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) AS d2,
+               Region, Sector
         FROM planets
-        WHERE FID != ?1
-          AND ((X - ?2)*(X - ?2) + (Y - ?3)*(Y - ?3)) <= ?4
-        ORDER BY d2 ASC
-        LIMIT ?5
+        WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+          AND FID <> ?3
+        ORDER BY d2 ASC, FID ASC
+        LIMIT ?4
         "#,
     )?;
-
     let rows = stmt
-        .query_map(params![center_fid, x, y, r2, limit], |r| {
-            let fid: i64 = r.get(0)?;
-            let planet: String = r.get(1)?;
-            let px: f64 = r.get(2)?;
-            let py: f64 = r.get(3)?;
-            let d2: f64 = r.get(4)?;
-            Ok(NearHit {
-                fid,
-                planet,
-                x: px,
-                y: py,
-                distance: d2.sqrt(),
-            })
-        })?
+        .query_map(
+            rusqlite::params![x, y, center_fid, limit],
+            near_hit_from_row,
+        )?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(rows)
 }
+
+/// Counts all planets within the radius (ignoring any limit), skipping every FID in `exclude`.
+///
+/// `filter` applies the same constraints as [`near_planets_excluding`].
+pub fn count_near_planets_excluding(
+    con: &Connection,
+    exclude: &[i64],
+    x: f64,
+    y: f64,
+    r: f64,
+    filter: &NearFilter,
+) -> Result<i64> {
+    validate_near_inputs(x, y, r, filter.min_r)?;
+
+    let r2 = r * r;
+
+    let sql = format!(
+        r#"
+        SELECT COUNT(*)
+        FROM planets
+        WHERE (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+          AND ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) <= ?3
+          AND ((X - ?1)*(X - ?1) + (Y - ?2)*(Y - ?2)) >= ?4
+          {continuity}
+          {exclusion}
+        "#,
+        continuity = continuity_clause(filter),
+        exclusion = exclusion_clause(exclude, 5)
+    );
+
+    let mut params: Vec<Value> = vec![
+        Value::Real(x),
+        Value::Real(y),
+        Value::Real(r2),
+        Value::Real(min_r2(filter.min_r)),
+    ];
+    params.extend(exclude.iter().map(|fid| Value::Integer(*fid)));
+
+    let n: i64 = con.query_row(&sql, params_from_iter(params.iter()), |r| r.get(0))?;
+
+    Ok(n)
+}
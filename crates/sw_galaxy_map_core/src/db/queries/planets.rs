@@ -160,6 +160,59 @@ pub fn find_planet_for_info(con: &Connection, query_norm: &str) -> Result<Option
     find_planet_by_alias_norm(con, query_norm)
 }
 
+/// Returns the planet whose FID is `query` (an integer, surrounding whitespace
+/// ignored); `None` for a non-numeric query or an unknown FID.
+pub fn find_planet_by_fid(con: &Connection, query: &str) -> Result<Option<Planet>> {
+    match query.trim().parse::<i64>() {
+        Ok(fid) => get_planet_by_fid(con, fid),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Returns `(FID, name)` of other planets in the same system, or in the same
+/// sector when `system` is null/blank. Empty when both are missing.
+pub fn list_planets_in_system(
+    con: &Connection,
+    system: Option<&str>,
+    sector: Option<&str>,
+    exclude_fid: i64,
+    limit: usize,
+) -> Result<Vec<(i64, String)>> {
+    let system = system.map(str::trim).filter(|s| !s.is_empty());
+    let sector = sector.map(str::trim).filter(|s| !s.is_empty());
+
+    let (column, value) = match (system, sector) {
+        (Some(sys), _) => ("System", sys),
+        (None, Some(sec)) => ("Sector", sec),
+        (None, None) => return Ok(Vec::new()),
+    };
+
+    let sql = format!(
+        r#"
+        SELECT FID, Planet
+        FROM planets
+        WHERE {column} = ?1 COLLATE NOCASE
+          AND FID <> ?2
+          AND (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid'))
+        ORDER BY Planet ASC
+        LIMIT ?3
+        "#
+    );
+
+    let mut stmt = con
+        .prepare(&sql)
+        .context("Failed to prepare list_planets_in_system query")?;
+
+    let rows = stmt
+        .query_map(params![value, exclude_fid, limit as i64], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .context("Failed to execute list_planets_in_system query")?;
+
+    let items = rows.collect::<std::result::Result<Vec<_>, rusqlite::Error>>()?;
+    Ok(items)
+}
+
 /// Returns planets inside the given bounding box, limited to the fields
 /// needed by the route command fallback path.
 pub fn list_planets_in_bbox(
@@ -226,7 +279,7 @@ pub fn list_routing_obstacles_in_bbox(
             FROM planets
             WHERE X BETWEEN ?1 AND ?2
               AND Y BETWEEN ?3 AND ?4
-            ORDER BY Planet ASC
+            ORDER BY Planet ASC, FID ASC
             LIMIT ?5
             "#,
         )
@@ -0,0 +1,258 @@
+use super::routes::{delete_route_children, detour_fingerprint_parts};
+use super::{
+    get_planet_by_fid, get_route_by_from_to, link_waypoint_to_planet, load_route,
+    upsert_computed_waypoint, upsert_route_id,
+};
+use crate::model::{RouteDetourRow, RouteExport, RouteExportObstacle, RouteOptionsJson};
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params};
+use std::collections::HashMap;
+
+/// Value of [`RouteExport::format`] for files written by this crate.
+pub const ROUTE_EXPORT_FORMAT: &str = "sw_galaxy_map.route";
+
+/// Current [`RouteExport::format_version`]. Bump on incompatible layout changes.
+pub const ROUTE_EXPORT_VERSION: u32 = 1;
+
+/// Result of [`import_route`].
+#[derive(Debug, Clone)]
+pub enum RouteImportOutcome {
+    /// Route stored under `route_id`; `replaced` when the FROM→TO pair already existed.
+    Imported {
+        route_id: i64,
+        replaced: bool,
+        /// Detoured obstacles whose FID is unknown in this database.
+        missing_obstacles: Vec<i64>,
+    },
+    /// Nothing written: these endpoint FIDs are not in this database.
+    MissingEndpoints { missing: Vec<i64> },
+}
+
+/// Builds the export document for a persisted route (`None` if the id is unknown).
+pub fn export_route(con: &Connection, route_id: i64) -> Result<Option<RouteExport>> {
+    let Some(loaded) = load_route(con, route_id)? else {
+        return Ok(None);
+    };
+
+    let options = serde_json::from_str::<RouteOptionsJson>(&loaded.route.options_json).ok();
+
+    let mut obstacles: Vec<RouteExportObstacle> = Vec::new();
+    for d in &loaded.detours {
+        if obstacles.iter().any(|o| o.fid == d.obstacle_id) {
+            continue;
+        }
+        obstacles.push(RouteExportObstacle {
+            fid: d.obstacle_id,
+            name: d.obstacle_name.clone(),
+            x: d.obstacle_x,
+            y: d.obstacle_y,
+            radius: d.obstacle_radius,
+        });
+    }
+
+    Ok(Some(RouteExport {
+        format: ROUTE_EXPORT_FORMAT.to_string(),
+        format_version: ROUTE_EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        route: loaded.route,
+        options,
+        waypoints: loaded.waypoints,
+        detours: loaded.detours,
+        obstacles,
+    }))
+}
+
+/// Rejects documents from another tool or an unsupported format version.
+pub fn validate_route_export(export: &RouteExport) -> Result<()> {
+    if export.format != ROUTE_EXPORT_FORMAT {
+        anyhow::bail!(
+            "Not a route export: format is '{}' (expected '{}')",
+            export.format,
+            ROUTE_EXPORT_FORMAT
+        );
+    }
+    if export.format_version != ROUTE_EXPORT_VERSION {
+        anyhow::bail!(
+            "Unsupported route export version {} (this build reads version {})",
+            export.format_version,
+            ROUTE_EXPORT_VERSION
+        );
+    }
+    if export.waypoints.len() < 2 {
+        anyhow::bail!(
+            "Route export has {} waypoint(s); at least start and end are required",
+            export.waypoints.len()
+        );
+    }
+    Ok(())
+}
+
+fn insert_detour_row(
+    con: &Connection,
+    route_id: i64,
+    d: &RouteDetourRow,
+    waypoint_id: Option<i64>,
+) -> Result<()> {
+    con.execute(
+        r#"
+        INSERT INTO route_detours(
+          route_id, idx,
+          iteration, segment_index,
+          obstacle_id, obstacle_x, obstacle_y, obstacle_radius,
+          closest_t, closest_qx, closest_qy, closest_dist,
+          offset_used,
+          wp_x, wp_y, waypoint_id,
+          score_base, score_turn, score_back, score_proximity, score_total,
+          tries_used, tries_exhausted
+        ) VALUES (
+          ?1, ?2,
+          ?3, ?4,
+          ?5, ?6, ?7, ?8,
+          ?9, ?10, ?11, ?12,
+          ?13,
+          ?14, ?15, ?16,
+          ?17, ?18, ?19, ?20, ?21,
+          ?22, ?23
+        )
+        "#,
+        params![
+            route_id,
+            d.idx,
+            d.iteration,
+            d.segment_index,
+            d.obstacle_id,
+            d.obstacle_x,
+            d.obstacle_y,
+            d.obstacle_radius,
+            d.closest_t,
+            d.closest_qx,
+            d.closest_qy,
+            d.closest_dist,
+            d.offset_used,
+            d.wp_x,
+            d.wp_y,
+            waypoint_id,
+            d.score_base,
+            d.score_turn,
+            d.score_back,
+            d.score_proximity,
+            d.score_total,
+            d.tries_used,
+            d.tries_exhausted,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Stores an exported route as-is (no re-planning), keyed by its endpoint FIDs.
+///
+/// Replaces any route already persisted for the same FROM→TO pair. Detour
+/// waypoints are recreated with the same fingerprint `route compute` uses, so
+/// they merge with waypoints the planner would produce locally.
+pub fn import_route(con: &mut Connection, export: &RouteExport) -> Result<RouteImportOutcome> {
+    validate_route_export(export)?;
+
+    let from_fid = export.route.from_planet_fid;
+    let to_fid = export.route.to_planet_fid;
+
+    let mut missing = Vec::new();
+    for fid in [from_fid, to_fid] {
+        if get_planet_by_fid(con, fid)?.is_none() && !missing.contains(&fid) {
+            missing.push(fid);
+        }
+    }
+    if !missing.is_empty() {
+        return Ok(RouteImportOutcome::MissingEndpoints { missing });
+    }
+
+    let mut missing_obstacles = Vec::new();
+    for o in &export.obstacles {
+        if get_planet_by_fid(con, o.fid)?.is_none() {
+            missing_obstacles.push(o.fid);
+        }
+    }
+
+    let replaced = get_route_by_from_to(con, from_fid, to_fid)?.is_some();
+
+    let options_json = match &export.options {
+        Some(opts) => serde_json::to_string(opts)?,
+        None => export.route.options_json.clone(),
+    };
+
+    let tx = con
+        .transaction()
+        .context("Failed to start route import transaction")?;
+
+    let route_id = upsert_route_id(
+        &tx,
+        from_fid,
+        to_fid,
+        &export.route.algo_version,
+        &options_json,
+        export.route.length.unwrap_or(0.0),
+        export.route.iterations.unwrap_or(0).max(0) as usize,
+        export.route.compute_ms,
+    )?;
+
+    delete_route_children(&tx, route_id)?;
+
+    // Waypoint ids in the file belong to the exporting database.
+    let mut wp_ids: HashMap<i64, i64> = HashMap::new();
+
+    for d in &export.detours {
+        let fp = detour_fingerprint_parts(
+            from_fid,
+            to_fid,
+            d.obstacle_id,
+            d.iteration,
+            d.segment_index,
+            d.wp_x,
+            d.wp_y,
+        );
+
+        let wp_name = format!("Detour {}", fp.get(0..8).unwrap_or("detour"));
+        let wp_norm = crate::utils::normalize::normalize_text(&wp_name);
+
+        let (wp_id, _created) = upsert_computed_waypoint(
+            &tx,
+            &wp_name,
+            &wp_norm,
+            d.wp_x,
+            d.wp_y,
+            "computed",
+            Some("Computed detour waypoint"),
+            &fp,
+        )?;
+
+        if !missing_obstacles.contains(&d.obstacle_id) {
+            let dist_to_ob = (d.wp_x - d.obstacle_x).hypot(d.wp_y - d.obstacle_y);
+            let _ = link_waypoint_to_planet(&tx, wp_id, d.obstacle_id, "avoid", Some(dist_to_ob));
+        }
+
+        insert_detour_row(&tx, route_id, d, Some(wp_id))?;
+
+        if let Some(old) = d.waypoint_id {
+            wp_ids.insert(old, wp_id);
+        }
+    }
+
+    for w in &export.waypoints {
+        let waypoint_id = w.waypoint_id.and_then(|old| wp_ids.get(&old).copied());
+        tx.execute(
+            r#"
+            INSERT INTO route_waypoints(route_id, seq, x, y, waypoint_id)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![route_id, w.seq, w.x, w.y, waypoint_id],
+        )?;
+    }
+
+    tx.commit()
+        .context("Failed to commit route import transaction")?;
+
+    Ok(RouteImportOutcome::Imported {
+        route_id,
+        replaced,
+        missing_obstacles,
+    })
+}
@@ -1,7 +1,12 @@
 use super::row_mappers::{route_detour_from_row, route_from_row, route_waypoint_from_row};
 use super::{link_waypoint_to_planet, upsert_computed_waypoint};
-use crate::model::{RouteDetourRow, RouteListRow, RouteLoaded, RouteRow, RouteWaypointRow};
-use crate::routing::router::{DetourDecision, Route as ComputedRoute, RouteOptions};
+use crate::model::{
+    RouteDetourRow, RouteLegOptions, RouteListRow, RouteLoaded, RoutePassRow, RouteRow,
+    RouteWaypointRow,
+};
+use crate::routing::router::{
+    DetourDecision, Route as ComputedRoute, RouteOptions, clamp_proximity_exponent,
+};
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, params};
 use sha2::{Digest, Sha256};
@@ -16,6 +21,7 @@ const ROUTE_SELECT: &str = r#"
   r.options_json    AS options_json,
   r.length          AS length,
   r.iterations      AS iterations,
+  r.compute_ms      AS compute_ms,
   r.status          AS status,
   r.error           AS error,
   r.created_at      AS created_at,
@@ -26,16 +32,65 @@ fn round4(v: f64) -> f64 {
     (v * 10_000.0).round() / 10_000.0
 }
 
+fn round_to(v: f64, decimals: u32) -> f64 {
+    let f = 10f64.powi(decimals as i32);
+    (v * f).round() / f
+}
+
+/// Key used to match route points with their detour waypoint.
+/// Full precision keeps the historical 4-decimal key.
+fn point_key(x: f64, y: f64, coord_precision: Option<u32>) -> String {
+    match coord_precision {
+        Some(p) => {
+            let p = p as usize;
+            format!("{:.p$},{:.p$}", x, y)
+        }
+        None => format!("{:.4},{:.4}", round4(x), round4(y)),
+    }
+}
+
+/// Copy of `d` with the stored coordinates rounded to `decimals`.
+fn round_detour(d: &DetourDecision, decimals: u32) -> DetourDecision {
+    let mut d = d.clone();
+    d.waypoint.x = round_to(d.waypoint.x, decimals);
+    d.waypoint.y = round_to(d.waypoint.y, decimals);
+    d.closest_q.x = round_to(d.closest_q.x, decimals);
+    d.closest_q.y = round_to(d.closest_q.y, decimals);
+    d
+}
+
 fn detour_fingerprint(from_fid: i64, to_fid: i64, d: &DetourDecision) -> String {
+    detour_fingerprint_parts(
+        from_fid,
+        to_fid,
+        d.obstacle_id,
+        d.iteration as i64,
+        d.segment_index as i64,
+        d.waypoint.x,
+        d.waypoint.y,
+    )
+}
+
+/// Fingerprint of a detour waypoint, shared with route import so both paths
+/// reuse the same computed waypoint.
+pub(super) fn detour_fingerprint_parts(
+    from_fid: i64,
+    to_fid: i64,
+    obstacle_id: i64,
+    iteration: i64,
+    segment_index: i64,
+    x: f64,
+    y: f64,
+) -> String {
     let s = format!(
         "detour|from={}|to={}|ob={}|it={}|seg={}|x={:.4}|y={:.4}",
         from_fid,
         to_fid,
-        d.obstacle_id,
-        d.iteration,
-        d.segment_index,
-        round4(d.waypoint.x),
-        round4(d.waypoint.y)
+        obstacle_id,
+        iteration,
+        segment_index,
+        round4(x),
+        round4(y)
     );
 
     let mut h = Sha256::new();
@@ -122,27 +177,50 @@ pub fn insert_route_detour(
     Ok(())
 }
 
+fn route_options_json(opts: &RouteOptions, leg: &RouteLegOptions) -> Result<String> {
+    Ok(serde_json::to_string(&serde_json::json!({
+        "clearance": opts.clearance,
+        "max_iters": opts.max_iters,
+        "max_waypoints": opts.max_waypoints,
+        "max_offset_tries": opts.max_offset_tries,
+        "offset_growth": opts.offset_growth,
+        "turn_weight": opts.turn_weight,
+        "back_weight": opts.back_weight,
+        "proximity_weight": opts.proximity_weight,
+        "proximity_margin": opts.proximity_margin,
+        "proximity_exponent": clamp_proximity_exponent(opts.proximity_exponent),
+        "explore_both_sides": opts.explore_both_sides,
+        "metric": opts.metric,
+        "vias": leg.vias,
+        "avoid": leg.avoid,
+        "snap_radius": leg.snap_radius,
+        "min_spacing": leg.min_spacing,
+        "safety": leg.safety,
+        "bbox_margin": leg.bbox_margin,
+        "max_obstacles": leg.max_obstacles,
+    }))?)
+}
+
+/// Stores `route` and its detours, replacing any previous children.
+///
+/// `coord_precision` rounds the stored waypoint/detour coordinates to that many
+/// decimals (`None` keeps full `f64` precision).
+#[allow(clippy::too_many_arguments)]
 pub fn persist_route(
     con: &mut Connection,
     from_planet_fid: i64,
     to_planet_fid: i64,
     opts: RouteOptions,
+    leg: &RouteLegOptions,
     route: &ComputedRoute,
+    coord_precision: Option<u32>,
+    compute_ms: Option<i64>,
 ) -> Result<i64> {
     let tx = con
         .transaction()
         .context("Failed to start route persistence transaction")?;
 
-    let options_json = serde_json::to_string(&serde_json::json!({
-        "clearance": opts.clearance,
-        "max_iters": opts.max_iters,
-        "max_offset_tries": opts.max_offset_tries,
-        "offset_growth": opts.offset_growth,
-        "turn_weight": opts.turn_weight,
-        "back_weight": opts.back_weight,
-        "proximity_weight": opts.proximity_weight,
-        "proximity_margin": opts.proximity_margin,
-    }))?;
+    let options_json = route_options_json(&opts, leg)?;
 
     let route_id = upsert_route_id(
         &tx,
@@ -152,6 +230,7 @@ pub fn persist_route(
         &options_json,
         route.length,
         route.iterations,
+        compute_ms,
     )?;
 
     delete_route_children(&tx, route_id)?;
@@ -160,6 +239,8 @@ pub fn persist_route(
     let mut detour_wp_ids: HashMap<String, i64> = HashMap::new();
 
     for (idx, d) in route.detours.iter().enumerate() {
+        let rounded = coord_precision.map(|p| round_detour(d, p));
+        let d = rounded.as_ref().unwrap_or(d);
         let fp = detour_fingerprint(from_planet_fid, to_planet_fid, d);
 
         let wp_name = format!("Detour {}", fp.get(0..8).unwrap_or("detour"));
@@ -181,14 +262,18 @@ pub fn persist_route(
 
         insert_route_detour(&tx, route_id, idx, d, Some(wp_id))?;
 
-        let key = format!("{:.4},{:.4}", round4(d.waypoint.x), round4(d.waypoint.y));
+        let key = point_key(d.waypoint.x, d.waypoint.y, coord_precision);
         detour_wp_ids.insert(key, wp_id);
     }
 
     for (seq, p) in route.waypoints.iter().enumerate() {
-        let key = format!("{:.4},{:.4}", round4(p.x), round4(p.y));
+        let (x, y) = match coord_precision {
+            Some(dp) => (round_to(p.x, dp), round_to(p.y, dp)),
+            None => (p.x, p.y),
+        };
+        let key = point_key(x, y, coord_precision);
         let waypoint_id = detour_wp_ids.get(&key).copied();
-        insert_route_waypoint(&tx, route_id, seq, p.x, p.y, waypoint_id)?;
+        insert_route_waypoint(&tx, route_id, seq, x, y, waypoint_id)?;
     }
 
     tx.commit()
@@ -196,6 +281,7 @@ pub fn persist_route(
     Ok(route_id)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn upsert_route_id(
     con: &Connection,
     from_planet_fid: i64,
@@ -204,16 +290,17 @@ pub fn upsert_route_id(
     options_json: &str,
     length: f64,
     iterations: usize,
+    compute_ms: Option<i64>,
 ) -> Result<i64> {
     con.execute(
         r#"
         INSERT INTO routes(
           from_planet_fid, to_planet_fid, algo_version, options_json,
-          length, iterations, status, error, created_at, updated_at
+          length, iterations, compute_ms, status, error, created_at, updated_at
         )
         VALUES (
           ?1, ?2, ?3, ?4,
-          ?5, ?6, 'ok', NULL,
+          ?5, ?6, ?7, 'ok', NULL,
           strftime('%Y-%m-%dT%H:%M:%fZ','now'),
           strftime('%Y-%m-%dT%H:%M:%fZ','now')
         )
@@ -222,6 +309,7 @@ pub fn upsert_route_id(
           options_json = excluded.options_json,
           length       = excluded.length,
           iterations   = excluded.iterations,
+          compute_ms   = excluded.compute_ms,
           status       = 'ok',
           error        = NULL,
           updated_at   = excluded.updated_at
@@ -232,7 +320,8 @@ pub fn upsert_route_id(
             algo_version,
             options_json,
             length,
-            iterations as i64
+            iterations as i64,
+            compute_ms
         ],
     )?;
 
@@ -249,7 +338,94 @@ pub fn upsert_route_id(
     Ok(id)
 }
 
-fn delete_route_children(con: &Connection, route_id: i64) -> Result<()> {
+/// Records a failed computation for FROM→TO (`status = 'failed'` plus `error`).
+///
+/// When the pair already has a stored polyline (from an earlier successful run), only
+/// the status and error change: its options, polyline, detours and stats are kept so
+/// the last good route stays viewable until a computation succeeds again. Otherwise
+/// `length`, `iterations` and `compute_ms` are left empty. Returns the route id.
+pub fn persist_route_failure(
+    con: &mut Connection,
+    from_planet_fid: i64,
+    to_planet_fid: i64,
+    opts: RouteOptions,
+    leg: &RouteLegOptions,
+    error: &str,
+) -> Result<i64> {
+    let tx = con
+        .transaction()
+        .context("Failed to start route failure transaction")?;
+
+    let with_polyline: Option<i64> = tx
+        .query_row(
+            r#"
+            SELECT r.id
+            FROM routes r
+            WHERE r.from_planet_fid = ?1 AND r.to_planet_fid = ?2
+              AND EXISTS (SELECT 1 FROM route_waypoints w WHERE w.route_id = r.id)
+            "#,
+            params![from_planet_fid, to_planet_fid],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if let Some(route_id) = with_polyline {
+        tx.execute(
+            r#"
+            UPDATE routes
+            SET status = 'failed',
+                error = ?2,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now')
+            WHERE id = ?1
+            "#,
+            params![route_id, error],
+        )?;
+        tx.commit()
+            .context("Failed to commit route failure transaction")?;
+        return Ok(route_id);
+    }
+
+    tx.execute(
+        r#"
+        INSERT INTO routes(
+          from_planet_fid, to_planet_fid, algo_version, options_json,
+          length, iterations, status, error, created_at, updated_at
+        )
+        VALUES (
+          ?1, ?2, 'router_v1', ?3,
+          NULL, NULL, 'failed', ?4,
+          strftime('%Y-%m-%dT%H:%M:%fZ','now'),
+          strftime('%Y-%m-%dT%H:%M:%fZ','now')
+        )
+        ON CONFLICT(from_planet_fid, to_planet_fid) DO UPDATE SET
+          algo_version = excluded.algo_version,
+          options_json = excluded.options_json,
+          length       = NULL,
+          iterations   = NULL,
+          compute_ms   = NULL,
+          status       = 'failed',
+          error        = excluded.error,
+          updated_at   = excluded.updated_at
+        "#,
+        params![
+            from_planet_fid,
+            to_planet_fid,
+            route_options_json(&opts, leg)?,
+            error
+        ],
+    )?;
+
+    let route_id: i64 = tx.query_row(
+        "SELECT id FROM routes WHERE from_planet_fid = ?1 AND to_planet_fid = ?2",
+        params![from_planet_fid, to_planet_fid],
+        |r| r.get(0),
+    )?;
+
+    tx.commit()
+        .context("Failed to commit route failure transaction")?;
+    Ok(route_id)
+}
+
+pub(super) fn delete_route_children(con: &Connection, route_id: i64) -> Result<()> {
     con.execute(
         "DELETE FROM route_waypoints WHERE route_id = ?1",
         [route_id],
@@ -284,6 +460,22 @@ pub fn get_route_by_from_to(
     Ok(row)
 }
 
+/// Look up the persisted route for a planet pair in either direction.
+///
+/// The exact `from`→`to` row wins; otherwise the mirror `to`→`from` row is returned
+/// with `true` as second element, meaning the caller must reverse it before use.
+pub fn get_route_by_pair_symmetric(
+    con: &Connection,
+    from_planet_fid: i64,
+    to_planet_fid: i64,
+) -> Result<Option<(RouteRow, bool)>> {
+    if let Some(r) = get_route_by_from_to(con, from_planet_fid, to_planet_fid)? {
+        return Ok(Some((r, false)));
+    }
+
+    Ok(get_route_by_from_to(con, to_planet_fid, from_planet_fid)?.map(|r| (r, true)))
+}
+
 pub fn load_route(con: &Connection, route_id: i64) -> Result<Option<RouteLoaded>> {
     let sql = format!(
         r#"
@@ -375,6 +567,7 @@ pub fn load_route(con: &Connection, route_id: i64) -> Result<Option<RouteLoaded>
     }))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn list_routes(
     con: &Connection,
     limit: usize,
@@ -382,6 +575,7 @@ pub fn list_routes(
     from: Option<i64>,
     to: Option<i64>,
     wp: Option<usize>,
+    near_obstacle: Option<i64>,
     sort: crate::domain::RouteListSort,
 ) -> Result<(Vec<RouteListRow>, usize)> {
     use rusqlite::types::Value;
@@ -401,6 +595,12 @@ pub fn list_routes(
         where_parts.push("r.to_planet_fid = ?");
         params.push(Value::Integer(fid));
     }
+    if let Some(fid) = near_obstacle {
+        where_parts.push(
+            "EXISTS (SELECT 1 FROM route_detours d WHERE d.route_id = r.id AND d.obstacle_id = ?)",
+        );
+        params.push(Value::Integer(fid));
+    }
 
     let order_sql = match sort {
         crate::domain::RouteListSort::Updated => {
@@ -499,6 +699,7 @@ pub fn list_routes(
               r.status AS status,
               r.length AS length,
               r.iterations AS iterations,
+              r.compute_ms AS compute_ms,
               r.created_at AS created_at,
               r.updated_at AS updated_at,
               COALESCE(wp.cnt, 0) AS waypoints_count,
@@ -527,6 +728,7 @@ pub fn list_routes(
               r.status AS status,
               r.length AS length,
               r.iterations AS iterations,
+              r.compute_ms AS compute_ms,
               r.created_at AS created_at,
               r.updated_at AS updated_at,
               (SELECT COUNT(*) FROM route_waypoints w WHERE w.route_id = r.id) AS waypoints_count,
@@ -555,6 +757,7 @@ pub fn list_routes(
                 status: row.get("status")?,
                 length: row.get("length")?,
                 iterations: row.get("iterations")?,
+                compute_ms: row.get("compute_ms")?,
                 created_at: row.get("created_at")?,
                 updated_at: row.get("updated_at")?,
                 waypoints_count: row.get("waypoints_count")?,
@@ -565,3 +768,88 @@ pub fn list_routes(
 
     Ok((rows, total))
 }
+
+/// Routes whose stored polyline passes within `radius` of (`x`, `y`), closest first.
+///
+/// Routes starting or ending at `exclude_endpoint_fid` are skipped, so a planet's
+/// own routes don't show up as "passing by". A bbox over each route's waypoints
+/// prefilters candidates before the exact segment distance is measured.
+pub fn routes_passing_near(
+    con: &Connection,
+    x: f64,
+    y: f64,
+    radius: f64,
+    exclude_endpoint_fid: Option<i64>,
+) -> Result<Vec<RoutePassRow>> {
+    use crate::routing::collision::closest_point_on_segment;
+    use crate::routing::geometry::{Point, dist};
+
+    if !x.is_finite() || !y.is_finite() {
+        anyhow::bail!("Center coordinates must be finite numbers");
+    }
+    if !radius.is_finite() || radius < 0.0 {
+        anyhow::bail!("Radius must be a finite number >= 0");
+    }
+
+    let mut stmt = con.prepare(
+        r#"
+        SELECT r.id, r.from_planet_fid, fp.Planet, r.to_planet_fid, tp.Planet
+        FROM routes r
+        JOIN planets fp ON fp.FID = r.from_planet_fid
+        JOIN planets tp ON tp.FID = r.to_planet_fid
+        JOIN (
+            SELECT route_id
+            FROM route_waypoints
+            GROUP BY route_id
+            HAVING MIN(x) <= ?1 + ?3 AND MAX(x) >= ?1 - ?3
+               AND MIN(y) <= ?2 + ?3 AND MAX(y) >= ?2 - ?3
+        ) bb ON bb.route_id = r.id
+        WHERE ?4 IS NULL OR (r.from_planet_fid <> ?4 AND r.to_planet_fid <> ?4)
+        "#,
+    )?;
+
+    let candidates = stmt
+        .query_map(params![x, y, radius, exclude_endpoint_fid], |r| {
+            Ok(RoutePassRow {
+                route_id: r.get(0)?,
+                from_planet_fid: r.get(1)?,
+                from_planet_name: r.get(2)?,
+                to_planet_fid: r.get(3)?,
+                to_planet_name: r.get(4)?,
+                distance: f64::INFINITY,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt_wp =
+        con.prepare("SELECT x, y FROM route_waypoints WHERE route_id = ?1 ORDER BY seq ASC")?;
+    let center = Point::new(x, y);
+
+    let mut out = Vec::new();
+    for mut row in candidates {
+        let points = stmt_wp
+            .query_map([row.route_id], |r| Ok(Point::new(r.get(0)?, r.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let closest = if points.len() == 1 {
+            dist(points[0], center)
+        } else {
+            points
+                .windows(2)
+                .map(|w| closest_point_on_segment(center, w[0], w[1]).dist)
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        if closest <= radius {
+            row.distance = closest;
+            out.push(row);
+        }
+    }
+
+    out.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then(a.route_id.cmp(&b.route_id))
+    });
+    Ok(out)
+}
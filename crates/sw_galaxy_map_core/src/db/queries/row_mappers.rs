@@ -75,6 +75,7 @@ pub(super) fn route_from_row(r: &Row<'_>) -> rusqlite::Result<RouteRow> {
         options_json: r.get("options_json")?,
         length: r.get("length")?,
         iterations: r.get("iterations")?,
+        compute_ms: r.get("compute_ms")?,
         status: r.get("status")?,
         error: r.get("error")?,
         created_at: r.get("created_at")?,
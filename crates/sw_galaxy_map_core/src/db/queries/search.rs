@@ -1,5 +1,5 @@
 use crate::db::has_table;
-use crate::model::{PlanetSearchRow, SearchFilter};
+use crate::model::{PlanetSearchRow, SearchFilter, SearchHit};
 use crate::utils::fuzzy::fuzzy_search;
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, params};
@@ -7,12 +7,14 @@ use rusqlite::{Connection, OptionalExtension, params};
 /// Searches planets by normalized free-text query.
 ///
 /// Uses FTS when available, otherwise falls back to a LIKE-based query
-/// that also matches aliases.
+/// that also matches aliases. `offset` skips that many rows of the ordered
+/// result, so consecutive calls page through it.
 pub fn search_planets(
     con: &Connection,
     query_norm: &str,
     limit: i64,
-) -> Result<Vec<PlanetSearchRow>> {
+    offset: i64,
+) -> Result<Vec<SearchHit>> {
     if limit <= 0 {
         return Ok(Vec::new());
     }
@@ -23,63 +25,130 @@ pub fn search_planets(
     }
 
     if has_table(con, "planets_fts")? {
-        return search_planets_fts(con, query_norm, limit);
+        return search_planets_fts(con, query_norm, limit, offset);
     }
 
-    search_planets_like(con, query_norm, limit)
+    search_planets_like(
+        con,
+        &format!("%{}%", like_escape(query_norm)),
+        limit,
+        offset,
+    )
 }
 
-fn search_planets_like(
+/// Like [`search_planets`], but every whitespace-separated token only has to
+/// start a word: "corell" finds "Corellia".
+///
+/// The LIKE fallback anchors the whole query at the start of the name/alias.
+pub fn search_planets_prefix(
     con: &Connection,
     query_norm: &str,
     limit: i64,
-) -> Result<Vec<PlanetSearchRow>> {
-    let like = format!("%{}%", query_norm);
+    offset: i64,
+) -> Result<Vec<SearchHit>> {
+    if limit <= 0 {
+        return Ok(Vec::new());
+    }
+
+    let query_norm = query_norm.trim();
+    if query_norm.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if has_table(con, "planets_fts")? {
+        let expr = fts_prefix_query(query_norm);
+        if expr.is_empty() {
+            return Ok(Vec::new());
+        }
+        return search_planets_fts(con, &expr, limit, offset);
+    }
 
+    search_planets_like(con, &format!("{}%", like_escape(query_norm)), limit, offset)
+}
+
+/// Builds an FTS5 MATCH expression with one `"token"*` prefix term per
+/// whitespace-separated token.
+///
+/// Each token is quoted (inner `"` doubled), so FTS operators and punctuation
+/// in user input are matched literally instead of breaking the query syntax.
+pub fn fts_prefix_query(query_norm: &str) -> String {
+    fts_quoted_query(query_norm, "*")
+}
+
+/// Like [`fts_prefix_query`], but every token must match a whole word.
+pub fn fts_token_query(query_norm: &str) -> String {
+    fts_quoted_query(query_norm, "")
+}
+
+fn fts_quoted_query(query_norm: &str, suffix: &str) -> String {
+    query_norm
+        .split_whitespace()
+        .map(|tok| format!("\"{}\"{}", tok.replace('"', "\"\""), suffix))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Escapes `\`, `%` and `_` so user input matches literally in a
+/// `LIKE ... ESCAPE '\'` pattern.
+pub fn like_escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// True when `meta.fts_enabled=1` but the `planets_fts` table is missing, so
+/// FTS-backed searches silently use the LIKE fallback instead.
+pub fn fts_expected_but_missing(con: &Connection) -> Result<bool> {
+    if !has_table(con, "meta")? {
+        return Ok(false);
+    }
+    let enabled = con
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'fts_enabled'",
+            [],
+            |r| r.get::<_, String>(0),
+        )
+        .optional()
+        .context("Failed to read meta.fts_enabled")?;
+    Ok(enabled.as_deref() == Some("1") && !has_table(con, "planets_fts")?)
+}
+
+fn search_planets_like(
+    con: &Connection,
+    like: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SearchHit>> {
     let mut stmt = con
         .prepare(
             r#"
-            SELECT DISTINCT
-                p.FID,
-                p.Planet,
-                p.Region,
-                p.Sector,
-                p.System,
-                p.Grid,
-                p.X,
-                p.Y,
-                COALESCE(p.Canon, 0),
-                COALESCE(p.Legends, 0),
-                p.status
+            SELECT DISTINCT p.FID, p.Planet
             FROM planets p
             LEFT JOIN planet_aliases pa
                 ON pa.planet_fid = p.FID
             WHERE
                 p.status NOT IN ('deleted', 'skipped', 'invalid')
                 AND (
-                    p.planet_norm LIKE ?1
-                    OR pa.alias_norm LIKE ?1
+                    p.planet_norm LIKE ?1 ESCAPE '\'
+                    OR pa.alias_norm LIKE ?1 ESCAPE '\'
                 )
-            ORDER BY p.planet_norm ASC
-            LIMIT ?2
+            ORDER BY p.planet_norm ASC, p.FID ASC
+            LIMIT ?2 OFFSET ?3
             "#,
         )
         .context("Failed to prepare LIKE search query")?;
 
     let rows = stmt
-        .query_map((like, limit), |r| {
-            Ok(PlanetSearchRow {
+        .query_map((like, limit, offset.max(0)), |r| {
+            Ok(SearchHit {
                 fid: r.get::<_, i64>(0)?,
                 name: r.get::<_, String>(1)?,
-                region: r.get::<_, Option<String>>(2)?,
-                sector: r.get::<_, Option<String>>(3)?,
-                system: r.get::<_, Option<String>>(4)?,
-                grid: r.get::<_, Option<String>>(5)?,
-                x: r.get(6)?,
-                y: r.get(7)?,
-                canon: r.get(8)?,
-                legends: r.get(9)?,
-                status: r.get::<_, Option<String>>(10)?,
+                score: None,
             })
         })
         .context("Failed to execute LIKE search query")?;
@@ -92,35 +161,27 @@ fn search_planets_fts(
     con: &Connection,
     query_norm: &str,
     limit: i64,
-) -> Result<Vec<PlanetSearchRow>> {
+    offset: i64,
+) -> Result<Vec<SearchHit>> {
     let mut stmt = con
         .prepare(
             r#"
-            SELECT p.FID, p.Planet, p.Region, p.Sector, p.System, p.Grid,
-                   p.X, p.Y, p.Canon, p.Legends, p.status
+            SELECT p.FID, p.Planet, bm25(planets_fts)
             FROM planets_fts f
             JOIN planets p ON p.FID = f.planet_fid
             WHERE p.status NOT IN ('deleted', 'skipped', 'invalid') AND planets_fts MATCH ?1
-            ORDER BY bm25(planets_fts)
-            LIMIT ?2
+            ORDER BY bm25(planets_fts), p.FID
+            LIMIT ?2 OFFSET ?3
             "#,
         )
         .context("Failed to prepare FTS search query")?;
 
     let rows = stmt
-        .query_map((query_norm, limit), |r| {
-            Ok(PlanetSearchRow {
+        .query_map((query_norm, limit, offset.max(0)), |r| {
+            Ok(SearchHit {
                 fid: r.get::<_, i64>(0)?,
                 name: r.get::<_, String>(1)?,
-                region: r.get::<_, Option<String>>(2)?,
-                sector: r.get::<_, Option<String>>(3)?,
-                system: r.get::<_, Option<String>>(4)?,
-                grid: r.get::<_, Option<String>>(5)?,
-                x: r.get(6)?,
-                y: r.get(7)?,
-                canon: r.get(8)?,
-                legends: r.get(9)?,
-                status: r.get::<_, Option<String>>(10)?,
+                score: Some(r.get(2)?),
             })
         })
         .context("Failed to execute FTS search query")?;
@@ -154,6 +215,7 @@ pub fn search_planets_filtered(
         .map(normalize_text);
 
     let has_text_query = query_norm.is_some();
+    let use_fts = has_text_query && has_table(con, "planets_fts")?;
 
     let mut sql = String::with_capacity(512);
 
@@ -169,12 +231,19 @@ pub fn search_planets_filtered(
             p.Y,
             COALESCE(p.Canon, 0),
             COALESCE(p.Legends, 0),
-            p.status
-        FROM planets p
+            p.status,
         "#,
     );
 
-    if has_text_query {
+    if use_fts {
+        sql.push_str(
+            "bm25(planets_fts)\nFROM planets p\nJOIN planets_fts ON planets_fts.planet_fid = p.FID\n",
+        );
+    } else {
+        sql.push_str("NULL\nFROM planets p\n");
+    }
+
+    if has_text_query && !use_fts {
         sql.push_str("LEFT JOIN planet_aliases pa ON pa.planet_fid = p.FID\n");
     }
 
@@ -197,10 +266,29 @@ pub fn search_planets_filtered(
     }
 
     if let Some(ref qn) = query_norm {
-        let like = format!("%{}%", qn);
-        sql.push_str(" AND (p.planet_norm LIKE ? OR pa.alias_norm LIKE ?)\n");
-        params.push(Value::from(like.clone()));
-        params.push(Value::from(like));
+        if use_fts {
+            let expr = if filter.prefix {
+                fts_prefix_query(qn)
+            } else {
+                fts_token_query(qn)
+            };
+            if expr.is_empty() {
+                return Ok(Vec::new());
+            }
+            sql.push_str(" AND planets_fts MATCH ?\n");
+            params.push(Value::from(expr));
+        } else {
+            let like = if filter.prefix {
+                format!("{}%", like_escape(qn))
+            } else {
+                format!("%{}%", like_escape(qn))
+            };
+            sql.push_str(
+                " AND (p.planet_norm LIKE ? ESCAPE '\\' OR pa.alias_norm LIKE ? ESCAPE '\\')\n",
+            );
+            params.push(Value::from(like.clone()));
+            params.push(Value::from(like));
+        }
     }
 
     if let Some(r) = filter
@@ -209,8 +297,8 @@ pub fn search_planets_filtered(
         .map(str::trim)
         .filter(|s| !s.is_empty())
     {
-        let like = format!("%{}%", r);
-        sql.push_str(" AND p.Region LIKE ? COLLATE NOCASE\n");
+        let like = format!("%{}%", like_escape(r));
+        sql.push_str(" AND p.Region LIKE ? ESCAPE '\\' COLLATE NOCASE\n");
         params.push(Value::from(like));
     }
 
@@ -220,8 +308,8 @@ pub fn search_planets_filtered(
         .map(str::trim)
         .filter(|s| !s.is_empty())
     {
-        let like = format!("%{}%", s);
-        sql.push_str(" AND p.Sector LIKE ? COLLATE NOCASE\n");
+        let like = format!("%{}%", like_escape(s));
+        sql.push_str(" AND p.Sector LIKE ? ESCAPE '\\' COLLATE NOCASE\n");
         params.push(Value::from(like));
     }
 
@@ -235,6 +323,17 @@ pub fn search_planets_filtered(
         params.push(Value::from(g.to_string()));
     }
 
+    if let Some(rf) = filter
+        .reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let like = format!("%{}%", like_escape(rf));
+        sql.push_str(" AND p.ref LIKE ? ESCAPE '\\' COLLATE NOCASE\n");
+        params.push(Value::from(like));
+    }
+
     if filter.canon == Some(true) {
         sql.push_str(" AND COALESCE(p.Canon, 0) = 1\n");
     }
@@ -243,9 +342,14 @@ pub fn search_planets_filtered(
         sql.push_str(" AND COALESCE(p.Legends, 0) = 1\n");
     }
 
-    sql.push_str(" ORDER BY p.planet_norm ASC\n");
-    sql.push_str(" LIMIT ?\n");
+    if use_fts {
+        sql.push_str(" ORDER BY bm25(planets_fts) ASC, p.planet_norm ASC, p.FID ASC\n");
+    } else {
+        sql.push_str(" ORDER BY p.planet_norm ASC, p.FID ASC\n");
+    }
+    sql.push_str(" LIMIT ? OFFSET ?\n");
     params.push(Value::from(filter.limit));
+    params.push(Value::from(filter.offset.max(0)));
 
     let mut stmt = con
         .prepare(&sql)
@@ -265,6 +369,7 @@ pub fn search_planets_filtered(
                 canon: r.get(8)?,
                 legends: r.get(9)?,
                 status: r.get::<_, Option<String>>(10)?,
+                score: r.get::<_, Option<f64>>(11)?,
             })
         })
         .context("Failed to execute filtered search query")?;
@@ -281,7 +386,7 @@ pub fn search_planets_filtered(
 /// 2. hydrate them as `PlanetSearchRow`
 /// 3. apply structured filters
 /// 4. restore original fuzzy order
-/// 5. truncate to `filter.limit`
+/// 5. skip `filter.offset` rows, then truncate to `filter.limit`
 ///
 /// This function uses adaptive over-fetching to avoid losing valid matches
 /// when structured filters (region/sector/grid/status/canon/legends) are
@@ -298,17 +403,26 @@ pub fn fuzzy_search_filtered(
         return Ok(Vec::new());
     }
 
+    let skip = filter.offset.max(0) as usize;
     let target_limit = filter.limit as usize;
+    let wanted = skip.saturating_add(target_limit);
 
     let status_filter = filter.status.as_deref().map(|s| s.to_ascii_lowercase());
     let region_filter = filter.region.as_deref().map(|s| s.to_ascii_lowercase());
     let sector_filter = filter.sector.as_deref().map(|s| s.to_ascii_lowercase());
     let grid_filter = filter.grid.as_deref().map(|s| s.to_ascii_lowercase());
+    // `ref` is not part of PlanetSearchRow: filter it during hydration instead.
+    let ref_like = filter
+        .reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| format!("%{}%", like_escape(s)));
 
     // Start with a reasonably wide batch and grow until we either:
     // - have enough filtered results
     // - exhaust fuzzy candidates
-    let mut fetch_limit = target_limit.saturating_mul(10).max(50);
+    let mut fetch_limit = wanted.saturating_mul(10).max(50);
 
     loop {
         let candidates = fuzzy_search(
@@ -326,8 +440,13 @@ pub fn fuzzy_search_filtered(
 
         // Preserve fuzzy order so we can restore it after SQL hydration/filtering.
         let mut order_by_fid: HashMap<i64, usize> = HashMap::with_capacity(candidates.len());
+        let mut distance_by_fid: HashMap<i64, usize> = HashMap::with_capacity(candidates.len());
         for (idx, hit) in candidates.iter().enumerate() {
             order_by_fid.entry(hit.fid).or_insert(idx);
+            distance_by_fid
+                .entry(hit.fid)
+                .and_modify(|d| *d = (*d).min(hit.distance))
+                .or_insert(hit.distance);
         }
 
         let fids: Vec<i64> = candidates.iter().map(|h| h.fid).collect();
@@ -351,6 +470,7 @@ pub fn fuzzy_search_filtered(
                     status
                 FROM planets
                 WHERE FID = ?1
+                  AND (?2 IS NULL OR ref LIKE ?2 ESCAPE '\' COLLATE NOCASE)
                 "#,
             )
             .context("Failed to prepare fuzzy candidate hydration query")?;
@@ -359,7 +479,7 @@ pub fn fuzzy_search_filtered(
 
         for fid in &fids {
             let row = stmt
-                .query_row(params![fid], |r| {
+                .query_row(params![fid, ref_like], |r| {
                     Ok(PlanetSearchRow {
                         fid: r.get(0)?,
                         name: r.get(1)?,
@@ -372,6 +492,7 @@ pub fn fuzzy_search_filtered(
                         canon: r.get::<_, Option<i64>>(8)?.unwrap_or(0) == 1,
                         legends: r.get::<_, Option<i64>>(9)?.unwrap_or(0) == 1,
                         status: r.get(10)?,
+                        score: distance_by_fid.get(fid).map(|d| *d as f64),
                     })
                 })
                 .optional()
@@ -433,9 +554,8 @@ pub fn fuzzy_search_filtered(
         // Stop if:
         // 1) we have enough results
         // 2) fuzzy_search returned fewer items than requested => no more candidates available
-        if filtered.len() >= target_limit || candidates.len() < fetch_limit {
-            filtered.truncate(target_limit);
-            return Ok(filtered);
+        if filtered.len() >= wanted || candidates.len() < fetch_limit {
+            return Ok(filtered.into_iter().skip(skip).take(target_limit).collect());
         }
 
         // Otherwise, widen the fuzzy window and try again.
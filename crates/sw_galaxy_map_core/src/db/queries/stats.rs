@@ -131,3 +131,36 @@ pub fn galaxy_stats(con: &Connection, top_n: usize) -> Result<crate::model::Gala
 
     Ok(s)
 }
+
+/// Distinct values of `column` with their planet counts, ordered by value.
+///
+/// Deleted/skipped/invalid planets are ignored, except for `status` itself,
+/// which counts every row (NULL is reported as `(none)`).
+pub fn distinct_values(
+    con: &Connection,
+    column: crate::domain::PlanetColumn,
+) -> Result<Vec<(String, i64)>> {
+    use crate::domain::PlanetColumn;
+
+    let sql = match column {
+        PlanetColumn::Status => {
+            "SELECT COALESCE(status, '(none)') AS v, COUNT(*) FROM planets GROUP BY v ORDER BY v"
+                .to_string()
+        }
+        _ => {
+            let col = column.sql_name();
+            format!(
+                "SELECT {col} AS v, COUNT(*) FROM planets WHERE {col} IS NOT NULL AND TRIM({col}) != '' AND (status IS NULL OR status NOT IN ('deleted', 'skipped', 'invalid')) GROUP BY v ORDER BY v COLLATE NOCASE"
+            )
+        }
+    };
+
+    let mut stmt = con.prepare(&sql)?;
+    let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
@@ -1,6 +1,6 @@
 use super::near_planets;
 use super::row_mappers::unknown_planet_from_row;
-use crate::model::{NearHit, UnknownNearHit, UnknownPlanet};
+use crate::model::{NearFilter, NearHit, UnknownNearHit, UnknownPlanet};
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, params};
 
@@ -210,7 +210,14 @@ pub fn near_planets_for_unknown_id(
     let origin_x = unknown.x.ok_or(rusqlite::Error::InvalidQuery)?;
     let origin_y = unknown.y.ok_or(rusqlite::Error::InvalidQuery)?;
 
-    let rows = near_planets(con, origin_x, origin_y, radius, limit)?;
+    let rows = near_planets(
+        con,
+        origin_x,
+        origin_y,
+        radius,
+        &NearFilter::default(),
+        limit,
+    )?;
 
     Ok((unknown, rows))
 }
@@ -0,0 +1,39 @@
+use crate::model::UpdateLogRow;
+use anyhow::Result;
+use rusqlite::{Connection, params};
+
+/// Returns the most recent `db update` runs, newest first.
+pub fn list_update_log(con: &Connection, limit: usize) -> Result<Vec<UpdateLogRow>> {
+    let mut stmt = con.prepare(
+        r#"
+        SELECT id, ran_at, downloaded_features,
+               inserted, updated, revived, unchanged,
+               marked_deleted, pruned, skipped,
+               prune_used, mark_deleted_used
+        FROM update_log
+        ORDER BY ran_at DESC, id DESC
+        LIMIT ?1
+        "#,
+    )?;
+
+    let rows = stmt
+        .query_map(params![limit as i64], |r| {
+            Ok(UpdateLogRow {
+                id: r.get(0)?,
+                ran_at: r.get(1)?,
+                downloaded_features: r.get(2)?,
+                inserted: r.get(3)?,
+                updated: r.get(4)?,
+                revived: r.get(5)?,
+                unchanged: r.get(6)?,
+                marked_deleted: r.get(7)?,
+                pruned: r.get(8)?,
+                skipped: r.get(9)?,
+                prune_used: r.get(10)?,
+                mark_deleted_used: r.get(11)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(rows)
+}
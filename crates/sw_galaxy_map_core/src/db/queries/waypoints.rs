@@ -2,6 +2,7 @@ use super::row_mappers::{link_from_row, waypoint_from_row};
 use crate::model::{
     Waypoint, WaypointLinkRow, WaypointListRow, WaypointPlanetLink, WaypointRouteRow,
 };
+use crate::utils::normalize_text;
 use anyhow::Result;
 use rusqlite::{Connection, OptionalExtension, params};
 
@@ -39,6 +40,101 @@ pub fn insert_waypoint(
     Ok(con.last_insert_rowid())
 }
 
+/// Fields to change on an existing waypoint (`None` keeps the current value).
+#[derive(Debug, Clone, Default)]
+pub struct WaypointUpdate {
+    pub name: Option<String>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub kind: Option<String>,
+    pub note: Option<String>,
+}
+
+impl WaypointUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.x.is_none()
+            && self.y.is_none()
+            && self.kind.is_none()
+            && self.note.is_none()
+    }
+}
+
+/// Updates the provided fields of a waypoint and returns the stored row.
+///
+/// A rename recomputes `name_norm` and fails if another waypoint already uses it;
+/// `updated_at` is set by the `trg_waypoints_updated_at` trigger.
+pub fn update_waypoint(con: &Connection, id: i64, update: &WaypointUpdate) -> Result<Waypoint> {
+    if find_waypoint_by_id(con, id)?.is_none() {
+        anyhow::bail!("Waypoint not found: id={}", id);
+    }
+
+    let name = update
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let name_norm = name.map(normalize_text);
+    if let Some(norm) = name_norm.as_deref()
+        && let Some(other) = find_waypoint_by_norm(con, norm)?
+        && other.id != id
+    {
+        anyhow::bail!(
+            "Waypoint name already in use: '{}' (id={})",
+            other.name,
+            other.id
+        );
+    }
+    for v in [update.x, update.y].into_iter().flatten() {
+        if !v.is_finite() {
+            anyhow::bail!("Invalid waypoint coordinate: {}", v);
+        }
+    }
+
+    con.execute(
+        r#"
+        UPDATE waypoints
+        SET name      = COALESCE(?2, name),
+            name_norm = COALESCE(?3, name_norm),
+            x         = COALESCE(?4, x),
+            y         = COALESCE(?5, y),
+            kind      = COALESCE(?6, kind),
+            note      = COALESCE(?7, note)
+        WHERE id = ?1
+        "#,
+        params![
+            id,
+            name,
+            name_norm,
+            update.x,
+            update.y,
+            update.kind,
+            update.note
+        ],
+    )?;
+
+    find_waypoint_by_id(con, id)?
+        .ok_or_else(|| anyhow::anyhow!("Waypoint vanished during update: id={}", id))
+}
+
+/// Renames a waypoint, recomputing `name_norm`.
+///
+/// Links in `waypoint_planets` reference the id and are left untouched.
+/// Fails when another waypoint already uses the normalized name.
+pub fn rename_waypoint(con: &Connection, id: i64, new_name: &str) -> Result<Waypoint> {
+    if new_name.trim().is_empty() {
+        anyhow::bail!("Waypoint name cannot be empty");
+    }
+    update_waypoint(
+        con,
+        id,
+        &WaypointUpdate {
+            name: Some(new_name.to_string()),
+            ..Default::default()
+        },
+    )
+}
+
 /// Returns a waypoint by normalized name.
 pub fn find_waypoint_by_norm(con: &Connection, name_norm: &str) -> Result<Option<Waypoint>> {
     let sql = format!(
@@ -114,6 +210,122 @@ pub fn list_waypoints(
     Ok((out, total.max(0) as usize))
 }
 
+/// Returns every waypoint ordered by id (no pagination).
+pub fn list_all_waypoints(con: &Connection) -> Result<Vec<Waypoint>> {
+    let sql = format!(
+        r#"
+        SELECT
+          {select}
+        FROM waypoints w
+        ORDER BY w.id
+        "#,
+        select = WAYPOINT_SELECT
+    );
+
+    let mut stmt = con.prepare(&sql)?;
+    let rows = stmt
+        .query_map([], waypoint_from_row)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Returns every waypoint <-> planet link ordered by waypoint, then planet.
+pub fn list_all_waypoint_links(con: &Connection) -> Result<Vec<WaypointPlanetLink>> {
+    let mut stmt = con.prepare(
+        r#"
+        SELECT
+          waypoint_id AS waypoint_id,
+          planet_fid  AS planet_fid,
+          role        AS role,
+          distance    AS distance
+        FROM waypoint_planets
+        ORDER BY waypoint_id, planet_fid
+        "#,
+    )?;
+
+    let rows = stmt
+        .query_map([], link_from_row)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Returns the closest waypoint within `radius` of `(x, y)` with its distance.
+///
+/// Computed detour waypoints are ignored: only user-placed nav points qualify.
+pub fn nearest_waypoint(
+    con: &Connection,
+    x: f64,
+    y: f64,
+    radius: f64,
+) -> Result<Option<(Waypoint, f64)>> {
+    let sql = format!(
+        r#"
+        SELECT
+          {select}
+        FROM waypoints w
+        WHERE w.kind <> 'computed'
+          AND w.x BETWEEN ?1 - ?3 AND ?1 + ?3
+          AND w.y BETWEEN ?2 - ?3 AND ?2 + ?3
+        ORDER BY (w.x - ?1) * (w.x - ?1) + (w.y - ?2) * (w.y - ?2), w.id
+        LIMIT 1
+        "#,
+        select = WAYPOINT_SELECT
+    );
+
+    let mut stmt = con.prepare(&sql)?;
+    let wp = stmt
+        .query_row(params![x, y, radius], waypoint_from_row)
+        .optional()?;
+
+    Ok(wp
+        .map(|w| {
+            let d = ((w.x - x).powi(2) + (w.y - y).powi(2)).sqrt();
+            (w, d)
+        })
+        .filter(|(_, d)| *d <= radius))
+}
+
+/// Returns waypoints within `r` of `(x, y)` with their distance, closest first.
+///
+/// Uses the same squared-distance predicate as the planet `near` queries.
+pub fn near_waypoints(
+    con: &Connection,
+    x: f64,
+    y: f64,
+    r: f64,
+    limit: usize,
+) -> Result<Vec<(Waypoint, f64)>> {
+    if !x.is_finite() || !y.is_finite() {
+        anyhow::bail!("Center coordinates must be finite numbers");
+    }
+    if !r.is_finite() || r < 0.0 {
+        anyhow::bail!("Radius must be a finite number >= 0");
+    }
+
+    let sql = format!(
+        r#"
+        SELECT
+          {select},
+          ((w.x - ?1)*(w.x - ?1) + (w.y - ?2)*(w.y - ?2)) AS d2
+        FROM waypoints w
+        WHERE ((w.x - ?1)*(w.x - ?1) + (w.y - ?2)*(w.y - ?2)) <= ?3
+        ORDER BY d2 ASC, w.id ASC
+        LIMIT ?4
+        "#,
+        select = WAYPOINT_SELECT
+    );
+
+    let mut stmt = con.prepare(&sql)?;
+    let rows = stmt
+        .query_map(params![x, y, r * r, limit as i64], |row| {
+            let d2: f64 = row.get("d2")?;
+            Ok((waypoint_from_row(row)?, d2.sqrt()))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(rows)
+}
+
 /// Deletes a waypoint by id.
 pub fn delete_waypoint(con: &Connection, id: i64) -> Result<usize> {
     let n = con.execute("DELETE FROM waypoints WHERE id = ?1", [id])?;
@@ -150,8 +362,7 @@ pub fn link_waypoint_to_planet(
         r#"
         INSERT INTO waypoint_planets(waypoint_id, planet_fid, role, distance)
         VALUES (?1, ?2, ?3, ?4)
-        ON CONFLICT(waypoint_id, planet_fid) DO UPDATE SET
-          role=excluded.role,
+        ON CONFLICT(waypoint_id, planet_fid, role) DO UPDATE SET
           distance=excluded.distance
         "#,
         params![waypoint_id, planet_fid, role, distance],
@@ -159,15 +370,21 @@ pub fn link_waypoint_to_planet(
     Ok(())
 }
 
-/// Deletes a single waypoint <-> planet link.
+/// Deletes waypoint <-> planet links: only `role` when given, every role otherwise.
 pub fn unlink_waypoint_from_planet(
     con: &Connection,
     waypoint_id: i64,
     planet_fid: i64,
+    role: Option<&str>,
 ) -> Result<usize> {
     let n = con.execute(
-        "DELETE FROM waypoint_planets WHERE waypoint_id = ?1 AND planet_fid = ?2",
-        params![waypoint_id, planet_fid],
+        r#"
+        DELETE FROM waypoint_planets
+        WHERE waypoint_id = ?1
+          AND planet_fid = ?2
+          AND (?3 IS NULL OR role = ?3)
+        "#,
+        params![waypoint_id, planet_fid, role],
     )?;
     Ok(n)
 }
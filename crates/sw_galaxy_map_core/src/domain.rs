@@ -1,4 +1,7 @@
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::routing::geometry::{Point, dist};
 
 /// Sorting strategy for persisted route listings.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
@@ -7,3 +10,71 @@ pub enum RouteListSort {
     Id,
     Length,
 }
+
+/// Result order for `near` queries.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum NearSort {
+    #[default]
+    Distance,
+    Name,
+    Fid,
+}
+
+/// Which occurrence to keep when the source feed repeats a FID.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DedupePolicy {
+    First,
+    Last,
+}
+
+/// Categorical `planets` column whose distinct values can be listed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PlanetColumn {
+    Region,
+    Sector,
+    System,
+    Grid,
+    Status,
+}
+
+impl PlanetColumn {
+    /// SQL column name. This is the whitelist: only these identifiers are ever
+    /// interpolated into a query.
+    pub fn sql_name(self) -> &'static str {
+        match self {
+            PlanetColumn::Region => "Region",
+            PlanetColumn::Sector => "Sector",
+            PlanetColumn::System => "System",
+            PlanetColumn::Grid => "Grid",
+            PlanetColumn::Status => "status",
+        }
+    }
+}
+
+/// Distance used for route length and detour scoring.
+///
+/// Collision checks stay Euclidean: obstacles are circles.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(self, a: Point, b: Point) -> f64 {
+        let (dx, dy) = ((b.x - a.x).abs(), (b.y - a.y).abs());
+        match self {
+            Metric::Euclidean => dist(a, b),
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+        }
+    }
+
+    /// Length of the polyline through `points`.
+    pub fn polyline_length(self, points: &[Point]) -> f64 {
+        points.windows(2).map(|w| self.distance(w[0], w[1])).sum()
+    }
+}
@@ -1,10 +1,12 @@
+use crate::domain::{Metric, NearSort};
 use crate::utils::wiki::fandom_planet_url;
 use rusqlite::{Result as SqlResult, Row};
 use serde::{Deserialize, Serialize};
 
 pub const PC_TO_LY: f64 = 3.26156;
 
-#[derive(Debug)]
+/// Serialized field names follow the `planets` columns (`ref`, `cregion`, ...).
+#[derive(Debug, Serialize)]
 pub struct Planet {
     pub fid: i64,
     pub planet: String,
@@ -23,13 +25,16 @@ pub struct Planet {
     pub name2: Option<String>,
     pub lat: Option<f64>,
     pub long: Option<f64>,
+    #[serde(rename = "ref")]
     pub reference: Option<String>,
     pub status: Option<String>,
+    #[serde(rename = "cregion")]
     pub c_region: Option<String>,
+    #[serde(rename = "cregion_li")]
     pub c_region_li: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct AliasRow {
     pub alias: String,
     pub source: Option<String>,
@@ -39,6 +44,8 @@ pub struct AliasRow {
 pub struct NearHit {
     pub fid: i64,
     pub planet: String,
+    pub region: Option<String>,
+    pub sector: Option<String>,
     pub x: f64,
     pub y: f64,
     pub distance: f64,
@@ -76,7 +83,7 @@ pub struct UnknownPlanet {
     pub notes: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Waypoint {
     pub id: i64,
     pub name: String,
@@ -127,7 +134,7 @@ impl Waypoint {
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct WaypointPlanetLink {
     pub waypoint_id: i64,
     pub planet_fid: i64,
@@ -168,7 +175,7 @@ impl Planet {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RouteRow {
     pub id: i64,
     pub from_planet_fid: i64,
@@ -179,13 +186,15 @@ pub struct RouteRow {
     pub options_json: String,
     pub length: Option<f64>,
     pub iterations: Option<i64>,
+    /// Wall-clock time of the last computation (ms); `None` for older or imported routes.
+    pub compute_ms: Option<i64>,
     pub status: String,
     pub error: Option<String>,
     pub created_at: String,
     pub updated_at: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RouteWaypointRow {
     pub seq: i64,
     pub x: f64,
@@ -197,7 +206,7 @@ pub struct RouteWaypointRow {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RouteDetourRow {
     pub idx: i64,
 
@@ -238,6 +247,59 @@ pub struct RouteLoaded {
     pub detours: Vec<RouteDetourRow>,
 }
 
+impl RouteLoaded {
+    /// Mirror view of the route (TO→FROM): endpoints swapped and polyline reversed.
+    ///
+    /// Only meaningful while the routing cost model is symmetric. Detour records are kept
+    /// as computed for the stored direction (their segment indexes are not remapped).
+    pub fn reversed(&self) -> RouteLoaded {
+        let mut route = self.route.clone();
+        std::mem::swap(&mut route.from_planet_fid, &mut route.to_planet_fid);
+        std::mem::swap(&mut route.from_planet_name, &mut route.to_planet_name);
+
+        let waypoints = self
+            .waypoints
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(seq, w)| RouteWaypointRow {
+                seq: seq as i64,
+                ..w.clone()
+            })
+            .collect();
+
+        RouteLoaded {
+            route,
+            waypoints,
+            detours: self.detours.clone(),
+        }
+    }
+}
+
+/// Obstacle referenced by an exported route, kept so the file stays readable
+/// on a database that lacks (or has moved) that planet.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RouteExportObstacle {
+    pub fid: i64,
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub radius: f64,
+}
+
+/// Self-contained JSON document written by `route export` and read by `route import`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RouteExport {
+    pub format: String,
+    pub format_version: u32,
+    pub exported_at: String,
+    pub route: RouteRow,
+    pub options: Option<RouteOptionsJson>,
+    pub waypoints: Vec<RouteWaypointRow>,
+    pub detours: Vec<RouteDetourRow>,
+    pub obstacles: Vec<RouteExportObstacle>,
+}
+
 /// A lightweight view of an obstacle used by the routing engine.
 ///
 /// We use a named struct rather than a large tuple to keep the API readable and
@@ -255,12 +317,66 @@ pub struct RoutingObstacleRow {
 pub struct RouteOptionsJson {
     pub clearance: f64,
     pub max_iters: usize,
+    /// Missing for routes persisted before the cap existed.
+    #[serde(default)]
+    pub max_waypoints: Option<usize>,
     pub max_offset_tries: usize,
     pub offset_growth: f64,
     pub turn_weight: f64,
     pub back_weight: f64,
     pub proximity_weight: f64,
     pub proximity_margin: f64,
+    /// Missing for routes persisted before the exponent existed (quadratic).
+    #[serde(default)]
+    pub proximity_exponent: Option<f64>,
+    #[serde(default)]
+    pub explore_both_sides: bool,
+    #[serde(default)]
+    pub metric: Metric,
+    #[serde(flatten)]
+    pub leg: RouteLegOptions,
+}
+
+/// `route compute` inputs that shape a leg beyond the router options.
+///
+/// Stored next to them in `options_json` so a persisted route is only reused
+/// (mirrored or seeded) for the same request. Every field is empty for routes
+/// persisted before they existed.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RouteLegOptions {
+    /// Catalog waypoints the route is pinned through (`--waypoint`), in order.
+    #[serde(default)]
+    pub vias: Vec<String>,
+    /// Planets forced in as obstacles (`--avoid`), sorted.
+    #[serde(default)]
+    pub avoid: Vec<String>,
+    /// Endpoint snap radius (`None` when endpoints were not snapped).
+    #[serde(default)]
+    pub snap_radius: Option<f64>,
+    /// `--min-spacing` merge distance.
+    #[serde(default)]
+    pub min_spacing: Option<f64>,
+    /// Obstacle radius (`--safety`).
+    #[serde(default)]
+    pub safety: Option<f64>,
+    /// Obstacle fetch margin around the leg (`--bbox-margin`).
+    #[serde(default)]
+    pub bbox_margin: Option<f64>,
+    /// Obstacle cap (`--max-obstacles`).
+    #[serde(default)]
+    pub max_obstacles: Option<usize>,
+}
+
+/// Persisted route whose polyline passes within some distance of a point.
+#[derive(Debug, Clone)]
+pub struct RoutePassRow {
+    pub route_id: i64,
+    pub from_planet_fid: i64,
+    pub from_planet_name: String,
+    pub to_planet_fid: i64,
+    pub to_planet_name: String,
+    /// Closest distance (parsec) between the polyline and the point.
+    pub distance: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -274,6 +390,7 @@ pub struct RouteListRow {
     pub status: String,
     pub length: Option<f64>,
     pub iterations: Option<i64>,
+    pub compute_ms: Option<i64>,
 
     pub created_at: String,
     pub updated_at: Option<String>,
@@ -295,6 +412,33 @@ pub struct PlanetSearchRow {
     pub canon: bool,
     pub legends: bool,
     pub status: Option<String>,
+    /// Relevance, lower is better: `bm25` for FTS hits, edit distance for fuzzy
+    /// hits, `None` when the search path does not rank (LIKE).
+    pub score: Option<f64>,
+}
+
+/// Free-text search hit: the planet plus its relevance.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub fid: i64,
+    pub name: String,
+    /// `bm25` (lower is better) for FTS hits, `None` from the LIKE fallback.
+    pub score: Option<f64>,
+}
+
+/// Optional constraints for `near` queries.
+///
+/// `Default` is a plain radius search ordered by distance.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NearFilter {
+    /// Inner radius (parsec): closer planets are skipped.
+    pub min_r: Option<f64>,
+    /// Result order.
+    pub sort: NearSort,
+    /// If true, only Canon planets.
+    pub canon: bool,
+    /// If true, only Legends planets.
+    pub legends: bool,
 }
 
 /// Filter criteria for combined planet search.
@@ -313,14 +457,37 @@ pub struct SearchFilter {
     pub grid: Option<String>,
     /// Status filter (exact: active, inserted, modified, skipped, deleted).
     pub status: Option<String>,
+    /// Source reference filter on the `ref` column (LIKE, case-insensitive).
+    pub reference: Option<String>,
     /// If true, only Canon planets.
     pub canon: Option<bool>,
     /// If true, only Legends planets.
     pub legends: Option<bool>,
     /// Enable fuzzy matching (Levenshtein distance tolerance for typos).
     pub fuzzy: bool,
+    /// Match each query token as a prefix (`token*` with FTS, `token%` with LIKE).
+    pub prefix: bool,
     /// Max results.
     pub limit: i64,
+    /// Rows to skip before the first returned result (paging).
+    pub offset: i64,
+}
+
+/// One committed `db update` run, as stored in `update_log`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateLogRow {
+    pub id: i64,
+    pub ran_at: String,
+    pub downloaded_features: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub revived: i64,
+    pub unchanged: i64,
+    pub marked_deleted: i64,
+    pub pruned: i64,
+    pub skipped: i64,
+    pub prune_used: bool,
+    pub mark_deleted_used: bool,
 }
 
 /// Aggregate galaxy statistics.
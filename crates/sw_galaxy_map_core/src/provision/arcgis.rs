@@ -1,10 +1,34 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use reqwest::StatusCode;
 use reqwest::blocking::Client;
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
 
-const LAYER_URL: &str =
+/// Feature layer queried when no `--service-url` is given.
+pub const DEFAULT_LAYER_URL: &str =
     "https://services3.arcgis.com/nM57tYg6wB9iTP3P/arcgis/rest/services/planets/FeatureServer/0";
 
+/// Effective layer URL: `custom` (validated, trailing `/` removed) or the default.
+pub fn layer_url(custom: Option<&str>) -> Result<String> {
+    let Some(raw) = custom else {
+        return Ok(DEFAULT_LAYER_URL.to_string());
+    };
+    let trimmed = raw.trim().trim_end_matches('/');
+    let url =
+        reqwest::Url::parse(trimmed).with_context(|| format!("Invalid service URL: '{}'", raw))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("Service URL must use http or https: '{}'", raw);
+    }
+    if url.query().is_some() {
+        bail!(
+            "Service URL must point at the layer itself, without a query string: '{}'",
+            raw
+        );
+    }
+    Ok(trimmed.to_string())
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LayerInfo {
     #[serde(rename = "serviceItemId")]
@@ -19,6 +43,16 @@ pub struct LayerInfo {
 
     #[serde(rename = "editingInfo")]
     pub editing_info: Option<EditingInfo>,
+
+    #[serde(rename = "editFieldsInfo")]
+    pub edit_fields_info: Option<EditFieldsInfo>,
+}
+
+/// Editor-tracking fields of the layer (absent when tracking is disabled).
+#[derive(Debug, Deserialize)]
+pub struct EditFieldsInfo {
+    #[serde(rename = "editDateField")]
+    pub edit_date_field: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,53 +77,218 @@ pub struct QueryResponse {
     pub exceeded_transfer_limit: bool,
 }
 
+#[derive(Debug, Deserialize)]
+struct CountResponse {
+    count: usize,
+}
+
+/// Download progress callback: features fetched so far, total when known.
+pub type FetchProgress<'a> = &'a mut dyn FnMut(usize, Option<usize>);
+
+/// Retry behaviour for ArcGIS requests.
+///
+/// Network errors and 5xx responses are retried with exponential backoff
+/// (`base_delay`, then ×2 per retry); 4xx responses and malformed JSON are not.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Called before each retry with a one-line description.
+    pub on_retry: Option<fn(&str)>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            on_retry: None,
+        }
+    }
+}
+
+/// How a failed request attempt is classified for retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    Status(StatusCode),
+    Network,
+    Other,
+}
+
+fn should_retry(f: Failure) -> bool {
+    match f {
+        Failure::Status(s) => s.is_server_error(),
+        Failure::Network => true,
+        Failure::Other => false,
+    }
+}
+
+fn classify(e: &reqwest::Error) -> Failure {
+    match e.status() {
+        Some(s) => Failure::Status(s),
+        None if e.is_timeout() || e.is_connect() || e.is_request() || e.is_body() => {
+            Failure::Network
+        }
+        None => Failure::Other,
+    }
+}
+
+fn backoff_delay(base: Duration, retry: u32) -> Duration {
+    base.saturating_mul(1u32 << retry.min(16))
+}
+
+/// Runs `attempt` until it succeeds, fails with a non-retryable error or the
+/// policy's retries are used up.
+fn with_retry<T, E>(
+    policy: &RetryPolicy,
+    what: &str,
+    classify: impl Fn(&E) -> Failure,
+    mut attempt: impl FnMut() -> std::result::Result<T, E>,
+) -> Result<T>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut retry = 0;
+    loop {
+        match attempt() {
+            Ok(v) => return Ok(v),
+            Err(e) if retry < policy.max_retries && should_retry(classify(&e)) => {
+                let delay = backoff_delay(policy.base_delay, retry);
+                retry += 1;
+                if let Some(notify) = policy.on_retry {
+                    notify(&format!(
+                        "{} failed ({}); retry {}/{} in {:.1}s",
+                        what,
+                        e,
+                        retry,
+                        policy.max_retries,
+                        delay.as_secs_f64()
+                    ));
+                }
+                std::thread::sleep(delay);
+                super::cancel::check()?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// GET `url` with `query` and decode the JSON body, retrying per `policy`.
+fn get_json<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    query: &[(&str, &str)],
+    policy: &RetryPolicy,
+    what: &str,
+) -> Result<T> {
+    with_retry(policy, what, classify, || {
+        client
+            .get(url)
+            .query(query)
+            .send()?
+            .error_for_status()?
+            .json::<T>()
+    })
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Feature {
     pub attributes: serde_json::Value,
 }
 
-pub fn fetch_layer_info(client: &Client) -> Result<LayerInfo> {
-    let url = format!("{LAYER_URL}?f=json");
-    let info: LayerInfo = client
-        .get(url)
+pub fn fetch_layer_info(
+    client: &Client,
+    layer_url: &str,
+    retry: &RetryPolicy,
+) -> Result<LayerInfo> {
+    get_json(
+        client,
+        layer_url,
+        &[("f", "json")],
+        retry,
+        "Layer info request",
+    )
+    .context("Failed to fetch layer info")
+}
+
+pub fn fetch_all_features(
+    client: &Client,
+    layer_url: &str,
+    page_size: i64,
+    retry: &RetryPolicy,
+    progress: FetchProgress<'_>,
+) -> Result<Vec<serde_json::Value>> {
+    fetch_features(client, layer_url, page_size, "1=1", retry, progress)
+}
+
+/// Number of features matching `where_clause` (`None` when the server won't say).
+fn fetch_feature_count(client: &Client, layer_url: &str, where_clause: &str) -> Option<usize> {
+    let resp: CountResponse = client
+        .get(format!("{layer_url}/query"))
+        .query(&[
+            ("f", "json"),
+            ("where", where_clause),
+            ("returnCountOnly", "true"),
+        ])
         .send()
-        .context("Failed to fetch layer info")?
+        .ok()?
         .error_for_status()
-        .context("Layer info request returned error status")?
+        .ok()?
         .json()
-        .context("Failed to parse layer info JSON")?;
+        .ok()?;
+    Some(resp.count)
+}
 
-    Ok(info)
+/// `where` clause selecting features whose `edit_date_field` is after `since_ms` (epoch ms).
+pub fn edited_since_clause(edit_date_field: &str, since_ms: i64) -> Option<String> {
+    let ts = chrono::DateTime::from_timestamp_millis(since_ms)?;
+    Some(format!(
+        "{} > TIMESTAMP '{}'",
+        edit_date_field,
+        ts.format("%Y-%m-%d %H:%M:%S")
+    ))
 }
 
-pub fn fetch_all_features(client: &Client, page_size: i64) -> Result<Vec<serde_json::Value>> {
+/// Pages through the features matching `where_clause`, calling `progress` after each page.
+pub fn fetch_features(
+    client: &Client,
+    layer_url: &str,
+    page_size: i64,
+    where_clause: &str,
+    retry: &RetryPolicy,
+    progress: FetchProgress<'_>,
+) -> Result<Vec<serde_json::Value>> {
     let mut out: Vec<serde_json::Value> = Vec::new();
+    let total = fetch_feature_count(client, layer_url, where_clause);
+    progress(0, total);
 
     let mut offset = 0i64;
     loop {
-        let url = format!("{LAYER_URL}/query");
-        let resp: QueryResponse = client
-            .get(&url)
-            .query(&[
+        super::cancel::check()?;
+
+        let url = format!("{layer_url}/query");
+        let resp: QueryResponse = get_json(
+            client,
+            &url,
+            &[
                 ("f", "json"),
-                ("where", "1=1"),
+                ("where", where_clause),
                 ("outFields", "*"),
                 ("returnGeometry", "false"),
                 ("orderByFields", "FID"),
                 ("resultOffset", &offset.to_string()),
                 ("resultRecordCount", &page_size.to_string()),
-            ])
-            .send()
-            .context("Failed to query features")?
-            .error_for_status()
-            .context("Query request returned error status")?
-            .json()
-            .context("Failed to parse query JSON")?;
+            ],
+            retry,
+            &format!("Feature page at offset {}", offset),
+        )
+        .context("Failed to query features")?;
 
         let n = resp.features.len();
         for f in resp.features {
             out.push(f.attributes);
         }
+        progress(out.len(), total);
 
         // Se non arrivano più record, stop
         if n == 0 {
@@ -107,3 +306,86 @@ pub fn fetch_all_features(client: &Client, page_size: i64) -> Result<Vec<serde_j
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[derive(Debug)]
+    struct FakeError(Failure);
+
+    impl std::fmt::Display for FakeError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+
+    impl std::error::Error for FakeError {}
+
+    /// Replays `script` (one outcome per attempt) and returns (result, attempts made).
+    fn replay(script: &[Option<Failure>], max_retries: u32) -> (Result<u32>, usize) {
+        let calls = Cell::new(0usize);
+        let policy = RetryPolicy {
+            max_retries,
+            base_delay: Duration::ZERO,
+            on_retry: None,
+        };
+        let res = with_retry(
+            &policy,
+            "fake",
+            |e: &FakeError| e.0,
+            || {
+                let i = calls.get();
+                calls.set(i + 1);
+                match script[i] {
+                    None => Ok(i as u32),
+                    Some(f) => Err(FakeError(f)),
+                }
+            },
+        );
+        (res, calls.get())
+    }
+
+    #[test]
+    fn retry_covers_network_and_5xx_but_not_4xx() {
+        let s503 = Some(Failure::Status(StatusCode::SERVICE_UNAVAILABLE));
+        let s404 = Some(Failure::Status(StatusCode::NOT_FOUND));
+
+        let (res, calls) = replay(&[Some(Failure::Network), s503, None], 3);
+        assert_eq!(res.unwrap(), 2);
+        assert_eq!(calls, 3);
+
+        let (res, calls) = replay(&[s404, None], 3);
+        assert!(res.is_err());
+        assert_eq!(calls, 1, "4xx is not retried");
+
+        let (res, calls) = replay(&[Some(Failure::Other), None], 3);
+        assert!(res.is_err());
+        assert_eq!(calls, 1, "decode errors are not retried");
+
+        let (res, calls) = replay(&[s503, s503, s503], 2);
+        assert!(res.is_err());
+        assert_eq!(calls, 3, "gives up after max_retries");
+    }
+
+    #[test]
+    fn backoff_doubles_per_retry() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(base, 0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(base, 1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn layer_url_defaults_and_validates_overrides() {
+        assert_eq!(layer_url(None).unwrap(), DEFAULT_LAYER_URL);
+        assert_eq!(
+            layer_url(Some(" https://mirror.example/arcgis/FeatureServer/0/ ")).unwrap(),
+            "https://mirror.example/arcgis/FeatureServer/0"
+        );
+        assert!(layer_url(Some("not a url")).is_err());
+        assert!(layer_url(Some("ftp://mirror.example/layer")).is_err());
+        assert!(layer_url(Some("https://mirror.example/layer?f=json")).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+//! Cancellation flag for long provisioning runs (`db init` / `db update`).
+//!
+//! Each run clears the flag when it starts; it is then checked between download
+//! pages and right before the final commit. The front end decides what raises it
+//! (the CLI wires it to Ctrl-C).
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Clear a request left over from a previous run in the same process.
+pub fn reset() {
+    CANCELLED.store(false, Ordering::SeqCst);
+}
+
+/// Ask the running job to stop at its next safe point.
+///
+/// Returns `true` if a stop had already been requested.
+pub fn request() -> bool {
+    CANCELLED.swap(true, Ordering::SeqCst)
+}
+
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Bail out if an interrupt was received.
+pub fn check() -> Result<()> {
+    if is_cancelled() {
+        anyhow::bail!("Cancelled, no changes written");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_cancelled, request, reset};
+
+    #[test]
+    fn reset_clears_a_previous_request() {
+        reset();
+        assert!(!request());
+        assert!(request());
+        assert!(is_cancelled());
+
+        reset();
+        assert!(!is_cancelled());
+    }
+}
@@ -1 +1,2 @@
 pub mod arcgis;
+pub mod cancel;
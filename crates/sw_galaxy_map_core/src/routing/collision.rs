@@ -5,7 +5,54 @@ pub struct Obstacle {
     pub id: i64,
     pub name: String,
     pub center: Point,
+    /// Radius, or the X semi-axis when `radius_y` is set.
     pub radius: f64,
+    /// Y semi-axis: makes the obstacle an axis-aligned ellipse (`None` = circle).
+    pub radius_y: Option<f64>,
+}
+
+impl Obstacle {
+    /// Factor mapping the ellipse onto a circle of `radius` by scaling Y (1 for circles).
+    fn y_scale(&self) -> f64 {
+        match self.radius_y {
+            Some(ry) if ry > 0.0 && ry != self.radius => self.radius / ry,
+            _ => 1.0,
+        }
+    }
+
+    /// Largest semi-axis (the radius for circles).
+    pub fn extent(&self) -> f64 {
+        self.radius_y.map_or(self.radius, |ry| self.radius.max(ry))
+    }
+
+    /// Same obstacle with both semi-axes grown by `by`.
+    pub fn inflated(&self, by: f64) -> Obstacle {
+        Obstacle {
+            radius: self.radius + by,
+            radius_y: self.radius_y.map(|ry| ry + by),
+            ..self.clone()
+        }
+    }
+
+    /// Closest approach of segment A→B to the center.
+    ///
+    /// `t` and `q` are in map space; for an ellipse `dist` is measured after scaling Y
+    /// so that it compares against `radius` exactly like a circle's distance does.
+    pub fn closest_point(&self, a: Point, b: Point) -> ClosestPoint {
+        let k = self.y_scale();
+        if k == 1.0 {
+            return closest_point_on_segment(self.center, a, b);
+        }
+
+        let c = self.center;
+        let scale = |p: Point| Point::new(p.x, c.y + (p.y - c.y) * k);
+        let cp = closest_point_on_segment(c, scale(a), scale(b));
+        ClosestPoint {
+            t: cp.t,
+            q: add(a, mul(sub(b, a), cp.t)),
+            dist: cp.dist,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -40,11 +87,15 @@ pub fn closest_point_on_segment(p: Point, a: Point, b: Point) -> ClosestPoint {
     ClosestPoint { t, q, dist: d }
 }
 
-pub fn first_collision_on_segment(a: Point, b: Point, obstacles: &[Obstacle]) -> Option<Hit> {
+pub fn first_collision_on_segment<'o>(
+    a: Point,
+    b: Point,
+    obstacles: impl IntoIterator<Item = &'o Obstacle>,
+) -> Option<Hit> {
     let mut best: Option<Hit> = None;
 
     for o in obstacles {
-        let cp = closest_point_on_segment(o.center, a, b);
+        let cp = o.closest_point(a, b);
 
         // stessa regola: ignora endpoint collision
         const EPS_T: f64 = 1e-9;
@@ -56,7 +107,7 @@ pub fn first_collision_on_segment(a: Point, b: Point, obstacles: &[Obstacle]) ->
             let h = Hit {
                 obstacle_id: o.id,
                 obstacle_center: o.center,
-                obstacle_radius: o.radius,
+                obstacle_radius: o.extent(),
                 closest: cp,
             };
 
@@ -74,7 +125,11 @@ pub fn first_collision_on_segment(a: Point, b: Point, obstacles: &[Obstacle]) ->
     best
 }
 
-pub fn is_segment_safe(a: Point, b: Point, obstacles: &[Obstacle]) -> bool {
+pub fn is_segment_safe<'o>(
+    a: Point,
+    b: Point,
+    obstacles: impl IntoIterator<Item = &'o Obstacle>,
+) -> bool {
     for o in obstacles {
         if interior_collision_on_segment(a, b, o) {
             return false;
@@ -83,6 +138,67 @@ pub fn is_segment_safe(a: Point, b: Point, obstacles: &[Obstacle]) -> bool {
     true
 }
 
+/// Obstacles sorted by center X, so a segment query only scans the obstacles whose
+/// center can reach the segment's bounding box.
+///
+/// Queries return obstacles in input order, so callers see the same sequence (and
+/// the same tie-breaks and float sums) as a scan over the whole slice.
+#[derive(Debug, Clone)]
+pub struct ObstacleIndex<'a> {
+    obstacles: &'a [Obstacle],
+    by_x: Vec<usize>,
+    max_radius: f64,
+}
+
+impl<'a> ObstacleIndex<'a> {
+    pub fn new(obstacles: &'a [Obstacle]) -> Self {
+        let mut by_x: Vec<usize> = (0..obstacles.len()).collect();
+        by_x.sort_by(|&i, &j| obstacles[i].center.x.total_cmp(&obstacles[j].center.x));
+        let max_radius = obstacles.iter().map(Obstacle::extent).fold(0.0, f64::max);
+        Self {
+            obstacles,
+            by_x,
+            max_radius,
+        }
+    }
+
+    /// Index that returns every obstacle for every query (reference for tests).
+    #[cfg(test)]
+    pub(crate) fn brute_force(obstacles: &'a [Obstacle]) -> Self {
+        Self {
+            max_radius: f64::INFINITY,
+            ..Self::new(obstacles)
+        }
+    }
+
+    pub fn obstacles(&self) -> &'a [Obstacle] {
+        self.obstacles
+    }
+
+    /// Obstacles whose disc, grown by `reach`, may touch segment A→B; a superset
+    /// of the exact answer, in input order.
+    pub fn near_segment(&self, a: Point, b: Point, reach: f64) -> Vec<&'a Obstacle> {
+        let pad = self.max_radius + reach.max(0.0);
+        let (min_x, max_x) = (a.x.min(b.x) - pad, a.x.max(b.x) + pad);
+        let (min_y, max_y) = (a.y.min(b.y) - pad, a.y.max(b.y) + pad);
+
+        let x_of = |i: &usize| self.obstacles[*i].center.x;
+        let lo = self.by_x.partition_point(|i| x_of(i) < min_x);
+        let hi = self.by_x.partition_point(|i| x_of(i) <= max_x);
+
+        let mut hits: Vec<usize> = self.by_x[lo..hi.max(lo)]
+            .iter()
+            .copied()
+            .filter(|&i| {
+                let y = self.obstacles[i].center.y;
+                y >= min_y && y <= max_y
+            })
+            .collect();
+        hits.sort_unstable();
+        hits.into_iter().map(|i| &self.obstacles[i]).collect()
+    }
+}
+
 pub fn proximity_penalty_for_segment(
     a: Point,
     b: Point,
@@ -97,7 +213,7 @@ pub fn proximity_penalty_for_segment(
     let mut pen = 0.0;
 
     for o in obstacles {
-        let cp = closest_point_on_segment(o.center, a, b);
+        let cp = o.closest_point(a, b);
         let warning = o.radius + margin;
 
         // collision handled elsewhere (d < o.radius)
@@ -122,7 +238,7 @@ pub fn proximity_penalty_for_segment(
 pub fn interior_collision_on_segment(a: Point, b: Point, o: &Obstacle) -> bool {
     const EPS_T: f64 = 1e-9;
 
-    let cp = closest_point_on_segment(o.center, a, b);
+    let cp = o.closest_point(a, b);
 
     // collisioni solo su endpoint ammesse
     if cp.t <= EPS_T || cp.t >= 1.0 - EPS_T {
@@ -131,3 +247,85 @@ pub fn interior_collision_on_segment(a: Point, b: Point, o: &Obstacle) -> bool {
 
     cp.dist < o.radius
 }
+
+/// Narrowest gap between the polyline and any obstacle edge (`dist - radius`).
+///
+/// Returns `None` when there is nothing to measure (no obstacles or no segment).
+pub fn min_corridor_clearance(points: &[Point], obstacles: &[Obstacle]) -> Option<f64> {
+    let mut best: Option<f64> = None;
+
+    for seg in points.windows(2) {
+        for o in obstacles {
+            let cp = o.closest_point(seg[0], seg[1]);
+            let gap = cp.dist - o.radius;
+            best = Some(best.map_or(gap, |b: f64| b.min(gap)));
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obstacle(id: i64, x: f64, y: f64, radius: f64) -> Obstacle {
+        Obstacle {
+            id,
+            name: format!("o{}", id),
+            center: Point::new(x, y),
+            radius,
+            radius_y: None,
+        }
+    }
+
+    #[test]
+    fn min_corridor_clearance_takes_narrowest_gap() {
+        let path = [
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 10.0),
+        ];
+        let obstacles = [obstacle(1, 5.0, 3.0, 1.0), obstacle(2, 12.0, 5.0, 1.5)];
+
+        let gap = min_corridor_clearance(&path, &obstacles).expect("gap");
+        assert!((gap - 0.5).abs() < 1e-9);
+
+        assert_eq!(min_corridor_clearance(&path, &[]), None);
+        assert_eq!(min_corridor_clearance(&path[..1], &obstacles), None);
+    }
+
+    #[test]
+    fn ellipse_is_wide_along_x_and_thin_along_y() {
+        let ellipse = Obstacle {
+            radius_y: Some(1.0),
+            ..obstacle(1, 0.0, 0.0, 5.0)
+        };
+        let seg = |ax, ay, bx, by| (Point::new(ax, ay), Point::new(bx, by));
+
+        // Grazing just outside the long (X) axis: safe, though the bounding circle is hit.
+        let (a, b) = seg(-10.0, 1.2, 10.0, 1.2);
+        assert!(is_segment_safe(a, b, [&ellipse]));
+        assert!(!is_segment_safe(a, b, [&obstacle(2, 0.0, 0.0, 5.0)]));
+
+        // Crossing the short (Y) axis, well inside the long one: collides.
+        let (a, b) = seg(4.0, -3.0, 4.0, 3.0);
+        assert!(!is_segment_safe(a, b, [&ellipse]));
+        let hit = first_collision_on_segment(a, b, [&ellipse]).expect("hit");
+        assert_eq!(hit.obstacle_radius, 5.0);
+        assert!((hit.closest.q.x - 4.0).abs() < 1e-9 && hit.closest.q.y.abs() < 1e-9);
+
+        // Past the tip of the long axis: safe again.
+        let (a, b) = seg(5.5, -3.0, 5.5, 3.0);
+        assert!(is_segment_safe(a, b, [&ellipse]));
+
+        // `radius_y: None` (or equal to `radius`) is the plain circle.
+        let (a, b) = seg(-10.0, 4.0, 10.0, 4.0);
+        let round = Obstacle {
+            radius_y: Some(5.0),
+            ..obstacle(3, 0.0, 0.0, 5.0)
+        };
+        assert!(!is_segment_safe(a, b, [&round]));
+        assert!(!is_segment_safe(a, b, [&obstacle(4, 0.0, 0.0, 5.0)]));
+    }
+}
@@ -21,8 +21,8 @@ use crate::db::queries;
 use crate::model::RouteLoaded;
 use crate::routing::geometry::{Point, dist as geom_dist, polyline_length_waypoints_parsec};
 use crate::routing::hyperspace::{
-    DetourPenaltyParams, GalacticRegion, detour_penalty_multiplier, estimate_travel_time_hours,
-    extract_galactic_region,
+    CompressionTable, DetourPenaltyParams, GalacticRegion, detour_penalty_multiplier,
+    estimate_travel_time_hours, extract_galactic_region,
 };
 
 /// Region blending policy used to derive the base compression factor from the
@@ -79,6 +79,8 @@ impl RouteEtaEstimate {
 /// - invalid numeric inputs are provided
 /// - endpoint planets cannot be loaded
 /// - route/direct geometry is degenerate
+///
+/// Region factors come from `table` (see [`CompressionTable::default`] for the built-ins).
 pub fn estimate_route_eta(
     con: &Connection,
     loaded: &RouteLoaded,
@@ -86,6 +88,7 @@ pub fn estimate_route_eta(
     blend: RegionBlend,
     detour_count_base: f64,
     severity_k: f64,
+    table: &CompressionTable,
 ) -> Option<RouteEtaEstimate> {
     if loaded.waypoints.len() < 2 {
         return None;
@@ -138,8 +141,8 @@ pub fn estimate_route_eta(
     let from_region = extract_galactic_region(&from_planet).unwrap_or(GalacticRegion::OuterRim);
     let to_region = extract_galactic_region(&to_planet).unwrap_or(GalacticRegion::OuterRim);
 
-    let cf_from = from_region.base_compression_factor();
-    let cf_to = to_region.base_compression_factor();
+    let cf_from = table.factor(from_region);
+    let cf_to = table.factor(to_region);
 
     let base_compression_factor = match blend {
         RegionBlend::Avg => (cf_from + cf_to) / 2.0,
@@ -11,13 +11,18 @@
 //!   space distance, and is typically derived from the galactic region.
 //! - Detours can further reduce the effective compression factor via a penalty
 //!   multiplier.
+//! - Per-region factors can be overridden at runtime with a [`CompressionTable`].
+
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 use crate::model::Planet;
 
 /// Ordered from most internal to most external.
 ///
-/// Values (base compression factors) are user-provided and are intended to be
-/// tuned without recompiling (e.g., via config) in future iterations.
+/// Values (base compression factors) are built in; a [`CompressionTable`] can
+/// override them without recompiling.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum GalacticRegion {
     DeepCore,
@@ -72,6 +77,56 @@ impl GalacticRegion {
     }
 }
 
+/// Per-region compression factors: built-in defaults plus runtime overrides.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompressionTable {
+    overrides: BTreeMap<GalacticRegion, f64>,
+}
+
+impl CompressionTable {
+    /// Compression factor for `region` (override first, built-in default otherwise).
+    pub fn factor(&self, region: GalacticRegion) -> f64 {
+        self.overrides
+            .get(&region)
+            .copied()
+            .unwrap_or_else(|| region.base_compression_factor())
+    }
+
+    /// Parses a JSON object mapping region names to factors, e.g.
+    /// `{ "outer rim": 20.0, "core worlds": 50 }`. Regions left out keep their default.
+    pub fn from_json_str(s: &str) -> Result<Self> {
+        let raw: HashMap<String, f64> = serde_json::from_str(s)
+            .context("Invalid compression config (expected a JSON object)")?;
+
+        let mut overrides = BTreeMap::new();
+        for (name, factor) in raw {
+            let Some(region) = GalacticRegion::parse(&name) else {
+                bail!("Unknown galactic region in compression config: '{}'", name);
+            };
+            if !factor.is_finite() || factor <= 0.0 {
+                bail!(
+                    "Compression factor for '{}' must be a finite number > 0",
+                    name
+                );
+            }
+            overrides.insert(region, factor);
+        }
+
+        Ok(Self { overrides })
+    }
+
+    /// Loads the table from `path`, or returns the built-in defaults when `None`.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read compression config: {}", path.display()))?;
+        Self::from_json_str(&s)
+            .with_context(|| format!("Failed to load compression config: {}", path.display()))
+    }
+}
+
 pub fn extract_galactic_region(p: &Planet) -> Option<GalacticRegion> {
     parse_first_region(&[
         p.c_region.as_deref(),
@@ -204,10 +259,14 @@ pub fn estimate_travel_time_hours(
 }
 
 /// Convenience helper: derive an effective compression factor from a region
-/// and detour penalty.
-pub fn effective_compression_factor(region: GalacticRegion, detour_multiplier: f64) -> f64 {
+/// (looked up in `table`) and detour penalty.
+pub fn effective_compression_factor(
+    region: GalacticRegion,
+    detour_multiplier: f64,
+    table: &CompressionTable,
+) -> f64 {
     assert!(detour_multiplier > 0.0, "detour_multiplier must be > 0");
-    region.base_compression_factor() * detour_multiplier
+    table.factor(region) * detour_multiplier
 }
 
 #[cfg(test)]
@@ -260,9 +319,52 @@ mod tests {
         let distance = 14_757.761;
         let region = GalacticRegion::OuterRim;
         let detour_mult = 0.85;
-        let cf = effective_compression_factor(region, detour_mult);
+        let cf = effective_compression_factor(region, detour_mult, &CompressionTable::default());
         let hours = estimate_travel_time_hours(distance, cf, 1.0);
         // 14757.761 / (18*0.85) ≈ 964.6
         assert!((hours - 964.6).abs() < 1.0);
     }
+
+    #[test]
+    fn compression_override_file_changes_travel_time() {
+        let path = std::env::temp_dir().join(format!(
+            "sw_galaxy_map_compression_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, r#"{ "Outer Rim": 36.0 }"#).expect("write config");
+        let table = CompressionTable::load(Some(&path));
+        let _ = std::fs::remove_file(&path);
+        let table = table.expect("load config");
+
+        let defaults = CompressionTable::load(None).expect("defaults");
+        assert_eq!(defaults, CompressionTable::default());
+
+        let region = GalacticRegion::OuterRim;
+        let base = estimate_travel_time_hours(
+            720.0,
+            effective_compression_factor(region, 1.0, &defaults),
+            1.0,
+        );
+        let tuned = estimate_travel_time_hours(
+            720.0,
+            effective_compression_factor(region, 1.0, &table),
+            1.0,
+        );
+        assert!((base - 40.0).abs() < 1e-9);
+        assert!(
+            (tuned - 20.0).abs() < 1e-9,
+            "doubling the factor halves the time"
+        );
+
+        // Regions left out keep their built-in factor.
+        assert_eq!(table.factor(GalacticRegion::CoreWorlds), 45.0);
+    }
+
+    #[test]
+    fn compression_config_rejects_bad_entries() {
+        assert!(CompressionTable::from_json_str(r#"{ "Nowhere": 10.0 }"#).is_err());
+        assert!(CompressionTable::from_json_str(r#"{ "Mid Rim": 0.0 }"#).is_err());
+        assert!(CompressionTable::from_json_str("[1, 2]").is_err());
+        assert!(CompressionTable::load(Some(Path::new("/nonexistent/cf.json"))).is_err());
+    }
 }
@@ -2,6 +2,7 @@ pub mod collision;
 pub mod eta;
 pub mod geometry;
 pub mod hyperspace;
+pub mod obstacle_cache;
 pub mod route_debug;
 pub mod router;
 pub mod sublight;
@@ -0,0 +1,283 @@
+use crate::db::queries::list_routing_obstacles_in_bbox;
+use crate::model::RoutingObstacleRow;
+use anyhow::Result;
+use rusqlite::Connection;
+use std::collections::VecDeque;
+
+/// Grid step (parsec) used to quantize cached bounding boxes.
+pub const DEFAULT_CACHE_CELL: f64 = 50.0;
+
+/// Max number of quantized boxes kept in memory.
+pub const DEFAULT_CACHE_CAPACITY: usize = 16;
+
+/// Bounding box snapped outward to the cache grid, in cell units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellBox {
+    min_x: i64,
+    max_x: i64,
+    min_y: i64,
+    max_y: i64,
+}
+
+impl CellBox {
+    fn contains(&self, other: &CellBox) -> bool {
+        self.min_x <= other.min_x
+            && self.max_x >= other.max_x
+            && self.min_y <= other.min_y
+            && self.max_y >= other.max_y
+    }
+}
+
+struct Entry {
+    cells: CellBox,
+    safety_bits: u64,
+    /// Every obstacle inside `cells` (never truncated by a limit).
+    rows: Vec<RoutingObstacleRow>,
+}
+
+/// In-memory cache for [`list_routing_obstacles_in_bbox`] across consecutive
+/// route computations.
+///
+/// Requests are widened to a quantized box, fetched once, then filtered back to
+/// the exact bbox, so callers get the same rows (and order) as the direct query.
+/// Boxes too dense to fetch completely within `limit` bypass the cache.
+pub struct ObstacleCache {
+    cell: f64,
+    capacity: usize,
+    entries: VecDeque<Entry>,
+    hits: usize,
+    misses: usize,
+}
+
+impl Default for ObstacleCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CELL, DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+impl ObstacleCache {
+    pub fn new(cell: f64, capacity: usize) -> Self {
+        Self {
+            cell,
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    fn floor_cell(&self, v: f64) -> i64 {
+        let mut c = (v / self.cell).floor() as i64;
+        // Guard against the division rounding up across a cell boundary.
+        if c as f64 * self.cell > v {
+            c -= 1;
+        }
+        c
+    }
+
+    fn ceil_cell(&self, v: f64) -> i64 {
+        let mut c = (v / self.cell).ceil() as i64;
+        if (c as f64 * self.cell) < v {
+            c += 1;
+        }
+        c
+    }
+
+    /// Cached equivalent of [`list_routing_obstacles_in_bbox`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn routing_obstacles(
+        &mut self,
+        con: &Connection,
+        min_x: f64,
+        max_x: f64,
+        min_y: f64,
+        max_y: f64,
+        limit: usize,
+        safety: f64,
+    ) -> Result<Vec<RoutingObstacleRow>> {
+        let usable = [min_x, max_x, min_y, max_y, self.cell]
+            .iter()
+            .all(|v| v.is_finite())
+            && self.cell > 0.0;
+        if !usable {
+            return list_routing_obstacles_in_bbox(con, min_x, max_x, min_y, max_y, limit, safety);
+        }
+
+        let cells = CellBox {
+            min_x: self.floor_cell(min_x),
+            max_x: self.ceil_cell(max_x),
+            min_y: self.floor_cell(min_y),
+            max_y: self.ceil_cell(max_y),
+        };
+        let safety_bits = safety.to_bits();
+
+        let filter = |rows: &[RoutingObstacleRow]| -> Vec<RoutingObstacleRow> {
+            rows.iter()
+                .filter(|r| r.x >= min_x && r.x <= max_x && r.y >= min_y && r.y <= max_y)
+                .take(limit)
+                .cloned()
+                .collect()
+        };
+
+        if let Some(pos) = self
+            .entries
+            .iter()
+            .position(|e| e.safety_bits == safety_bits && e.cells.contains(&cells))
+        {
+            self.hits += 1;
+            // Most recently used entries live at the back.
+            let entry = self.entries.remove(pos).expect("position is in range");
+            let out = filter(&entry.rows);
+            self.entries.push_back(entry);
+            return Ok(out);
+        }
+
+        self.misses += 1;
+
+        let fetch_limit = limit.saturating_add(1);
+        let rows = list_routing_obstacles_in_bbox(
+            con,
+            cells.min_x as f64 * self.cell,
+            cells.max_x as f64 * self.cell,
+            cells.min_y as f64 * self.cell,
+            cells.max_y as f64 * self.cell,
+            fetch_limit,
+            safety,
+        )?;
+
+        if rows.len() >= fetch_limit {
+            // Truncated: filtering would not match the exact query.
+            return list_routing_obstacles_in_bbox(con, min_x, max_x, min_y, max_y, limit, safety);
+        }
+
+        let out = filter(&rows);
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry {
+            cells,
+            safety_bits,
+            rows,
+        });
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing::collision::Obstacle;
+    use crate::routing::geometry::Point;
+    use crate::routing::router::{RouteOptions, compute_route};
+
+    fn setup_db() -> Connection {
+        let con = Connection::open_in_memory().expect("in-memory sqlite");
+        con.execute_batch(
+            r#"
+            CREATE TABLE planets (
+                FID INTEGER PRIMARY KEY,
+                Planet TEXT NOT NULL,
+                X REAL NOT NULL,
+                Y REAL NOT NULL
+            );
+            "#,
+        )
+        .expect("schema setup");
+
+        // Deterministic scatter over a 400x400 area.
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 33) as f64) / ((1u64 << 31) as f64)
+        };
+        for fid in 1..=300 {
+            let x = next() * 400.0 - 200.0;
+            let y = next() * 400.0 - 200.0;
+            con.execute(
+                "INSERT INTO planets (FID, Planet, X, Y) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![fid, format!("P{:04}", fid), x, y],
+            )
+            .expect("insert planet");
+        }
+        con
+    }
+
+    fn fids(rows: &[RoutingObstacleRow]) -> Vec<i64> {
+        rows.iter().map(|r| r.fid).collect()
+    }
+
+    fn to_obstacles(rows: &[RoutingObstacleRow]) -> Vec<Obstacle> {
+        rows.iter()
+            .map(|r| Obstacle {
+                id: r.fid,
+                name: r.planet.clone(),
+                center: Point::new(r.x, r.y),
+                radius: r.radius,
+                radius_y: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cached_obstacles_and_routes_match_uncached() {
+        let con = setup_db();
+        let mut cache = ObstacleCache::default();
+
+        let legs: [((f64, f64), (f64, f64)); 5] = [
+            ((-120.0, -110.0), (-40.0, -30.0)),
+            ((-115.0, -105.0), (-45.0, -20.0)),
+            ((-40.0, -30.0), (-118.0, -108.0)),
+            ((60.0, 70.0), (150.0, 120.0)),
+            ((65.0, 72.0), (140.0, 118.0)),
+        ];
+        let margin = 10.0;
+
+        for (a, b) in legs {
+            let (min_x, max_x) = (a.0.min(b.0) - margin, a.0.max(b.0) + margin);
+            let (min_y, max_y) = (a.1.min(b.1) - margin, a.1.max(b.1) + margin);
+
+            let direct =
+                list_routing_obstacles_in_bbox(&con, min_x, max_x, min_y, max_y, 8000, 2.0)
+                    .expect("direct query");
+            let cached = cache
+                .routing_obstacles(&con, min_x, max_x, min_y, max_y, 8000, 2.0)
+                .expect("cached query");
+            assert_eq!(fids(&direct), fids(&cached));
+
+            let start = Point::new(a.0, a.1);
+            let end = Point::new(b.0, b.1);
+            let r1 = compute_route(start, end, &to_obstacles(&direct), RouteOptions::default())
+                .expect("route (direct)");
+            let r2 = compute_route(start, end, &to_obstacles(&cached), RouteOptions::default())
+                .expect("route (cached)");
+            assert_eq!(r1.waypoints, r2.waypoints);
+            assert_eq!(r1.length, r2.length);
+        }
+
+        assert!(cache.hits() > 0, "overlapping legs should reuse the cache");
+    }
+
+    #[test]
+    fn truncated_boxes_bypass_the_cache() {
+        let con = setup_db();
+        let mut cache = ObstacleCache::default();
+
+        let direct = list_routing_obstacles_in_bbox(&con, -50.0, 50.0, -50.0, 50.0, 3, 2.0)
+            .expect("direct query");
+        let cached = cache
+            .routing_obstacles(&con, -50.0, 50.0, -50.0, 50.0, 3, 2.0)
+            .expect("cached query");
+
+        assert_eq!(fids(&direct), fids(&cached));
+        assert!(cache.entries.is_empty());
+    }
+}
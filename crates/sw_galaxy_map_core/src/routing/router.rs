@@ -1,5 +1,7 @@
 use anyhow::{Result, bail};
+use serde::Serialize;
 
+use crate::domain::Metric;
 use crate::routing::collision::*;
 use crate::routing::geometry::*;
 use crate::routing::route_debug::debug_failed_detour;
@@ -41,10 +43,34 @@ pub struct DetourDecision {
     pub tries_exhausted: bool,
 }
 
+/// Default cap on the polyline size (start and end included).
+pub const DEFAULT_MAX_WAYPOINTS: usize = 64;
+
+/// Default falloff of the proximity penalty (quadratic).
+pub const DEFAULT_PROXIMITY_EXPONENT: f64 = 2.0;
+
+/// Accepted `proximity_exponent` values; anything outside is clamped.
+pub const PROXIMITY_EXPONENT_RANGE: std::ops::RangeInclusive<f64> = 1.0..=4.0;
+
+/// Clamps `exponent` to [`PROXIMITY_EXPONENT_RANGE`]; a non-finite value falls back
+/// to [`DEFAULT_PROXIMITY_EXPONENT`].
+pub fn clamp_proximity_exponent(exponent: f64) -> f64 {
+    if exponent.is_finite() {
+        exponent.clamp(
+            *PROXIMITY_EXPONENT_RANGE.start(),
+            *PROXIMITY_EXPONENT_RANGE.end(),
+        )
+    } else {
+        DEFAULT_PROXIMITY_EXPONENT
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct RouteOptions {
     pub clearance: f64,
     pub max_iters: usize,
+    /// Upper bound on `waypoints.len()`; routing fails once a detour would exceed it.
+    pub max_waypoints: usize,
     pub max_offset_tries: usize,
     pub offset_growth: f64,
 
@@ -55,6 +81,18 @@ pub struct RouteOptions {
     // proximity scoring
     pub proximity_weight: f64, // intensità penalità
     pub proximity_margin: f64, // fascia extra oltre il raggio (warning band)
+    /// Falloff of the proximity penalty, `weight * x^exponent` with `x` in (0, 1]
+    /// across the warning band. Clamped to [`PROXIMITY_EXPONENT_RANGE`].
+    pub proximity_exponent: f64,
+
+    /// Keep expanding the offset until both sides of the obstacle have a valid
+    /// candidate, then pick the side with the lower cost including the turns at the
+    /// neighbouring waypoints. Costs up to `max_offset_tries` times more candidate
+    /// evaluations per detour than the default first-valid-offset search.
+    pub explore_both_sides: bool,
+
+    /// Metric for the reported length and the base (length) term of detour scores.
+    pub metric: Metric,
 }
 
 impl Default for RouteOptions {
@@ -62,12 +100,16 @@ impl Default for RouteOptions {
         Self {
             clearance: 0.2,
             max_iters: 32,
+            max_waypoints: DEFAULT_MAX_WAYPOINTS,
             max_offset_tries: 6,
             offset_growth: 1.4,
             turn_weight: 0.8, // taratura iniziale
             back_weight: 3.0, // più forte del turn
             proximity_weight: 1.5,
             proximity_margin: 0.5,
+            proximity_exponent: DEFAULT_PROXIMITY_EXPONENT,
+            explore_both_sides: false,
+            metric: Metric::Euclidean,
         }
     }
 }
@@ -97,6 +139,31 @@ impl CandidateScore {
     }
 }
 
+/// Detour candidate considered during one router iteration.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceCandidate {
+    pub try_index: usize,
+    pub offset: f64,
+    pub x: f64,
+    pub y: f64,
+    /// `None` when the candidate collides with an obstacle.
+    pub score: Option<f64>,
+}
+
+/// State of one router iteration, as reported to [`compute_route_traced`] callers.
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteTraceEvent {
+    pub iteration: usize,
+    pub segment_index: usize,
+    pub obstacle_id: i64,
+    pub obstacle_name: String,
+    pub candidates: Vec<TraceCandidate>,
+    /// Chosen waypoint (`None` when no candidate was valid and routing fails).
+    pub chosen_x: Option<f64>,
+    pub chosen_y: Option<f64>,
+    pub chosen_score: Option<f64>,
+}
+
 fn detour_candidates(a: Point, b: Point, hit: &Hit, offset: f64) -> Vec<Point> {
     let dir = normalize(sub(b, a));
     let n = perp(dir);
@@ -140,29 +207,25 @@ fn evaluate_candidate(
     a: Point,
     w: Point,
     b: Point,
-    obstacles: &[Obstacle],
+    index: &ObstacleIndex<'_>,
     opts: RouteOptions,
     exclude_obstacle_id: Option<i64>,
 ) -> Option<CandidateScore> {
-    if !is_segment_safe(a, w, obstacles) {
+    // One lookup per leg covers both the collision check and the proximity band.
+    let near_aw = index.near_segment(a, w, opts.proximity_margin);
+    if !is_segment_safe(a, w, near_aw.iter().copied()) {
         return None;
     }
-    if !is_segment_safe(w, b, obstacles) {
+    let near_wb = index.near_segment(w, b, opts.proximity_margin);
+    if !is_segment_safe(w, b, near_wb.iter().copied()) {
         return None;
     }
 
     // --- base length
-    let base = dist(a, w) + dist(w, b);
+    let base = opts.metric.distance(a, w) + opts.metric.distance(w, b);
 
     // --- turn penalty
-    let u1 = normalize(sub(w, a));
-    let u2 = normalize(sub(b, w));
-
-    let mut turn = 0.0;
-    if !(u1.x == 0.0 && u1.y == 0.0 || u2.x == 0.0 && u2.y == 0.0) {
-        let cos_theta = clamp(dot(u1, u2), -1.0, 1.0);
-        turn = opts.turn_weight * (1.0 - cos_theta);
-    }
+    let turn = turn_penalty(a, w, b, opts.turn_weight);
 
     // --- backtracking penalty
     let ab_dir = normalize(sub(b, a));
@@ -178,16 +241,18 @@ fn evaluate_candidate(
     let proximity = proximity_penalty_for_segment(
         a,
         w,
-        obstacles,
+        &near_aw,
         opts.proximity_margin,
         opts.proximity_weight,
+        opts.proximity_exponent,
         exclude_obstacle_id,
     ) + proximity_penalty_for_segment(
         w,
         b,
-        obstacles,
+        &near_wb,
         opts.proximity_margin,
         opts.proximity_weight,
+        opts.proximity_exponent,
         exclude_obstacle_id,
     );
 
@@ -199,11 +264,85 @@ fn evaluate_candidate(
     })
 }
 
+/// Penalty for the turn at `q` on the path P→Q→R (0 for a straight line).
+fn turn_penalty(p: Point, q: Point, r: Point, weight: f64) -> f64 {
+    let u1 = normalize(sub(q, p));
+    let u2 = normalize(sub(r, q));
+
+    if u1.x == 0.0 && u1.y == 0.0 || u2.x == 0.0 && u2.y == 0.0 {
+        return 0.0;
+    }
+    let cos_theta = clamp(dot(u1, u2), -1.0, 1.0);
+    weight * (1.0 - cos_theta)
+}
+
+/// Side of segment A→B that `w` lies on: 0 = left (or on the line), 1 = right.
+fn side_of(a: Point, b: Point, w: Point) -> usize {
+    if dot(sub(w, a), perp(sub(b, a))) >= 0.0 {
+        0
+    } else {
+        1
+    }
+}
+
+type DetourPick = (Point, CandidateScore, f64, usize, bool);
+// (waypoint, score, offset_used, try_index, exhausted_at_selection)
+
+/// `explore_both_sides`: cheaper of the two per-side winners, counting the turns
+/// its detour forces at A (coming from `prev`) and at B (heading to `next`).
+fn pick_side(
+    sides: [Option<DetourPick>; 2],
+    prev: Option<Point>,
+    a: Point,
+    b: Point,
+    next: Option<Point>,
+    opts: RouteOptions,
+) -> Option<DetourPick> {
+    let cost = |p: &DetourPick| {
+        let w = p.0;
+        p.1.total()
+            + prev.map_or(0.0, |pv| turn_penalty(pv, a, w, opts.turn_weight))
+            + next.map_or(0.0, |nx| turn_penalty(w, b, nx, opts.turn_weight))
+    };
+    match sides {
+        [Some(l), Some(r)] => Some(if cost(&r) < cost(&l) { r } else { l }),
+        [l, r] => l.or(r),
+    }
+}
+
 pub fn compute_route(
     start: Point,
     end: Point,
     obstacles: &[Obstacle],
     opts: RouteOptions,
+) -> Result<Route> {
+    compute_route_inner(start, end, &ObstacleIndex::new(obstacles), opts, None)
+}
+
+/// Same as [`compute_route`], calling `on_iteration` once per detour iteration
+/// with the colliding segment, every candidate evaluated and the one chosen.
+pub fn compute_route_traced(
+    start: Point,
+    end: Point,
+    obstacles: &[Obstacle],
+    opts: RouteOptions,
+    on_iteration: &mut dyn FnMut(&RouteTraceEvent),
+) -> Result<Route> {
+    compute_route_inner(
+        start,
+        end,
+        &ObstacleIndex::new(obstacles),
+        opts,
+        Some(on_iteration),
+    )
+}
+
+fn compute_route_inner(
+    start: Point,
+    end: Point,
+    index: &ObstacleIndex<'_>,
+    opts: RouteOptions,
+    on_iteration: Option<&mut dyn FnMut(&RouteTraceEvent)>,
 ) -> Result<Route> {
     if start == end {
         return Ok(Route {
@@ -214,6 +353,64 @@ pub fn compute_route(
         });
     }
 
+    repair_polyline(vec![start, end], Vec::new(), index, opts, on_iteration)
+}
+
+/// Like [`compute_route`], but starts from `seed` (e.g. a previously persisted
+/// route between the same endpoints) instead of the straight segment.
+///
+/// Interior seed waypoints lying inside an obstacle are dropped first, together
+/// with their detour records; the detour loop then only repairs the segments that
+/// still collide. The returned `iterations` counts the repair iterations alone.
+pub fn compute_route_seeded(
+    seed: Route,
+    obstacles: &[Obstacle],
+    opts: RouteOptions,
+    on_iteration: Option<&mut dyn FnMut(&RouteTraceEvent)>,
+) -> Result<Route> {
+    let Route {
+        mut waypoints,
+        mut detours,
+        ..
+    } = seed;
+    if waypoints.len() < 2 {
+        bail!("Seed route needs at least 2 waypoints");
+    }
+
+    let inside = |p: &Point| {
+        obstacles
+            .iter()
+            .any(|o| o.closest_point(*p, *p).dist < o.radius)
+    };
+    let last = waypoints.len() - 1;
+    let mut idx = 0usize;
+    waypoints.retain(|p| {
+        let keep = idx == 0 || idx == last || !inside(p);
+        idx += 1;
+        keep
+    });
+    detours.retain(|d| waypoints.contains(&d.waypoint));
+
+    repair_polyline(
+        waypoints,
+        detours,
+        &ObstacleIndex::new(obstacles),
+        opts,
+        on_iteration,
+    )
+}
+
+/// Detour loop shared by fresh and seeded computations: repeatedly splits the
+/// first colliding segment of `waypoints` until the polyline is clear.
+fn repair_polyline(
+    mut waypoints: Vec<Point>,
+    mut detours: Vec<DetourDecision>,
+    index: &ObstacleIndex<'_>,
+    opts: RouteOptions,
+    mut on_iteration: Option<&mut dyn FnMut(&RouteTraceEvent)>,
+) -> Result<Route> {
+    let obstacles = index.obstacles();
+
     // Guardrails: avoid degenerate configs
     if opts.max_offset_tries == 0 {
         bail!("Invalid RouteOptions: max_offset_tries must be >= 1");
@@ -221,9 +418,18 @@ pub fn compute_route(
     if opts.offset_growth <= 1.0 {
         bail!("Invalid RouteOptions: offset_growth must be > 1.0");
     }
+    if opts.max_waypoints < 2 {
+        bail!("Invalid RouteOptions: max_waypoints must be >= 2");
+    }
+
+    if waypoints.len() > opts.max_waypoints {
+        bail!(
+            "Seed route has {} waypoints, exceeding max_waypoints={}",
+            waypoints.len(),
+            opts.max_waypoints
+        );
+    }
 
-    let mut waypoints = vec![start, end];
-    let mut detours: Vec<DetourDecision> = Vec::new();
     let mut iterations = 0usize;
 
     while iterations < opts.max_iters {
@@ -234,7 +440,7 @@ pub fn compute_route(
             let a = waypoints[seg_idx];
             let b = waypoints[seg_idx + 1];
 
-            if let Some(hit) = first_collision_on_segment(a, b, obstacles) {
+            if let Some(hit) = first_collision_on_segment(a, b, index.near_segment(a, b, 0.0)) {
                 first_collision = Some((seg_idx, hit));
                 break;
             }
@@ -242,7 +448,7 @@ pub fn compute_route(
 
         // No collisions -> done
         if first_collision.is_none() {
-            let length: f64 = waypoints.windows(2).map(|w| dist(w[0], w[1])).sum();
+            let length = opts.metric.polyline_length(&waypoints);
             return Ok(Route {
                 waypoints,
                 length,
@@ -258,23 +464,43 @@ pub fn compute_route(
         // 2) Find best detour candidate (with expanding offset)
         let base_offset = hit.obstacle_radius + opts.clearance;
 
-        let mut best: Option<(Point, CandidateScore, f64, usize, bool)> = None;
-        // (waypoint, score, offset_used, try_index, exhausted_at_selection)
+        let mut best: Option<DetourPick> = None;
+        // Per-side winners, only used with `explore_both_sides`.
+        let mut sides: [Option<DetourPick>; 2] = [None, None];
 
         let mut offset = base_offset;
         let mut last_candidates: Vec<Point> = Vec::new();
+        // Only filled when tracing.
+        let mut traced: Vec<TraceCandidate> = Vec::new();
 
         for try_idx in 0..opts.max_offset_tries {
             let candidates = detour_candidates(a, b, &hit, offset);
             last_candidates = candidates.clone();
 
             for w in candidates {
-                let Some(score) =
-                    evaluate_candidate(a, w, b, obstacles, opts, Some(hit.obstacle_id))
-                else {
+                let score = evaluate_candidate(a, w, b, index, opts, Some(hit.obstacle_id));
+                if on_iteration.is_some() {
+                    traced.push(TraceCandidate {
+                        try_index: try_idx,
+                        offset,
+                        x: w.x,
+                        y: w.y,
+                        score: score.as_ref().map(CandidateScore::total),
+                    });
+                }
+                let Some(score) = score else {
                     continue;
                 };
 
+                if opts.explore_both_sides {
+                    let slot = &mut sides[side_of(a, b, w)];
+                    if slot.as_ref().is_none_or(|s| score.total() < s.1.total()) {
+                        let exhausted = (try_idx + 1) == opts.max_offset_tries;
+                        *slot = Some((w, score, offset, try_idx, exhausted));
+                    }
+                    continue;
+                }
+
                 let better = match &best {
                     None => true,
                     Some((_bw, bs, _bo, _bi, _be)) => score.total() < bs.total(),
@@ -288,13 +514,38 @@ pub fn compute_route(
 
             // As soon as we found a valid candidate at this offset,
             // we stop expanding offsets (keep the best among this offset's candidates).
-            if best.is_some() {
+            if best.is_some() || sides.iter().all(Option::is_some) {
                 break;
             }
 
             offset *= opts.offset_growth;
         }
 
+        if opts.explore_both_sides {
+            let prev = seg_idx.checked_sub(1).map(|i| waypoints[i]);
+            let next = waypoints.get(seg_idx + 2).copied();
+            best = pick_side(sides, prev, a, b, next, opts);
+        }
+
+        let obstacle_name = obstacles
+            .iter()
+            .find(|o| o.id == hit.obstacle_id)
+            .map(|o| o.name.clone())
+            .unwrap_or_else(|| "<unknown>".to_string());
+
+        if let Some(cb) = on_iteration.as_mut() {
+            cb(&RouteTraceEvent {
+                iteration: iterations,
+                segment_index: seg_idx,
+                obstacle_id: hit.obstacle_id,
+                obstacle_name: obstacle_name.clone(),
+                candidates: std::mem::take(&mut traced),
+                chosen_x: best.as_ref().map(|b| b.0.x),
+                chosen_y: best.as_ref().map(|b| b.0.y),
+                chosen_score: best.as_ref().map(|b| b.1.total()),
+            });
+        }
+
         let (detour_wp, detour_score, offset_used, try_idx, exhausted) = match best {
             Some(v) => v,
             None => {
@@ -312,12 +563,6 @@ pub fn compute_route(
         let tries_exhausted = exhausted;
 
         // 3) Record detour decision (before inserting)
-        let obstacle_name = obstacles
-            .iter()
-            .find(|o| o.id == hit.obstacle_id)
-            .map(|o| o.name.clone())
-            .unwrap_or_else(|| "<unknown>".to_string());
-
         detours.push(DetourDecision {
             iteration: iterations,
             segment_index: seg_idx,
@@ -343,23 +588,85 @@ pub fn compute_route(
         // 4) Apply detour
         waypoints.insert(seg_idx + 1, detour_wp);
         iterations += 1;
+
+        if waypoints.len() > opts.max_waypoints {
+            bail!(
+                "Route grew to {} waypoints, exceeding max_waypoints={} (obstacle id={} at segment idx={})",
+                waypoints.len(),
+                opts.max_waypoints,
+                hit.obstacle_id,
+                seg_idx
+            );
+        }
     }
 
     bail!("Route computation exceeded max_iters={}", opts.max_iters)
 }
 
+/// Drops interior waypoints lying closer than `min_spacing` to the previous kept
+/// one, but only when the shortcut segment still clears every obstacle.
+///
+/// Endpoints are never moved. Detour records whose waypoint was dropped are
+/// discarded and `length` is recomputed with `metric`. Returns the number of merged
+/// waypoints.
+pub fn merge_close_waypoints(
+    route: &mut Route,
+    min_spacing: f64,
+    obstacles: &[Obstacle],
+    metric: Metric,
+) -> usize {
+    let n = route.waypoints.len();
+    if n < 3 || !min_spacing.is_finite() || min_spacing <= 0.0 {
+        return 0;
+    }
+
+    let pts = &route.waypoints;
+    let safe = |a: Point, b: Point| first_collision_on_segment(a, b, obstacles).is_none();
+
+    let mut kept: Vec<Point> = vec![pts[0]];
+    for i in 1..n - 1 {
+        let last = *kept.last().expect("start is always kept");
+        if dist(last, pts[i]) < min_spacing && safe(last, pts[i + 1]) {
+            continue;
+        }
+        kept.push(pts[i]);
+    }
+
+    // The end point is fixed, so a crowded tail drops the last interior point instead.
+    let end = pts[n - 1];
+    if kept.len() >= 2 {
+        let last = kept[kept.len() - 1];
+        let before = kept[kept.len() - 2];
+        if dist(last, end) < min_spacing && safe(before, end) {
+            kept.pop();
+        }
+    }
+    kept.push(end);
+
+    let merged = n - kept.len();
+    if merged > 0 {
+        route.detours.retain(|d| kept.contains(&d.waypoint));
+        route.length = metric.polyline_length(&kept);
+        route.waypoints = kept;
+    }
+    merged
+}
+
 fn proximity_penalty_for_segment(
     a: Point,
     b: Point,
-    obstacles: &[Obstacle],
+    obstacles: &[&Obstacle],
     margin: f64,
     weight: f64,
+    exponent: f64,
     exclude_id: Option<i64>,
 ) -> f64 {
     if margin <= 0.0 || weight <= 0.0 {
         return 0.0;
     }
 
+    let exponent = clamp_proximity_exponent(exponent);
+
     let mut pen = 0.0;
 
     for o in obstacles {
@@ -367,7 +674,7 @@ fn proximity_penalty_for_segment(
             continue;
         }
 
-        let cp = closest_point_on_segment(o.center, a, b);
+        let cp = o.closest_point(a, b);
         let warning = o.radius + margin;
 
         if cp.dist >= warning {
@@ -382,8 +689,376 @@ fn proximity_penalty_for_segment(
         }
 
         let x = (warning - cp.dist) / margin; // (0..1]
-        pen += weight * x * x; // quadratic growth
+        pen += weight * x.powf(exponent); // quadratic growth by default
     }
 
     pen
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route_through(points: &[(f64, f64)]) -> Route {
+        let waypoints: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        let length = waypoints.windows(2).map(|w| dist(w[0], w[1])).sum();
+        Route {
+            waypoints,
+            length,
+            iterations: 0,
+            detours: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn traced_route_matches_untraced_and_reports_each_detour() {
+        let obstacles = [
+            Obstacle {
+                id: 1,
+                name: "o1".to_string(),
+                center: Point::new(5.0, 0.0),
+                radius: 1.0,
+                radius_y: None,
+            },
+            Obstacle {
+                id: 2,
+                name: "o2".to_string(),
+                center: Point::new(12.0, 0.5),
+                radius: 1.0,
+                radius_y: None,
+            },
+        ];
+        let start = Point::new(0.0, 0.0);
+        let end = Point::new(20.0, 0.0);
+
+        let plain = compute_route(start, end, &obstacles, RouteOptions::default()).expect("route");
+        let mut events = Vec::new();
+        let traced =
+            compute_route_traced(start, end, &obstacles, RouteOptions::default(), &mut |ev| {
+                events.push(ev.clone())
+            })
+            .expect("traced route");
+
+        assert_eq!(plain.waypoints, traced.waypoints);
+        assert_eq!(events.len(), traced.detours.len());
+        for (ev, d) in events.iter().zip(&traced.detours) {
+            assert_eq!(ev.obstacle_id, d.obstacle_id);
+            assert_eq!(ev.chosen_x, Some(d.waypoint.x));
+            assert!(!ev.candidates.is_empty());
+        }
+    }
+
+    #[test]
+    fn max_waypoints_caps_polyline_growth() {
+        let obstacles = [
+            Obstacle {
+                id: 1,
+                name: "o1".to_string(),
+                center: Point::new(5.0, 0.0),
+                radius: 1.0,
+                radius_y: None,
+            },
+            Obstacle {
+                id: 2,
+                name: "o2".to_string(),
+                center: Point::new(12.0, 0.5),
+                radius: 1.0,
+                radius_y: None,
+            },
+        ];
+        let (start, end) = (Point::new(0.0, 0.0), Point::new(20.0, 0.0));
+
+        let full = compute_route(start, end, &obstacles, RouteOptions::default()).expect("route");
+        let n = full.waypoints.len();
+        assert!(n >= 3, "fixture should force a detour");
+
+        let capped = RouteOptions {
+            max_waypoints: n - 1,
+            ..Default::default()
+        };
+        let err = compute_route(start, end, &obstacles, capped).expect_err("cap exceeded");
+        let msg = err.to_string();
+        assert!(msg.contains(&format!("grew to {} waypoints", n)), "{msg}");
+        assert!(!msg.contains("max_iters"), "{msg}");
+
+        let exact = RouteOptions {
+            max_waypoints: n,
+            ..Default::default()
+        };
+        assert!(compute_route(start, end, &obstacles, exact).is_ok());
+    }
+
+    #[test]
+    fn explore_both_sides_can_pick_the_side_found_at_a_larger_offset() {
+        // Off-centre obstacle: the detour below is valid at the first offset but passes
+        // close to a small body; the way round above only clears at the next offset.
+        let obstacles = [
+            Obstacle {
+                id: 1,
+                name: "o1".to_string(),
+                center: Point::new(10.0, 0.3),
+                radius: 1.0,
+                radius_y: None,
+            },
+            Obstacle {
+                id: 2,
+                name: "o2".to_string(),
+                center: Point::new(10.0, -1.0),
+                radius: 0.5,
+                radius_y: None,
+            },
+        ];
+        let (start, end) = (Point::new(0.0, 0.0), Point::new(20.0, 0.0));
+
+        let greedy = compute_route(start, end, &obstacles, RouteOptions::default()).expect("route");
+        let explored = compute_route(
+            start,
+            end,
+            &obstacles,
+            RouteOptions {
+                explore_both_sides: true,
+                ..Default::default()
+            },
+        )
+        .expect("route");
+
+        assert_eq!(greedy.detours.len(), 1);
+        assert_eq!(explored.detours.len(), 1);
+        assert!(greedy.detours[0].waypoint.y < 0.0);
+        assert!(explored.detours[0].waypoint.y > 0.0);
+        assert!(explored.detours[0].score.total() < greedy.detours[0].score.total());
+        assert!(explored.length < greedy.length);
+        assert!(explored.detours[0].tries_used > greedy.detours[0].tries_used);
+    }
+
+    #[test]
+    fn metric_drives_reported_length() {
+        let (start, end) = (Point::new(0.0, 0.0), Point::new(3.0, 4.0));
+        let with = |metric| RouteOptions {
+            metric,
+            ..Default::default()
+        };
+
+        for (metric, expected) in [
+            (Metric::Euclidean, 5.0),
+            (Metric::Manhattan, 7.0),
+            (Metric::Chebyshev, 4.0),
+        ] {
+            let route = compute_route(start, end, &[], with(metric)).expect("route");
+            assert_eq!(route.length, expected, "{:?}", metric);
+        }
+
+        let path = [
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 4.0),
+            Point::new(6.0, 0.0),
+        ];
+        assert_eq!(Metric::Euclidean.polyline_length(&path), 10.0);
+        assert_eq!(Metric::Manhattan.polyline_length(&path), 14.0);
+        assert_eq!(Metric::Chebyshev.polyline_length(&path), 8.0);
+
+        // Detours are still found with circle geometry, whatever the metric.
+        let obstacles = [Obstacle {
+            id: 1,
+            name: "o1".to_string(),
+            center: Point::new(5.0, 0.0),
+            radius: 1.0,
+            radius_y: None,
+        }];
+        let far = Point::new(10.0, 0.0);
+        for metric in [Metric::Manhattan, Metric::Chebyshev] {
+            let route = compute_route(start, far, &obstacles, with(metric)).expect("route");
+            assert_eq!(route.detours.len(), 1);
+            assert_eq!(route.length, metric.polyline_length(&route.waypoints));
+        }
+    }
+
+    #[test]
+    fn seeded_route_reuses_the_seed_and_repairs_new_collisions() {
+        let obstacle = |id, x: f64, y: f64, r| Obstacle {
+            id,
+            name: format!("o{id}"),
+            center: Point::new(x, y),
+            radius: r,
+            radius_y: None,
+        };
+        let (start, end) = (Point::new(0.0, 0.0), Point::new(20.0, 0.0));
+        let mut obstacles = vec![obstacle(1, 5.0, 0.0, 1.0)];
+        let opts = RouteOptions::default();
+
+        let fresh = compute_route(start, end, &obstacles, opts).expect("fresh route");
+        assert_eq!(fresh.detours.len(), 1);
+
+        // Nothing changed: the seed is already clear and is returned as-is.
+        let same =
+            compute_route_seeded(fresh.clone(), &obstacles, opts, None).expect("seeded route");
+        assert_eq!(same.iterations, 0);
+        assert_eq!(same.waypoints, fresh.waypoints);
+        assert_eq!(same.detours.len(), 1);
+
+        // A new obstacle on the last leg only costs the iterations needed to bypass it.
+        obstacles.push(obstacle(2, 15.0, 0.0, 1.0));
+        let repaired =
+            compute_route_seeded(fresh.clone(), &obstacles, opts, None).expect("repaired");
+        assert_eq!(repaired.iterations, 1);
+        assert_eq!(repaired.detours.len(), 2);
+        assert!(repaired.waypoints.contains(&fresh.waypoints[1]));
+        assert!(
+            repaired
+                .waypoints
+                .windows(2)
+                .all(|s| is_segment_safe(s[0], s[1], &obstacles))
+        );
+
+        // A seed waypoint swallowed by an obstacle is dropped along with its detour.
+        let swallowed = vec![obstacle(3, fresh.waypoints[1].x, fresh.waypoints[1].y, 3.0)];
+        let rerouted =
+            compute_route_seeded(fresh.clone(), &swallowed, opts, None).expect("rerouted");
+        assert!(!rerouted.waypoints.contains(&fresh.waypoints[1]));
+        assert!(rerouted.detours.iter().all(|d| d.obstacle_id == 3));
+        assert_eq!(rerouted.waypoints.first(), Some(&start));
+        assert_eq!(rerouted.waypoints.last(), Some(&end));
+    }
+
+    #[test]
+    fn proximity_exponent_shapes_the_penalty_falloff() {
+        let o = Obstacle {
+            id: 1,
+            name: "o1".to_string(),
+            center: Point::new(0.0, 1.0),
+            radius: 0.5,
+            radius_y: None,
+        };
+        let (a, b) = (Point::new(-1.0, 0.0), Point::new(1.0, 0.0));
+        // Closest distance 1.0, warning band 0.5 + 1.0 => x = (1.5 - 1.0) / 1.0 = 0.5.
+        let pen = |exponent| proximity_penalty_for_segment(a, b, &[&o], 1.0, 2.0, exponent, None);
+
+        assert_eq!(pen(1.0), 2.0 * 0.5);
+        assert_eq!(pen(DEFAULT_PROXIMITY_EXPONENT), 2.0 * 0.25);
+        // Out-of-range exponents are clamped to 1.0..=4.0.
+        assert_eq!(pen(0.0), pen(1.0));
+        assert_eq!(pen(10.0), 2.0 * 0.0625);
+        assert_eq!(pen(f64::NAN), pen(DEFAULT_PROXIMITY_EXPONENT));
+    }
+
+    /// Deterministic dense field (LCG) around a clear horizontal lane at y = 500, with
+    /// blockers in the lane: few collisions, but thousands of obstacles to scan.
+    fn dense_field(n: usize, seed: u64) -> Vec<Obstacle> {
+        let mut state = seed;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let mut out = Vec::with_capacity(n);
+        while out.len() < n {
+            let (x, y, r) = (next() * 1000.0, next() * 1000.0, 0.5 + next() * 1.5);
+            if (y - 500.0).abs() < 12.0 {
+                continue;
+            }
+            out.push(Obstacle {
+                id: out.len() as i64,
+                name: format!("o{}", out.len()),
+                center: Point::new(x, y),
+                radius: r,
+                radius_y: None,
+            });
+        }
+        for (i, (x, y)) in [
+            (150.0, 500.5),
+            (420.0, 499.0),
+            (700.0, 501.0),
+            (702.0, 495.0),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            out.push(Obstacle {
+                id: 100_000 + i as i64,
+                name: format!("blocker{}", i),
+                center: Point::new(x, y),
+                radius: 2.5,
+                radius_y: None,
+            });
+        }
+        out
+    }
+
+    #[test]
+    fn indexed_obstacle_lookup_matches_brute_force() {
+        let obstacles = dense_field(8000, 0x5eed);
+        let opts = RouteOptions::default();
+        let pairs = [
+            ((0.0, 500.0), (1000.0, 500.0)),
+            ((1000.0, 498.0), (0.0, 502.0)),
+            ((100.0, 503.0), (800.0, 497.0)),
+        ];
+
+        let mut detours = 0;
+        for ((sx, sy), (ex, ey)) in pairs {
+            let (start, end) = (Point::new(sx, sy), Point::new(ex, ey));
+
+            let indexed =
+                compute_route_inner(start, end, &ObstacleIndex::new(&obstacles), opts, None)
+                    .expect("indexed route");
+            let brute = compute_route_inner(
+                start,
+                end,
+                &ObstacleIndex::brute_force(&obstacles),
+                opts,
+                None,
+            )
+            .expect("brute-force route");
+
+            assert_eq!(indexed.waypoints, brute.waypoints);
+            assert_eq!(indexed.length, brute.length);
+            assert_eq!(indexed.iterations, brute.iterations);
+            assert_eq!(indexed.detours.len(), brute.detours.len());
+            for (a, b) in indexed.detours.iter().zip(&brute.detours) {
+                assert_eq!(a.obstacle_id, b.obstacle_id);
+                assert_eq!(a.score.total(), b.score.total());
+                assert_eq!(a.tries_used, b.tries_used);
+            }
+            detours += indexed.detours.len();
+        }
+
+        assert!(detours >= 3, "fixture should force detours");
+    }
+
+    #[test]
+    fn merge_close_waypoints_keeps_route_collision_free() {
+        let obstacle = Obstacle {
+            id: 1,
+            name: "o1".to_string(),
+            center: Point::new(5.0, 0.0),
+            radius: 1.0,
+            radius_y: None,
+        };
+
+        // Clustered pair above the obstacle: one of them can go.
+        let mut route = route_through(&[(0.0, 0.0), (5.0, 2.0), (5.2, 2.0), (10.0, 0.0)]);
+        let merged = merge_close_waypoints(
+            &mut route,
+            0.5,
+            std::slice::from_ref(&obstacle),
+            Metric::Euclidean,
+        );
+        assert_eq!(merged, 1);
+        assert_eq!(route.waypoints.len(), 3);
+        for w in route.waypoints.windows(2) {
+            assert!(
+                first_collision_on_segment(w[0], w[1], std::slice::from_ref(&obstacle)).is_none()
+            );
+        }
+
+        // Dropping the only detour point would cut through the obstacle.
+        let mut route = route_through(&[(0.0, 0.0), (5.0, 1.5), (10.0, 0.0)]);
+        assert_eq!(
+            merge_close_waypoints(&mut route, 100.0, &[obstacle], Metric::Euclidean),
+            0
+        );
+        assert_eq!(route.waypoints.len(), 3);
+    }
+}
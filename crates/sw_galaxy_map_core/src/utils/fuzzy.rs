@@ -222,6 +222,7 @@ pub fn resolve_fuzzy_hits(
                     canon: r.get(8)?,
                     legends: r.get(9)?,
                     status: r.get::<_, Option<String>>(10)?,
+                    score: None,
                 })
             })
             .with_context(|| format!("Failed to resolve fuzzy hit FID {}", hit.fid))?;
@@ -1,3 +1,7 @@
+/// Builds the Wookieepedia (Fandom) article URL for a planet name.
+///
+/// Words are capitalized and joined with `_`, as in wiki page titles; any
+/// byte outside the unreserved URL set (e.g. `'`) is percent-encoded.
 pub fn fandom_planet_url(name: &str) -> String {
     let normalized = name
         .split_whitespace()
@@ -11,5 +15,49 @@ pub fn fandom_planet_url(name: &str) -> String {
         .collect::<Vec<_>>()
         .join("_");
 
-    format!("https://starwars.fandom.com/wiki/{}", normalized)
+    format!(
+        "https://starwars.fandom.com/wiki/{}",
+        percent_encode_title(&normalized)
+    )
+}
+
+fn percent_encode_title(title: &str) -> String {
+    let mut out = String::with_capacity(title.len());
+    for b in title.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fandom_planet_url;
+
+    #[test]
+    fn multi_word_names_are_joined_and_encoded() {
+        assert_eq!(
+            fandom_planet_url("Nar Shaddaa"),
+            "https://starwars.fandom.com/wiki/Nar_Shaddaa"
+        );
+        assert_eq!(
+            fandom_planet_url("  ord   mantell "),
+            "https://starwars.fandom.com/wiki/Ord_Mantell"
+        );
+        assert_eq!(
+            fandom_planet_url("Nar Shaddaa's Moon"),
+            "https://starwars.fandom.com/wiki/Nar_Shaddaa%27s_Moon"
+        );
+        assert_eq!(
+            fandom_planet_url("Ahch-To"),
+            "https://starwars.fandom.com/wiki/Ahch-To"
+        );
+        assert_eq!(
+            fandom_planet_url("Ésotéria"),
+            "https://starwars.fandom.com/wiki/%C3%89sot%C3%A9ria"
+        );
+    }
 }
@@ -59,9 +59,29 @@ pub fn validate_near(
          {TIP_NEGATIVE_COORDS}"
     )
 }
+/// Checks the optional inner radius against the search radius (`near --min-r`).
+pub fn validate_near_radius(range: f64, min_r: Option<f64>) -> Result<()> {
+    if let Some(m) = min_r {
+        if !m.is_finite() || m < 0.0 {
+            bail!("--min-r must be a finite number >= 0 (got {m})");
+        }
+        if m >= range {
+            bail!("--min-r ({m}) must be smaller than the search radius ({range})");
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_search(filter: &crate::model::SearchFilter) -> Result<()> {
     validate_limit(filter.limit, "search")?;
 
+    if filter.offset < 0 {
+        bail!(
+            "Invalid offset for search: {} (must be >= 0)",
+            filter.offset
+        );
+    }
+
     let has_query = filter
         .query
         .as_deref()
@@ -73,12 +93,13 @@ pub fn validate_search(filter: &crate::model::SearchFilter) -> Result<()> {
         || filter.sector.is_some()
         || filter.grid.is_some()
         || filter.status.is_some()
+        || filter.reference.is_some()
         || filter.canon == Some(true)
         || filter.legends == Some(true);
 
     if !has_query && !has_filter {
         bail!(
-            "search requires at least a query or one filter (--region, --sector, --grid, --status, --canon, --legends)"
+            "search requires at least a query or one filter (--region, --sector, --grid, --status, --by-ref, --canon, --legends)"
         );
     }
 
@@ -22,8 +22,14 @@ fn ensure_db_ready(db_path: &Path) -> Result<()> {
     println!("Local database not found at: {}", db_path.display());
     println!("Initializing it now...");
 
-    let report =
-        sw_galaxy_map_core::db::db_init::run(Some(db_path.to_string_lossy().to_string()), false)?;
+    let report = sw_galaxy_map_core::db::db_init::run(
+        Some(db_path.to_string_lossy().to_string()),
+        false,
+        None,
+        None,
+        &Default::default(),
+        &mut |_, _| {},
+    )?;
 
     println!();
     println!("Database initialized.");
@@ -3,9 +3,9 @@
 use anyhow::{Result, bail};
 use rusqlite::Connection;
 use sw_galaxy_map_core::db::queries::{
-    find_planet_by_alias_norm, find_planet_by_norm, get_planet_by_fid, search_planets,
+    find_planet_by_alias_norm, find_planet_by_norm, get_planet_by_fid, search_planets_filtered,
 };
-use sw_galaxy_map_core::model::{Planet, PlanetSearchRow};
+use sw_galaxy_map_core::model::{Planet, PlanetSearchRow, SearchFilter};
 use sw_galaxy_map_core::utils::normalize_text;
 
 /// Resolves a planet by exact FID.
@@ -34,8 +34,12 @@ pub fn resolve_by_name_or_alias(con: &Connection, raw: &str) -> Result<Option<Pl
 
 /// Searches planets by free-text query.
 pub fn search(con: &Connection, query: &str, limit: i64) -> Result<Vec<PlanetSearchRow>> {
-    let normalized = normalize_text(query);
-    search_planets(con, &normalized, limit)
+    let filter = SearchFilter {
+        query: Some(query.to_string()),
+        limit,
+        ..Default::default()
+    };
+    search_planets_filtered(con, &filter)
 }
 
 /// Resolves a single planet from a free-text query.